@@ -2,6 +2,7 @@
 
 require_relative "../lib/wreq"
 require "json"
+require "pathname"
 require "tmpdir"
 
 # Helpers
@@ -78,8 +79,24 @@ def example3_background_producer
   producer.join
 end
 
+def example4_direct_file_body
+  puts "\n=== Example 4: File body without a producer thread ==="
+  client = Wreq::Client.new
+
+  path = File.join(Dir.tmpdir, "wreq_upload_direct.bin")
+  File.binwrite(path, Random.new.bytes(2_500_00))
+
+  # The file is read on the runtime thread; Content-Length is set from its size.
+  resp = client.post(ENDPOINT, body: Pathname(path), headers: {"Content-Type" => "application/octet-stream"})
+  json = resp.json
+  puts "Echoed Content-Length: #{json.dig("headers", "Content-Length")}"
+ensure
+  File.delete(path) if path && File.exist?(path)
+end
+
 if __FILE__ == $0
   example1_simple_push
   example2_file_stream
   example3_background_producer
+  example4_direct_file_body
 end
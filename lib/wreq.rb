@@ -15,6 +15,11 @@ require_relative "wreq_ruby/body"
 require_relative "wreq_ruby/header"
 require_relative "wreq_ruby/error"
 require_relative "wreq_ruby/cookie"
+require_relative "wreq_ruby/websocket"
+require_relative "wreq_ruby/sse"
+require_relative "wreq_ruby/pending"
+require_relative "wreq_ruby/request"
+require_relative "wreq_ruby/proxy_pool"
 
 unless defined?(Wreq)
   module Wreq
@@ -30,23 +35,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -62,23 +77,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -94,24 +119,34 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
 
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -127,23 +162,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -159,23 +204,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -191,23 +246,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -223,23 +288,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -255,23 +330,33 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
@@ -287,27 +372,204 @@ unless defined?(Wreq)
     # @param query [Hash, nil] URL query parameters
     # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
     # @param json [Object, nil] JSON body (will be serialized)
-    # @param body [String, IO, nil] Raw request body (string or stream)
+    # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+    # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+    # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+    # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
     # @param auth [String, nil] Authorization header value
     # @param bearer_auth [String, nil] Bearer token for Authorization header
     # @param basic_auth [Array<String>, nil] Username and password for basic auth
     # @param cookies [Array<String>, nil] Cookies to send
     # @param allow_redirects [Boolean, nil] Whether to follow redirects
     # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+    # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
     # @param gzip [Boolean, nil] Enable gzip compression
     # @param brotli [Boolean, nil] Enable Brotli compression
     # @param deflate [Boolean, nil] Enable deflate compression
     # @param zstd [Boolean, nil] Enable Zstandard compression
-    # @param timeout [Integer, nil] Total request timeout (seconds)
-    # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-    # @param proxy [String, nil] Proxy server URI
+    # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+    # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+    # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+    # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+    # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
     # @param no_proxy [Boolean, nil] Disable proxy for this request
     # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
     # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+    # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
     # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
     # @param version [Wreq::Version, nil] HTTP version to use
     # @return [Wreq::Response] HTTP response
     def self.patch(url, **options)
     end
+
+    # Send an HTTP request in the background.
+    #
+    # Accepts the same arguments as `request`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.request_async(method, url, **options)
+    end
+
+    # Send an HTTP GET request in the background.
+    #
+    # Accepts the same arguments as `get`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    # @example
+    #   pending = Wreq.get_async("https://example.com")
+    #   response = pending.value
+    def self.get_async(url, **options)
+    end
+
+    # Send an HTTP POST request in the background.
+    #
+    # Accepts the same arguments as `post`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.post_async(url, **options)
+    end
+
+    # Send an HTTP PUT request in the background.
+    #
+    # Accepts the same arguments as `put`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.put_async(url, **options)
+    end
+
+    # Send an HTTP DELETE request in the background.
+    #
+    # Accepts the same arguments as `delete`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.delete_async(url, **options)
+    end
+
+    # Send an HTTP HEAD request in the background.
+    #
+    # Accepts the same arguments as `head`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.head_async(url, **options)
+    end
+
+    # Send an HTTP OPTIONS request in the background.
+    #
+    # Accepts the same arguments as `options`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.options_async(url, **options)
+    end
+
+    # Send an HTTP TRACE request in the background.
+    #
+    # Accepts the same arguments as `trace`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.trace_async(url, **options)
+    end
+
+    # Send an HTTP PATCH request in the background.
+    #
+    # Accepts the same arguments as `patch`.
+    #
+    # @return [Wreq::Pending] Handle to the running request
+    def self.patch_async(url, **options)
+    end
+
+    # Send an HTTP GET request and save the response body to a file.
+    #
+    # Accepts every option of `get`, plus the options of Response#save_to.
+    # A 4xx or 5xx response raises Wreq::StatusError before the file is
+    # created, unless `raise_on_status:` is given for the download.
+    #
+    # @param url [String] Target URL
+    # @param path [String, Pathname] Destination file path
+    # @param atomic [Boolean] Write to a temporary file and rename it into place
+    # @param digest [Symbol, String, nil] Digest algorithm (:sha256, :sha384 or :sha512)
+    # @yield [written, total] Progress callback
+    # @param raise_on_status [Boolean, Array<Integer>, nil] The statuses
+    #   that raise instead of being saved (default: any 4xx or 5xx); `false`
+    #   saves the body of any response
+    # @return [Wreq::DownloadResult] Path, byte count and digest
+    # @raise [Wreq::StatusError] If the status is one to raise on
+    # @example
+    #   result = Wreq.download("https://example.com/file.zip", "file.zip", atomic: true)
+    #   puts result.bytes
+    def self.download(url, path, **options, &block)
+    end
+
+    # Send many requests concurrently without a thread per request.
+    #
+    # Each request is a Hash with a `:url`, an optional `:method` (a Wreq::Method,
+    # GET by default) and any option accepted by `request`. Failed requests do not
+    # raise; their exception object takes the place of the response.
+    #
+    # @param requests [Array<Hash>] Request specifications
+    # @param concurrency [Integer, nil] Maximum number of requests in flight (default: all)
+    # @yield [result, index] Called as each request completes, in completion order
+    # @return [Array<Wreq::Response, Exception>] Results in input order
+    # @example
+    #   results = Wreq.parallel([
+    #     {url: "https://example.com/a"},
+    #     {method: Wreq::Method::POST, url: "https://example.com/b", json: {id: 1}}
+    #   ], concurrency: 8)
+    #   results.each { |r| puts r.is_a?(Exception) ? r.message : r.code }
+    def self.parallel(requests, concurrency: nil, &block)
+    end
+
+    # Open a WebSocket connection.
+    #
+    # @param url [String] Target URL (ws:// or wss://)
+    # @param headers [Hash{String=>String}, nil] Custom headers for the handshake
+    # @param orig_headers [Array<String>, nil] Original header names (case and order)
+    # @param default_headers [Boolean, nil] Whether to send the client's default headers
+    # @param protocols [Array<String>, nil] Subprotocols to request (Sec-WebSocket-Protocol)
+    # @param query [Hash, nil] URL query parameters
+    # @param auth [String, nil] Authorization header value
+    # @param bearer_auth [String, nil] Bearer token for Authorization header
+    # @param basic_auth [Array<String>, nil] Username and password for basic auth
+    # @param cookies [Hash{String=>String}, nil] Cookies to send
+    # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
+    # @param local_address [String, nil] Bind the local source IP address
+    # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`
+    # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+    # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
+    # @param emulation [Wreq::Emulation, nil] Device/OS emulation for the handshake
+    # @return [Wreq::WebSocket] Open WebSocket connection
+    # @example
+    #   ws = Wreq.websocket("wss://example.com/socket", protocols: ["chat"])
+    #   ws.send_text("hello")
+    def self.websocket(url, **options)
+    end
+
+    # Open a Server-Sent Events stream, yielding each event.
+    #
+    # Sends a GET request with `Accept: text/event-stream`, accepting every
+    # option of `get`. Waiting for the next event releases the GVL. Returns
+    # when the stream ends, or, with `reconnect:`, when the server answers
+    # with 204 No Content. Other 4xx/5xx statuses raise Wreq::StatusError.
+    #
+    # @param url [String] Target URL
+    # @param reconnect [Boolean, Integer] Reconnect when the stream ends or
+    #   the connection fails, sending the last event ID as `Last-Event-ID`.
+    #   An Integer limits the reconnects in a row without receiving an event.
+    #   Each reconnect sends the request again, so a streamed body (IO or
+    #   {Wreq::BodySender}) raises ArgumentError.
+    # @param reconnect_delay [Float, Integer] Seconds to wait before
+    #   reconnecting (default 3), until the server sets it with `retry:`
+    # @yield [event] Each event of the stream
+    # @yieldparam event [Wreq::ServerSentEvent]
+    # @return [nil, Enumerator] nil, or an Enumerator without a block
+    # @example
+    #   Wreq.sse("https://example.com/stream", reconnect: true) do |event|
+    #     break if event.event == "done"
+    #     puts event.data
+    #   end
+    def self.sse(url, **options, &block)
+    end
   end
 end
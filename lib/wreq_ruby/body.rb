@@ -32,5 +32,61 @@ unless defined?(Wreq)
       def close
       end
     end
+
+    # Part: a single field of a multipart/form-data request body.
+    #
+    # The value decides how the part is sent:
+    #   - String: sent as text (binary-encoded strings are sent as bytes)
+    #   - Pathname or File: read from disk on the runtime, without the GVL
+    #   - IO (anything responding to `readpartial` or `read`): streamed in chunks
+    #   - Wreq::BodySender: streamed from the sender's channel
+    #
+    # Usage:
+    #   avatar = Wreq::Part.new("avatar", Pathname("me.png"), mime: "image/png")
+    #   name = Wreq::Part.new("name", "wreq")
+    #   resp = client.post(url, multipart: Wreq::Multipart.new(name, avatar))
+    #
+    # Note:
+    #   - Each Part instance can only be used once (single-use):
+    #     after being added to a Multipart, it cannot be added again.
+    class Part
+      # @param name [String] form field name
+      # @param value [String, Pathname, IO, Wreq::BodySender] field value
+      # @param filename [String, nil] filename sent in Content-Disposition
+      #   (defaults to the file's basename for Pathname/File values)
+      # @param mime [String, nil] Content-Type of the part
+      #   (guessed from the file extension for Pathname/File values)
+      # @param length [Integer, nil] length of a streamed value, if known
+      # @param headers [Hash{String=>String}, nil] extra headers for the part
+      def self.new(name, value, filename: nil, mime: nil, length: nil, headers: nil)
+      end
+
+      # @return [String] form field name
+      def name
+      end
+    end
+
+    # Multipart: a multipart/form-data request body, built from Wreq::Part instances.
+    #
+    # Pass it to any request method with the `multipart:` keyword. The parts are
+    # streamed through the request body rather than buffered in Ruby.
+    #
+    # Usage:
+    #   form = Wreq::Multipart.new(Wreq::Part.new("field", "value"))
+    #   form.add(Wreq::Part.new("upload", File.open("report.csv"), mime: "text/csv"))
+    #   resp = client.post(url, multipart: form)
+    #
+    # Note:
+    #   - Each Multipart instance can only be used once (single-use).
+    class Multipart
+      # @param parts [Array<Wreq::Part>] initial parts
+      def self.new(*parts)
+      end
+
+      # Append a part to the form.
+      # @param part [Wreq::Part]
+      def add(part)
+      end
+    end
   end
 end
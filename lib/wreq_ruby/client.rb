@@ -51,6 +51,21 @@ unless defined?(Wreq)
       #   follow before returning an error. Only applies when allow_redirects
       #   is true. Default is typically 10 if not specified.
       #
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy for every
+      #   request; giving one implies `allow_redirects: true`. A callable
+      #   receives each {Wreq::RedirectHop} before it is followed and returns
+      #   whether to follow it (a falsy result returns the redirect response
+      #   as is). A Hash accepts `max:`, `same_origin:` (false),
+      #   `deny_downgrade:` (false, refuse https to http), `allowed_hosts:`
+      #   (any) and `strip_sensitive_headers:` (true, drop `Authorization`,
+      #   `Cookie` and `Proxy-Authorization` once a redirect leaves the
+      #   original host). Refused redirects raise {Wreq::RedirectError}, as
+      #   do redirects that must send a streamed body (IO or
+      #   {Wreq::BodySender}) again. Exceptions raised by a callback are
+      #   raised by the request as is. Callbacks cannot run in `*_async`
+      #   requests, which raise ArgumentError when one would apply. Can be
+      #   overridden per request.
+      #
       # @param cookie_store [Boolean, nil] Enable an in-memory cookie jar
       #   that automatically handles Set-Cookie headers and sends appropriate
       #   Cookie headers on subsequent requests.
@@ -59,29 +74,74 @@ unless defined?(Wreq)
       #   used to store and retrieve cookies for all requests made by this
       #   client. Typically used together with `cookie_store: true`.
       #
-      # @param timeout [Integer, nil] Overall timeout for the entire request
+      # @param retry [Boolean, Integer, Hash, nil] Default retry policy for
+      #   every request. `true` uses the defaults, an Integer sets the maximum
+      #   number of attempts, and a Hash accepts `attempts:` (3), `backoff:`
+      #   (0.1 seconds, doubled on every retry), `max_backoff:` (10 seconds),
+      #   `jitter:` (true), `statuses:` ([429, 502, 503, 504]), `errors:`
+      #   ([Wreq::ConnectionError, Wreq::ConnectionResetError, Wreq::TimeoutError]),
+      #   `methods:` (idempotent methods only) and `retry_after:` (true, wait as
+      #   long as a `Retry-After` header asks, up to `max_backoff:`). Requests
+      #   whose body is a stream are never retried. Can be overridden per request.
+      #
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise
+      #   Wreq::StatusError instead of returning the response: `true` for any
+      #   4xx or 5xx status, or an Array of the status codes to raise on.
+      #   Checked after the middleware `on_response` hooks and after retries.
+      #   Can be overridden per request, `false` turning it off.
+      #
+      # @param middleware [Array<#call, #on_response>, nil] Middleware run
+      #   around every request, in order. Each object may respond to
+      #   `call(request)`, run before the request is sent with a mutable
+      #   {Wreq::Request}, and/or `on_response(request, response)`, run in
+      #   reverse order once the response headers are received. Returning a
+      #   {Wreq::Response} from `on_response` replaces the response. Hooks run
+      #   on the calling thread; for `*_async` requests, `on_response` runs
+      #   when {Wreq::Pending#value} is called. See also {#use}.
+      #
+      # Durations are given in seconds as any Numeric, e.g. `0.25` or `1/4r`.
+      # Each timeout raises {Wreq::TimeoutError} with {Wreq::TimeoutError#phase}
+      # set to the phase that ran out of time.
+      #
+      # @param timeout [Numeric, nil] Overall timeout for the entire request
       #   in seconds, including connection establishment, request transmission,
       #   and response reading. If not set, requests may wait indefinitely.
+      #   Phase `:total`.
+      #
+      # @param connect_timeout [Numeric, nil] Maximum time in seconds to wait
+      #   when establishing a connection to the remote server, including the
+      #   proxy tunnel and the TLS handshake. This is separate from the overall
+      #   timeout. Phase `:connect`.
       #
-      # @param connect_timeout [Integer, nil] Maximum time in seconds to wait
-      #   when establishing a connection to the remote server. This is separate
-      #   from the overall timeout.
+      # @param pool_timeout [Numeric, nil] Maximum time in seconds each attempt
+      #   of a request waits for a connection from the pool, such as when
+      #   `pool_max_size` connections are in use. The wait ends once a new
+      #   connection is being opened for the request. Phase `:pool`.
       #
-      # @param read_timeout [Integer, nil] Maximum time in seconds to wait
+      # @param read_timeout [Numeric, nil] Maximum time in seconds to wait
       #   between reading chunks of data from the server. Applies to each
-      #   read operation, not the entire response.
+      #   read operation, not the entire response. Phase `:read`.
+      #
+      # @param dns_timeout [Numeric, nil] Maximum time in seconds to look up a
+      #   host name, with the system, DNS-over-HTTPS or cached resolver; Ruby
+      #   `dns_resolver:` callables are not interrupted. Phase `:dns`.
       #
-      # @param tcp_keepalive [Integer, nil] Time in seconds that a connection
+      # @param first_byte_timeout [Numeric, nil] Maximum time in seconds from
+      #   sending each attempt of a request to receiving its response headers,
+      #   including waiting for a pooled connection and connecting. The body is
+      #   read afterwards, under `timeout` and `read_timeout`. Phase `:first_byte`.
+      #
+      # @param tcp_keepalive [Numeric, nil] Time in seconds that a connection
       #   must be idle before TCP keepalive probes are sent. Helps detect
       #   broken connections.
       #
-      # @param tcp_keepalive_interval [Integer, nil] Time in seconds between
+      # @param tcp_keepalive_interval [Numeric, nil] Time in seconds between
       #   individual TCP keepalive probes. Only relevant if tcp_keepalive is set.
       #
       # @param tcp_keepalive_retries [Integer, nil] Number of failed keepalive
       #   probes before the connection is considered dead and closed.
       #
-      # @param tcp_user_timeout [Integer, nil] Maximum time in seconds that
+      # @param tcp_user_timeout [Numeric, nil] Maximum time in seconds that
       #   transmitted data may remain unacknowledged before the connection is
       #   forcibly closed. Linux-specific option (Android, Fuchsia, Linux only).
       #
@@ -94,7 +154,7 @@ unless defined?(Wreq)
       #   allowing the reuse of local addresses in TIME_WAIT state. Useful for
       #   reducing port exhaustion in high-throughput scenarios.
       #
-      # @param pool_idle_timeout [Integer, nil] Time in seconds before idle
+      # @param pool_idle_timeout [Numeric, nil] Time in seconds before idle
       #   connections in the pool are evicted and closed. Helps free up
       #   resources for long-running applications.
       #
@@ -121,17 +181,63 @@ unless defined?(Wreq)
       # @param verify [Boolean, nil] Enable or disable TLS certificate
       #   verification. When false, the client will accept any certificate,
       #   including self-signed or expired ones. Should only be disabled
-      #   for testing purposes.
+      #   for testing purposes. To trust a private CA instead, use `ca_cert:`.
+      #
+      # @param ca_cert [String, Pathname, Symbol, Array, nil] Root certificates
+      #   to trust instead of the default ones: a PEM string, a path to a PEM
+      #   file (which may hold several certificates), `:env` for the file named
+      #   by the `SSL_CERT_FILE` environment variable (ignored when unset), or
+      #   an Array of those.
+      #
+      # @param identity [String, Pathname, nil] Client certificate and key for
+      #   mutual TLS, as a PKCS#12 archive (path or DER string). Use
+      #   `identity_password:` if the archive is encrypted.
+      #
+      # @param identity_password [String, nil] Password of the `identity:` archive.
+      #
+      # @param client_cert [String, Pathname, nil] Client certificate chain for
+      #   mutual TLS, as PEM (path or string). Requires `client_key:`.
+      #
+      # @param client_key [String, Pathname, nil] PKCS#8 private key of
+      #   `client_cert:`, as PEM (path or string).
+      #
+      # @param min_tls_version [String, Symbol, nil] Minimum TLS version to
+      #   negotiate: "1.0", "1.1", "1.2" or "1.3" (also `:tls1_2`, "TLSv1.2").
+      #
+      # @param max_tls_version [String, Symbol, nil] Maximum TLS version to
+      #   negotiate, in the same format as `min_tls_version:`.
+      #
+      # @param tls_info [Boolean, nil] Record the TLS session details of every
+      #   response, available with {Wreq::Response#tls_info}.
+      #
+      # @param pin_sha256 [String, Array<String>, nil] Public key pins: SHA-256
+      #   hashes of a SubjectPublicKeyInfo, in base64 (optionally prefixed with
+      #   `sha256/`) or hex. Every TLS connection must present a leaf or chain
+      #   certificate matching one of them, in addition to the usual
+      #   verification; otherwise {Wreq::TlsError} is raised, naming the
+      #   expected and actual hashes. The check runs on the certificates each
+      #   HTTPS response was received with, before the response is returned,
+      #   for requests, redirects and WebSockets alike; by then the request
+      #   has been sent on the connection.
       #
       # @param no_proxy [Boolean, nil] Disable use of any configured proxy
       #   for this client, even if proxy settings are detected from the
       #   environment.
       #
-      # @param proxy [String, nil] Proxy server URI to use for all requests.
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI to use for all requests.
       #   Supports HTTP, HTTPS, and SOCKS5 proxies. Format: "protocol://host:port"
       #   Example: "http://proxy.example.com:8080"
+      #   A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String).
+      #   Example: `{http: "http://proxy:3128", https: "http://proxy:3129", username: "user",
+      #   password: "secret", headers: {"X-Session-Id" => "42"}, no_proxy: ["localhost", "10.0.0.0/8"]}`
+      #   A `Wreq::ProxyPool` chooses the proxy of each request, rotating between its proxies.
       # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable.
       # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`.
+      # @param happy_eyeballs_delay [Numeric, false, nil] Seconds to wait for a connection over the first address family before also trying the other one (Happy Eyeballs, RFC 8305; default 0.3). `false` disables the fallback, so addresses are tried one after another. A client setting, as it configures the connector.
+      # @param dns_resolver [Symbol, #call, Hash, nil] How host names without `resolve:` entries are looked up. `:system` (default) uses the system resolver. An object responding to `call(host)` returns an IP address String, an Array of them, or nil to fall back to the system resolver; it runs on the thread sending the request, so `*_async` and `parallel` requests on the client raise ArgumentError, and exceptions it raises are raised by the request as is. A Hash takes `call:` (such an object) or `doh:` (a DNS-over-HTTPS endpoint URL serving the JSON API, e.g. "https://cloudflare-dns.com/dns-query"), and `cache:` to cache lookups: `true` or `{ttl:, negative_ttl:, max_entries:}` (TTLs in seconds; by default answers are cached for their DNS TTL or 60 seconds, failures are not cached, and up to 1024 hosts are kept).
       #
       # @param gzip [Boolean, nil] Accept and automatically decompress gzip
       #   content encoding. When true, adds "Accept-Encoding: gzip" header.
@@ -168,9 +274,11 @@ unless defined?(Wreq)
       #
       # @example Client with timeouts
       #   client = Wreq::Client.new(
-      #     timeout: 30,           # 30 seconds total
-      #     connect_timeout: 5,    # 5 seconds to connect
-      #     read_timeout: 10       # 10 seconds between reads
+      #     timeout: 30,             # 30 seconds total
+      #     connect_timeout: 5,      # 5 seconds to connect
+      #     read_timeout: 10,        # 10 seconds between reads
+      #     dns_timeout: 0.5,        # 500 ms to look up host names
+      #     first_byte_timeout: 2.5  # 2.5 seconds to the response headers
       #   )
       #
       # @example Client with redirect handling
@@ -204,6 +312,14 @@ unless defined?(Wreq)
       #     verify: true
       #   )
       #
+      # @example Private CA and mutual TLS
+      #   client = Wreq::Client.new(
+      #     ca_cert: "/etc/ssl/internal-ca.pem",
+      #     client_cert: "client.pem",
+      #     client_key: "client.key",
+      #     min_tls_version: "1.2"
+      #   )
+      #
       # @example HTTP/2 optimized client
       #   client = Wreq::Client.new(
       #     http2_only: true,
@@ -242,22 +358,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the client's local source IP address (IPv4/IPv6). Useful on multi-homed hosts to originate connections from a specific address or enforce source routing. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable or the connection may fail.
       # @param interface [String, nil] Bind the socket to a specific network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Effective only on systems that support the option (Linux/Android/Fuchsia) and typically requires privileges (root or CAP_NET_ADMIN).
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -273,22 +399,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate GET requests from a specific address. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1". The address must exist on the host and be routable.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0"). Supported on Linux/Android/Fuchsia; typically requires privileges.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -304,22 +440,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate HEAD requests from a specific address. Examples: "192.168.1.10", "10.0.0.5", "2001:db8::1".
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE` (e.g., "eth0", "wlan0", "tun0").
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -335,22 +481,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate POST requests from a specific address.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -366,22 +522,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate PUT requests from a specific address.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -397,22 +563,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate DELETE requests from a specific address.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -428,22 +604,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate OPTIONS requests from a specific address.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -459,22 +645,32 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate OPTIONS requests from a specific address.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
@@ -490,27 +686,228 @@ unless defined?(Wreq)
       # @param query [Hash, nil] URL query parameters
       # @param form [Hash{String=>String}, nil] Form data (application/x-www-form-urlencoded)
       # @param json [Object, nil] JSON body (will be serialized)
-      # @param body [String, IO, nil] Raw request body (string or stream)
+      # @param retry [Boolean, Integer, Hash, nil] Retry policy, overriding the client's (see Client.new); false disables retries
+      # @param raise_on_status [Boolean, Array<Integer>, nil] Raise Wreq::StatusError for a 4xx/5xx status (true) or for the given status codes, overriding the client's (see Client.new); false disables it
+      # @param multipart [Wreq::Multipart, nil] Multipart form data (multipart/form-data)
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] Raw request body. Files and Pathnames are read on the runtime without the GVL, an open File from its current position; other IO objects are streamed with `readpartial`/`read`. Content-Length is set when the size is known.
       # @param auth [String, nil] Authorization header value
       # @param bearer_auth [String, nil] Bearer token for Authorization header
       # @param basic_auth [Array<String>, nil] Username and password for basic auth
       # @param cookies [Array<String>, nil] Cookies to send
       # @param allow_redirects [Boolean, nil] Whether to follow redirects
       # @param max_redirects [Integer, nil] Maximum number of redirects to follow
+      # @param redirect [Hash, Proc, #call, nil] Redirect policy, overriding the client's (see Client.new); following redirects is implied
       # @param gzip [Boolean, nil] Enable gzip compression
       # @param brotli [Boolean, nil] Enable Brotli compression
       # @param deflate [Boolean, nil] Enable deflate compression
       # @param zstd [Boolean, nil] Enable Zstandard compression
-      # @param timeout [Integer, nil] Total request timeout (seconds)
-      # @param read_timeout [Integer, nil] Per-chunk read timeout (seconds)
-      # @param proxy [String, nil] Proxy server URI
+      # @param timeout [Numeric, nil] Total request timeout (seconds, e.g. 0.5)
+      # @param read_timeout [Numeric, nil] Per-chunk read timeout (seconds)
+      # @param dns_timeout [Numeric, nil] Host name lookup timeout (seconds); raises Wreq::TimeoutError with phase :dns
+      # @param first_byte_timeout [Numeric, nil] Time to the response headers of each attempt (seconds); raises Wreq::TimeoutError with phase :first_byte
+      # @param pool_timeout [Numeric, nil] Time each attempt waits for a pooled connection (seconds); raises Wreq::TimeoutError with phase :pool
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
       # @param local_address [String, nil] Bind the request's local source IP address (IPv4/IPv6). Useful to originate OPTIONS requests from a specific address.
       # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`.
+      # @param unix_socket [String, nil] Path of a Unix domain socket to connect to instead of TCP (e.g. "/var/run/docker.sock"). The URL still supplies the path and `Host` header, e.g. `http://docker/v1.43/info`; HTTP/1.1 and h2c (with `http2_only: true`) are supported, and `https://` URLs are tunneled with `CONNECT` through the socket. Takes the place of any `proxy:`. Unix only.
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
       # @param emulation [Wreq::Emulation, nil] Device/OS emulation for this request
       # @param version [Wreq::Version, nil] HTTP version to use
       # @return [Wreq::Response] HTTP response
       def patch(url, **options)
       end
+
+      # Send an HTTP request in the background.
+      #
+      # Accepts the same arguments as `request`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def request_async(method, url, **options)
+      end
+
+      # Send an HTTP GET request in the background.
+      #
+      # Accepts the same arguments as `get`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      # @example
+      #   pending = client.get_async("https://example.com")
+      #   response = pending.value
+      def get_async(url, **options)
+      end
+
+      # Send an HTTP POST request in the background.
+      #
+      # Accepts the same arguments as `post`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def post_async(url, **options)
+      end
+
+      # Send an HTTP PUT request in the background.
+      #
+      # Accepts the same arguments as `put`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def put_async(url, **options)
+      end
+
+      # Send an HTTP DELETE request in the background.
+      #
+      # Accepts the same arguments as `delete`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def delete_async(url, **options)
+      end
+
+      # Send an HTTP HEAD request in the background.
+      #
+      # Accepts the same arguments as `head`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def head_async(url, **options)
+      end
+
+      # Send an HTTP OPTIONS request in the background.
+      #
+      # Accepts the same arguments as `options`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def options_async(url, **options)
+      end
+
+      # Send an HTTP TRACE request in the background.
+      #
+      # Accepts the same arguments as `trace`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def trace_async(url, **options)
+      end
+
+      # Send an HTTP PATCH request in the background.
+      #
+      # Accepts the same arguments as `patch`.
+      #
+      # @return [Wreq::Pending] Handle to the running request
+      def patch_async(url, **options)
+      end
+
+      # Send an HTTP GET request and save the response body to a file.
+      #
+      # Accepts every option of `get`, plus the options of Response#save_to.
+      # A 4xx or 5xx response raises Wreq::StatusError before the file is
+      # created, unless `raise_on_status:` is given for the download.
+      #
+      # @param url [String] Target URL
+      # @param path [String, Pathname] Destination file path
+      # @param atomic [Boolean] Write to a temporary file and rename it into place
+      # @param digest [Symbol, String, nil] Digest algorithm (:sha256, :sha384 or :sha512)
+      # @yield [written, total] Progress callback
+      # @param raise_on_status [Boolean, Array<Integer>, nil] The statuses
+      #   that raise instead of being saved (default: any 4xx or 5xx); `false`
+      #   saves the body of any response
+      # @return [Wreq::DownloadResult] Path, byte count and digest
+      # @raise [Wreq::StatusError] If the status is one to raise on
+      # @example
+      #   result = client.download("https://example.com/file.zip", "file.zip", atomic: true)
+      #   puts result.bytes
+      def download(url, path, **options, &block)
+      end
+
+      # Send many requests concurrently without a thread per request.
+      #
+      # Each request is a Hash with a `:url`, an optional `:method` (a Wreq::Method,
+      # GET by default) and any option accepted by `request`. Failed requests do not
+      # raise; their exception object takes the place of the response.
+      #
+      # @param requests [Array<Hash>] Request specifications
+      # @param concurrency [Integer, nil] Maximum number of requests in flight (default: all)
+      # @yield [result, index] Called as each request completes, in completion order
+      # @return [Array<Wreq::Response, Exception>] Results in input order
+      # @example
+      #   results = client.parallel([
+      #     {url: "https://example.com/a"},
+      #     {method: Wreq::Method::POST, url: "https://example.com/b", json: {id: 1}}
+      #   ], concurrency: 8)
+      #   results.each { |r| puts r.is_a?(Exception) ? r.message : r.code }
+      def parallel(requests, concurrency: nil, &block)
+      end
+
+      # Append a middleware to the client's stack (see the `middleware:`
+      # option of {Client.new}). It applies to requests started afterwards.
+      #
+      # @param middleware [#call, #on_response] Middleware object
+      # @return [Wreq::Client] The client, for chaining
+      # @raise [ArgumentError] If the object responds to neither hook
+      # @example Sign requests and log responses
+      #   client.use(->(request) { request.headers.set("X-Signature", sign(request.body)) })
+      #   client.use(ResponseLogger.new(logger))
+      def use(middleware)
+      end
+
+      # Get the counters of the DNS cache set with the `cache:` setting of the
+      # `dns_resolver:` option.
+      #
+      # @return [Hash{Symbol => Integer}, nil] `:hits`, `:misses` and live
+      #   `:entries`, or nil if lookups are not cached
+      # @example
+      #   client = Wreq::Client.new(dns_resolver: {cache: {ttl: 30}})
+      #   client.get("https://example.com")
+      #   client.dns_cache_stats # => {hits: 0, misses: 1, entries: 1}
+      def dns_cache_stats
+      end
+
+      # Open a WebSocket connection.
+      #
+      # @param url [String] Target URL (ws:// or wss://)
+      # @param headers [Hash{String=>String}, nil] Custom headers for the handshake
+      # @param orig_headers [Array<String>, nil] Original header names (case and order)
+      # @param default_headers [Boolean, nil] Whether to send the client's default headers
+      # @param protocols [Array<String>, nil] Subprotocols to request (Sec-WebSocket-Protocol)
+      # @param query [Hash, nil] URL query parameters
+      # @param auth [String, nil] Authorization header value
+      # @param bearer_auth [String, nil] Bearer token for Authorization header
+      # @param basic_auth [Array<String>, nil] Username and password for basic auth
+      # @param cookies [Hash{String=>String}, nil] Cookies to send
+      # @param proxy [String, Hash, Wreq::ProxyPool, nil] Proxy server URI. A Hash configures the proxy in detail: `url:` (all schemes), `http:` and `https:` (per scheme, taking precedence over `url:`), `username:` and `password:` (basic auth, instead of credentials in the URL), `headers:` (extra headers sent to the proxy, including on the CONNECT request of HTTPS requests) and `no_proxy:` (hosts, domains and CIDRs to connect to directly, as an Array or comma-separated String). A `Wreq::ProxyPool` chooses the proxy of each request.
+      # @param local_address [String, nil] Bind the local source IP address
+      # @param interface [String, nil] Bind the socket to a network interface via `SO_BINDTODEVICE`
+      # @param resolve [Hash{String => String, Array<String>}, nil] Static addresses for host names, like `curl --resolve`. Keys are "host" or "host:port" (to only apply to that port), values are one IP address or an Array of them. Only the DNS lookup is replaced; the URL, `Host` header and TLS server name (SNI) are kept. Example: `{"example.com" => ["10.0.0.5", "::1"], "api.example.com:8443" => "127.0.0.1"}`.
+      # @param ip_family [Symbol, String, nil] The address family to connect with: `:v4` (IPv4 only), `:v6` (IPv6 only) or `:any` (default). Applies to resolved host names, including `resolve:` entries; the family used is reported by `Response#ip_family`. A request with its own `resolve:` or `ip_family:` opens connections of its own, only reused by requests with the same settings.
+      # @param emulation [Wreq::Emulation, nil] Device/OS emulation for the handshake
+      # @return [Wreq::WebSocket] Open WebSocket connection
+      # @example
+      #   ws = client.websocket("wss://example.com/socket", protocols: ["chat"])
+      #   ws.send_text("hello")
+      def websocket(url, **options)
+      end
+
+      # Open a Server-Sent Events stream, yielding each event.
+      #
+      # Sends a GET request with `Accept: text/event-stream`, accepting every
+      # option of `get`. Waiting for the next event releases the GVL. Returns
+      # when the stream ends, or, with `reconnect:`, when the server answers
+      # with 204 No Content. Other 4xx/5xx statuses raise Wreq::StatusError.
+      #
+      # @param url [String] Target URL
+      # @param reconnect [Boolean, Integer] Reconnect when the stream ends or
+      #   the connection fails, sending the last event ID as `Last-Event-ID`.
+      #   An Integer limits the reconnects in a row without receiving an event.
+      #   Each reconnect sends the request again, so a streamed body (IO or
+      #   {Wreq::BodySender}) raises ArgumentError.
+      # @param reconnect_delay [Float, Integer] Seconds to wait before
+      #   reconnecting (default 3), until the server sets it with `retry:`
+      # @yield [event] Each event of the stream
+      # @yieldparam event [Wreq::ServerSentEvent]
+      # @return [nil, Enumerator] nil, or an Enumerator without a block
+      # @example
+      #   client.sse("https://example.com/stream", reconnect: true) do |event|
+      #     break if event.event == "done"
+      #     puts event.data
+      #   end
+      def sse(url, **options, &block)
+      end
     end
   end
 end
@@ -2,10 +2,67 @@
 
 unless defined?(Wreq)
   module Wreq
+    # Base class of the errors raised by Wreq, a RuntimeError.
+    #
+    # Besides the message, every error carries what is known about the
+    # failure; attributes that do not apply are nil.
+    #
+    # @example
+    #   begin
+    #     client.get("http://localhost:9999")
+    #   rescue Wreq::Error => e
+    #     e.url               # => "http://localhost:9999/"
+    #     e.io_error_kind     # => :ECONNREFUSED
+    #     e.cause_chain.last  # => "Connection refused (os error 111)"
+    #     retry if e.retryable?
+    #   end
+    class Error < RuntimeError
+      # @return [String, nil] The URL of the request that failed, if known
+      def url
+      end
+
+      # @return [Wreq::StatusCode, nil] The status of the response that
+      #   caused the error, for status errors
+      def status
+      end
+
+      # The messages of the underlying errors, outermost first, down to the
+      # operating system error if any.
+      #
+      # @return [Array<String>]
+      def cause_chain
+      end
+
+      # The name of the operating system error behind the failure, as in
+      # Ruby's `Errno` module, such as `:ECONNREFUSED`, `:ECONNRESET` or
+      # `:EHOSTUNREACH`.
+      #
+      # @return [Symbol, nil]
+      def io_error_kind
+      end
+
+      # Whether the default retry policy (see the `retry:` option of
+      # Client.new) would retry the request: connection failures, resets and
+      # timeouts, and the statuses 429, 502, 503 and 504.
+      #
+      # @return [Boolean]
+      def retryable?
+      end
+
+      # The phase of the request that ran out of time, for timeouts:
+      # `:dns`, `:connect`, `:pool`, `:first_byte`, `:read` or `:total`,
+      # after the `dns_timeout`, `connect_timeout`, `pool_timeout`,
+      # `first_byte_timeout`, `read_timeout` and `timeout` options. Nil for other timeouts, such as waiting on a WebSocket.
+      #
+      # @return [Symbol, nil]
+      def phase
+      end
+    end
+
     # System-level and runtime errors
 
     # Memory allocation failed.
-    class MemoryError < StandardError; end
+    class MemoryError < Error; end
 
     # Network connection errors
 
@@ -20,7 +77,7 @@ unless defined?(Wreq)
     #     puts "Connection failed: #{e.message}"
     #     retry_with_backoff
     #   end
-    class ConnectionError < StandardError; end
+    class ConnectionError < Error; end
 
     # Proxy Connection to the server failed.
     #
@@ -32,7 +89,7 @@ unless defined?(Wreq)
     #     puts "Proxy connection failed: #{e.message}"
     #     retry_with_different_proxy
     #   end
-    class ProxyConnectionError < StandardError; end
+    class ProxyConnectionError < Error; end
 
     # Connection was reset by the server.
     #
@@ -42,7 +99,7 @@ unless defined?(Wreq)
     #   rescue Wreq::ConnectionResetError => e
     #     puts "Connection reset: #{e.message}"
     #   end
-    class ConnectionResetError < StandardError; end
+    class ConnectionResetError < Error; end
 
     # TLS/SSL error occurred.
     #
@@ -55,7 +112,7 @@ unless defined?(Wreq)
     #   rescue Wreq::TlsError => e
     #     puts "TLS error: #{e.message}"
     #   end
-    class TlsError < StandardError; end
+    class TlsError < Error; end
 
     # HTTP protocol and request/response errors
 
@@ -67,20 +124,36 @@ unless defined?(Wreq)
     #   rescue Wreq::RequestError => e
     #     puts "Request failed: #{e.message}"
     #   end
-    class RequestError < StandardError; end
+    class RequestError < Error; end
 
     # HTTP status code indicates an error.
     #
-    # Raised when the server returns an error status code (4xx or 5xx).
+    # Raised by Response#raise_for_status for a 4xx or 5xx status, and for
+    # the statuses chosen with the `raise_on_status:` option of a client or
+    # a request.
     #
     # @example
     #   begin
-    #     response = client.get("https://httpbin.io/status/404")
+    #     response = client.get("https://httpbin.io/status/404", raise_on_status: true)
     #   rescue Wreq::StatusError => e
     #     puts "HTTP error: #{e.message}"
-    #     # e.response contains the full response
+    #     puts e.status.as_int  # => 404
+    #     puts e.body           # the start of the response body
     #   end
-    class StatusError < StandardError; end
+    #
+    # Its `status` and `url` are those of the response, after any redirects.
+    class StatusError < Error
+      # @return [Wreq::Headers, nil] The headers of the response
+      def headers
+      end
+
+      # The start of the response body, at most 1 KiB, decoded as UTF-8 with
+      # invalid bytes replaced. The body stays readable from the response.
+      #
+      # @return [String, nil] The body preview, or nil if it could not be read
+      def body
+      end
+    end
 
     # Redirect handling failed.
     #
@@ -93,7 +166,7 @@ unless defined?(Wreq)
     #   rescue Wreq::RedirectError => e
     #     puts "Too many redirects: #{e.message}"
     #   end
-    class RedirectError < StandardError; end
+    class RedirectError < Error; end
 
     # Request timed out.
     #
@@ -107,7 +180,9 @@ unless defined?(Wreq)
     #     puts "Request timed out: #{e.message}"
     #     retry_with_longer_timeout
     #   end
-    class TimeoutError < StandardError; end
+    #
+    # Its `phase` tells which timeout expired.
+    class TimeoutError < Error; end
 
     # Data processing and encoding errors
 
@@ -119,7 +194,7 @@ unless defined?(Wreq)
     #   rescue Wreq::BodyError => e
     #     puts "Body error: #{e.message}"
     #   end
-    class BodyError < StandardError; end
+    class BodyError < Error; end
 
     # Decoding response failed.
     #
@@ -135,7 +210,7 @@ unless defined?(Wreq)
     #     # Fall back to binary data
     #     data = response.body
     #   end
-    class DecodingError < StandardError; end
+    class DecodingError < Error; end
 
     # Configuration and builder errors
 
@@ -152,6 +227,6 @@ unless defined?(Wreq)
     #   rescue Wreq::BuilderError => e
     #     puts "Invalid configuration: #{e.message}"
     #   end
-    class BuilderError < StandardError; end
+    class BuilderError < Error; end
   end
 end
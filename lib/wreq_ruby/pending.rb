@@ -0,0 +1,67 @@
+# frozen_string_literal: true
+
+unless defined?(Wreq)
+  module Wreq
+    # A request running in the background, returned by the `*_async` methods
+    # such as Client#get_async or Wreq.get_async.
+    #
+    # The request is started immediately on the shared runtime, without a Ruby
+    # thread per request. Waiting releases the GVL and can be interrupted with
+    # Thread#kill / Thread#raise.
+    #
+    # @example Fire and collect
+    #   pending = urls.map { |url| client.get_async(url) }
+    #   Wreq::Pending.wait_all(pending, 10)
+    #   responses = pending.map(&:value)
+    #
+    # @example First one wins
+    #   pending = mirrors.map { |url| client.get_async(url) }
+    #   winner = Wreq::Pending.wait_any(pending)
+    #   (pending - [winner]).each(&:cancel)
+    #   puts winner.value.text
+    class Pending
+      # Wait until any of the given requests has finished.
+      #
+      # @param pending [Array<Wreq::Pending>] Requests to wait for
+      # @param timeout [Float, nil] Maximum number of seconds to wait
+      # @return [Wreq::Pending, nil] The first finished request, or nil on timeout
+      def self.wait_any(pending, timeout = nil)
+      end
+
+      # Wait until all of the given requests have finished.
+      #
+      # @param pending [Array<Wreq::Pending>] Requests to wait for
+      # @param timeout [Float, nil] Maximum number of seconds to wait
+      # @return [Boolean] false if the timeout expired first
+      def self.wait_all(pending, timeout = nil)
+      end
+
+      # @return [Boolean] Whether the request has finished or was cancelled
+      def ready?
+      end
+
+      # Wait for the request to finish.
+      #
+      # @param timeout [Float, nil] Maximum number of seconds to wait
+      # @return [Boolean] false if the timeout expired first
+      def wait(timeout = nil)
+      end
+
+      # Wait for the request and return its response.
+      #
+      # The response can only be taken once.
+      #
+      # @return [Wreq::Response] HTTP response
+      # @raise [Wreq::RequestError] if the request was cancelled
+      # @raise [Wreq::TimeoutError, Wreq::ConnectionError, ...] if the request failed
+      def value
+      end
+
+      # Cancel the request if it is still in flight.
+      #
+      # @return [Boolean] true if the request was cancelled, false if it had already finished
+      def cancel
+      end
+    end
+  end
+end
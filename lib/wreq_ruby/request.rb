@@ -0,0 +1,62 @@
+# frozen_string_literal: true
+
+unless defined?(Wreq)
+  module Wreq
+    # A request about to be sent, as passed to client middleware.
+    #
+    # Middleware `call(request)` hooks may change the URL, headers and body
+    # before the request is sent. The same object is then passed to
+    # `on_response(request, response)` hooks.
+    #
+    # @example Inject a trace header
+    #   class Tracing
+    #     def call(request)
+    #       request.headers.set("X-Trace-Id", SecureRandom.hex(8))
+    #     end
+    #
+    #     def on_response(request, response)
+    #       puts "#{request.method} #{request.url} -> #{response.code}"
+    #     end
+    #   end
+    #
+    #   client = Wreq::Client.new(middleware: [Tracing.new])
+    class Request
+      # @return [Wreq::Method] HTTP method
+      def method
+      end
+
+      # @return [String] Request URL
+      def url
+      end
+
+      # Set the request URL.
+      #
+      # @param url [String] New URL
+      def url=(url)
+      end
+
+      # Get the request headers, which can be changed in place.
+      #
+      # Only the headers given for this request are included, not the
+      # client's default headers.
+      #
+      # @return [Wreq::Headers] Request headers
+      def headers
+      end
+
+      # Get the request body, if it was given as a String.
+      #
+      # @return [String, nil] Request body, or nil for no body, files,
+      #   streams and `form:`/`json:`/`multipart:` bodies
+      def body
+      end
+
+      # Replace the request body, dropping any `form:`, `json:` or
+      # `multipart:` body.
+      #
+      # @param body [String, Pathname, File, IO, Wreq::BodySender, nil] New body
+      def body=(body)
+      end
+    end
+  end
+end
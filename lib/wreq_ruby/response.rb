@@ -77,9 +77,99 @@ unless defined?(Wreq)
       # @return [String, nil] Remote address (e.g., "93.184.216.34:443"), or nil
       # @example
       #   response.remote_addr  # => "93.184.216.34:443"
+      #   response.remote_addr  # => "[2606:2800:220:1::1]:443" (over IPv6)
       def remote_addr
       end
 
+      # Get the address family the connection was made with.
+      #
+      # @return [Symbol, nil] `:v4` or `:v6`, or nil if the remote address is unknown
+      # @example
+      #   Wreq.get("https://example.com", ip_family: :v6).ip_family  # => :v6
+      def ip_family
+      end
+
+      # Get the details of the TLS session the response was received on.
+      #
+      # Only recorded when the client was created with `tls_info: true`. The
+      # negotiated TLS version, cipher suite and session resumption are not
+      # reported by the TLS backend.
+      #
+      # @return [Wreq::TlsInfo, nil] TLS details, or nil for plain HTTP or
+      #   when not recorded
+      # @example
+      #   client = Wreq::Client.new(tls_info: true)
+      #   info = client.get("https://example.com").tls_info
+      #   info.alpn                         # => "h2"
+      #   info.peer_certificate.subject     # => "CN=example.com"
+      #   info.peer_certificate.not_after   # => 2026-01-15 23:59:59 UTC
+      def tls_info
+      end
+
+      # Get the number of attempts it took to get this response.
+      #
+      # Greater than 1 when the request was retried (see the `retry:` option).
+      #
+      # @return [Integer] Number of attempts, including the first one
+      # @example
+      #   response = client.get(url, retry: 5)
+      #   puts response.attempts  # => 2
+      def attempts
+      end
+
+      # Get the redirects followed to get this response, oldest first.
+      #
+      # Empty when the request was not redirected (see the `redirect:` and
+      # `allow_redirects:` options).
+      #
+      # @return [Array<Wreq::RedirectHop>] Intermediate redirect responses
+      # @example
+      #   response = client.get("https://httpbin.io/redirect/2", allow_redirects: true)
+      #   response.history.map { |hop| hop.status.as_int }  # => [302, 302]
+      def history
+      end
+
+      # Get the request sent for this response.
+      #
+      # Describes the last request sent, after following redirects, with the
+      # headers recorded as the client sent it: the client's default headers,
+      # the emulation's, cookies and `Accept-Encoding` included.
+      #
+      # @return [Wreq::RequestInfo, nil] The frozen request details
+      # @example
+      #   response = client.get(url, orig_headers: ["User-Agent", "Accept"])
+      #   response.request.headers.first  # => ["User-Agent", "..."]
+      def request
+      end
+
+      # Get the durations of the phases of the request.
+      #
+      # Covers the last attempt of the last request, after retries and
+      # redirects. The body phases are only known once the body is read.
+      #
+      # @return [Wreq::Timings, nil] Timings, as measured so far
+      # @example
+      #   response = client.get(url)
+      #   response.text
+      #   response.timings.to_h
+      #   # => {dns: 0.002, connect: 0.041, request_written: 0.000,
+      #   #     server: 0.120, first_byte: 0.163, body: 0.004, total: 0.167,
+      #   #     reused: false}
+      def timings
+      end
+
+      # Raise a Wreq::StatusError if the status is 4xx or 5xx.
+      #
+      # The error carries the status, headers, URL and the start of the body;
+      # the body can still be read from the response afterwards.
+      #
+      # @return [Wreq::Response] The response itself, for chaining
+      # @raise [Wreq::StatusError] If the status is a client or server error
+      # @example
+      #   data = client.get("https://httpbin.io/json").raise_for_status.json
+      def raise_for_status
+      end
+
       # Get the response bytes as a binary string.
       # @return [String] Response body as binary data
       # @example
@@ -126,8 +216,11 @@ unless defined?(Wreq)
       # by yielding each chunk of the body as it arrives, without loading
       # the entire response into memory.
       #
-      # @return An iterator over response body chunks (binary String)
+      # Without a block, returns an Enumerator over the chunks.
+      #
+      # @return [nil, Enumerator]
       # @yield [chunk] Each chunk of the response body as a binary String
+      # @raise [Wreq::Error] if reading the body fails
       # @example Save response to file
       #   File.open("output.bin", "wb") do |f|
       #     response.chunks { |chunk| f.write(chunk) }
@@ -136,11 +229,57 @@ unless defined?(Wreq)
       #   total = 0
       #   response.chunks { |chunk| total += chunk.bytesize }
       #   puts "Downloaded #{total} bytes"
-      #
-      # Note: The returned Receiver is only for reading response bodies, not for uploads.
       def chunks
       end
 
+      # Parse the body as a `text/event-stream`, yielding each event.
+      #
+      # Waiting for the next event releases the GVL. The body is read once,
+      # like with {#chunks}; use Client#sse to reconnect when the stream ends.
+      #
+      # @return [Enumerator] if no block is given
+      # @yield [event] Each event of the stream
+      # @yieldparam event [Wreq::ServerSentEvent]
+      # @raise [Wreq::Error] if reading the body fails
+      # @example
+      #   response = client.get(url, headers: {"Accept" => "text/event-stream"})
+      #   response.each_event { |event| puts event.data }
+      def each_event
+      end
+
+      # Save the response body to a file.
+      #
+      # The body is streamed to disk on the runtime thread without holding the GVL,
+      # so it never passes through Ruby strings. When a block is given it is called
+      # after each chunk with the bytes written so far and the total (if known).
+      #
+      # @param path [String, Pathname] Destination file path
+      # @param atomic [Boolean] Write to a temporary file in the same directory and
+      #   rename it into place once the body is complete (default: false)
+      # @param digest [Symbol, String, nil] Digest algorithm to compute while writing
+      #   (:sha256, :sha384 or :sha512)
+      # @yield [written, total] Progress callback
+      # @return [Wreq::DownloadResult] Path, byte count and digest
+      # @raise [Wreq::BodyError] if the file cannot be written
+      # @example
+      #   result = response.save_to("archive.tar.gz", atomic: true, digest: :sha256)
+      #   puts "#{result.bytes} bytes, sha256=#{result.digest}"
+      def save_to(path, atomic: false, digest: nil, &block)
+      end
+
+      # Copy the response body into an IO object.
+      #
+      # Each chunk is read without the GVL and then written with `io.write`.
+      #
+      # @param io [IO] Destination IO (File, StringIO, socket, ...)
+      # @param digest [Symbol, String, nil] Digest algorithm to compute while writing
+      # @yield [written, total] Progress callback
+      # @return [Wreq::DownloadResult] Byte count and digest (path is nil)
+      # @example
+      #   File.open("out.bin", "ab") { |f| response.copy_to(f) }
+      def copy_to(io, digest: nil, &block)
+      end
+
       # Close the response and free associated resources.
       #
       # @return [void]
@@ -149,6 +288,198 @@ unless defined?(Wreq)
       def close
       end
     end
+
+    # Result of Response#save_to, Response#copy_to and Wreq.download.
+    class DownloadResult
+      # @return [String, nil] Destination path, or nil for copy_to
+      def path
+      end
+
+      # @return [Integer] Number of body bytes written
+      def bytes
+      end
+
+      # @return [String, nil] Hex-encoded digest, if requested
+      def digest
+      end
+    end
+
+    # TLS details of a response, returned by {Response#tls_info}.
+    class TlsInfo
+      # @return [String, nil] Application protocol ("h2" or "http/1.1"),
+      #   inferred from the HTTP version used on the connection
+      def alpn
+      end
+
+      # @return [String, nil] Server name sent with SNI, or nil for IP addresses
+      def sni
+      end
+
+      # @return [Wreq::Certificate, nil] Leaf certificate of the server
+      def peer_certificate
+      end
+
+      # @return [Array<Wreq::Certificate>] Certificate chain of the server, leaf first
+      def peer_certificates
+      end
+    end
+
+    # A certificate presented by the server, see {TlsInfo}.
+    class Certificate
+      # @return [String] Certificate in DER encoding (binary)
+      def der
+      end
+
+      # @return [String] Certificate in PEM encoding
+      def to_pem
+      end
+
+      # @return [String, nil] Subject distinguished name (e.g. "CN=example.com")
+      def subject
+      end
+
+      # @return [String, nil] Issuer distinguished name
+      def issuer
+      end
+
+      # @return [Array<String>] DNS names, IP addresses, URIs and emails of
+      #   the subject alternative name extension
+      def subject_alt_names
+      end
+
+      # @return [Time, nil] Start of the validity period
+      def not_before
+      end
+
+      # @return [Time, nil] End of the validity period
+      def not_after
+      end
+    end
+
+    # A redirect response followed on the way to the final response.
+    #
+    # Listed by {Response#history} and passed to `redirect:` callbacks.
+    #
+    # @example Only follow redirects within the same path prefix
+    #   client.get(url, redirect: ->(hop) { URI(hop.location).path.start_with?("/api/") })
+    class RedirectHop
+      # @return [Wreq::StatusCode] Status of the redirect response
+      def status
+      end
+
+      # @return [String] URL that answered with the redirect
+      def url
+      end
+
+      # @return [String] Absolute URL the redirect points to
+      def location
+      end
+
+      # @return [Wreq::Headers] Headers of the redirect response
+      def headers
+      end
+    end
+
+    # The request sent for a response, after redirects, with the headers the
+    # client sent.
+    #
+    # Returned frozen by {Response#request}.
+    #
+    # @example
+    #   response = client.get("https://httpbin.io/redirect/1", allow_redirects: true)
+    #   response.request.url      # => "https://httpbin.io/get"
+    #   response.request.headers  # => [["user-agent", "..."], ["accept", "*/*"], ...]
+    class RequestInfo
+      # @return [Wreq::Method] Method of the last request, after any redirect
+      #   that changed it
+      def method
+      end
+
+      # @return [String] URL of the last request, after any redirects
+      def url
+      end
+
+      # The sent headers as `[name, value]` pairs, in the order they were
+      # sent. Names listed in `orig_headers:` come first, with their
+      # original case over HTTP/1; HTTP/2 sends all names in lowercase.
+      # Includes the client's default headers, those of the emulation and
+      # the `Host` header of HTTP/1 requests.
+      #
+      # @return [Array<Array(String, String)>] Frozen header pairs
+      def headers
+      end
+
+      # @return [Wreq::Version] HTTP version negotiated for the request
+      def version
+      end
+
+      # @return [Integer, nil] Size of the sent body in bytes, 0 without a
+      #   body, nil for a stream of unknown length
+      def body_size
+      end
+    end
+
+    # Durations of the phases of a request, in seconds, like the phases of
+    # `curl -w`.
+    #
+    # Requests sent on a pooled connection skip the lookup and the connect,
+    # so both are 0 and {#reused?} is true. The TLS backend does not report
+    # when the handshake starts, so it is timed as part of {#connect}.
+    #
+    # Returned by {Response#timings}.
+    #
+    # @example
+    #   timings = response.timings
+    #   timings.dns + timings.connect
+    #   # => time to open the connection
+    #   timings.server  # => time the server took to answer
+    class Timings
+      # @return [Float] Time spent looking up the host name
+      def dns
+      end
+
+      # The connect after the lookup, including any proxy tunnel and the
+      # TLS handshake (curl's `time_appconnect`, or `time_connect` for plain
+      # HTTP, less the lookup).
+      #
+      # @return [Float] Time spent connecting
+      def connect
+      end
+
+      # @return [Float] Time from the connection being ready to the end of
+      #   the request body being written
+      def request_written
+      end
+
+      # @return [Float] Time from the request being written to the
+      #   response headers
+      def server
+      end
+
+      # @return [Float] Time from sending the request to the response
+      #   headers (curl's `time_starttransfer`)
+      def first_byte
+      end
+
+      # @return [Float, nil] Time spent reading the body, nil until it is
+      #   read to the end
+      def body
+      end
+
+      # @return [Float, nil] Time from sending the request to the end of
+      #   the body (curl's `time_total`), nil until the body is read
+      def total
+      end
+
+      # @return [Boolean] Whether the request was sent on a pooled
+      #   connection
+      def reused?
+      end
+
+      # @return [Hash{Symbol => Float, Boolean, nil}] The timings as a Hash
+      def to_h
+      end
+    end
   end
 end
 
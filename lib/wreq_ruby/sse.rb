@@ -0,0 +1,35 @@
+# frozen_string_literal: true
+
+unless defined?(Wreq)
+  module Wreq
+    # An event of a `text/event-stream` response, yielded by Client#sse,
+    # Wreq.sse and Response#each_event.
+    #
+    # Multi-line `data:` fields are joined with newlines; comment lines
+    # (starting with `:`) and events without data are skipped.
+    #
+    # @example
+    #   client.sse("https://example.com/stream") do |event|
+    #     puts "#{event.event}: #{event.data}"
+    #   end
+    class ServerSentEvent
+      # @return [String] Event type, "message" unless set with `event:`
+      def event
+      end
+
+      # @return [String] Event data, its `data:` lines joined with newlines
+      def data
+      end
+
+      # @return [String, nil] Last event ID set with `id:`, kept for the
+      #   following events
+      def id
+      end
+
+      # @return [Integer, nil] Reconnect delay in milliseconds set with
+      #   `retry:` in this event
+      def retry
+      end
+    end
+  end
+end
@@ -0,0 +1,116 @@
+# frozen_string_literal: true
+
+unless defined?(Wreq)
+  module Wreq
+    # A WebSocket connection opened with Client#websocket or Wreq.websocket.
+    #
+    # The handshake uses the same emulated TLS/HTTP fingerprints as regular
+    # requests. Sending and receiving release the GVL and can be interrupted
+    # with Thread#kill / Thread#raise, just like HTTP requests.
+    #
+    # Sending and receiving are independent, so one thread may block in
+    # `recv` while another thread calls `send_text`.
+    #
+    # @example Echo
+    #   ws = client.websocket("wss://echo.websocket.org")
+    #   ws.send_text("hello")
+    #   puts ws.recv.text
+    #   ws.close
+    #
+    # @example Iterate over messages
+    #   ws.each do |message|
+    #     puts message.text if message.text?
+    #   end
+    class WebSocket
+      # @return [Wreq::StatusCode] Handshake response status (101)
+      def status
+      end
+
+      # @return [Wreq::Headers] Handshake response headers
+      def headers
+      end
+
+      # @return [String, nil] Subprotocol selected by the server
+      def protocol
+      end
+
+      # Send a text message.
+      # @param text [String]
+      # @return [void]
+      # @raise [Wreq::ConnectionError] if the connection has been closed
+      def send_text(text)
+      end
+
+      # Send a binary message.
+      # @param data [String] binary data
+      # @return [void]
+      # @raise [Wreq::ConnectionError] if the connection has been closed
+      def send_binary(data)
+      end
+
+      # Send a ping frame.
+      # @param payload [String] optional ping payload
+      # @return [void]
+      def ping(payload = "")
+      end
+
+      # Receive the next message, blocking until one arrives.
+      #
+      # @param timeout [Float, nil] Maximum time to wait, in seconds
+      # @return [Wreq::Message, nil] The message, or nil once the connection is closed
+      # @raise [Wreq::TimeoutError] if no message arrives within `timeout`
+      # @raise [ArgumentError] if `timeout` is negative or not finite
+      def recv(timeout: nil)
+      end
+
+      # Iterate over received messages until the connection is closed.
+      # @yield [message] Each received Wreq::Message
+      # @return [Enumerator] if no block is given
+      # @raise [Wreq::Error] if receiving fails, e.g. on a protocol error
+      def each
+      end
+
+      # Send a close frame and shut down the sending side.
+      # @param code [Integer] Close code (default 1000, normal closure)
+      # @param reason [String, nil] Close reason
+      # @return [void]
+      def close(code = 1000, reason = nil)
+      end
+    end
+
+    # A message received from a WebSocket.
+    class Message
+      # @return [Symbol] :text, :binary, :ping, :pong or :close
+      def kind
+      end
+
+      # @return [String, nil] Message text, for text messages
+      def text
+      end
+
+      # @return [String] Raw payload as a binary String
+      def data
+      end
+
+      # @return [Integer, nil] Close code, for close messages
+      def close_code
+      end
+
+      # @return [String, nil] Close reason, for close messages
+      def close_reason
+      end
+
+      # @return [Boolean]
+      def text?
+      end
+
+      # @return [Boolean]
+      def binary?
+      end
+
+      # @return [Boolean]
+      def close?
+      end
+    end
+  end
+end
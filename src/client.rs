@@ -1,22 +1,60 @@
 mod body;
+mod connect;
+mod dns;
+mod middleware;
+mod parallel;
 mod param;
+pub mod pending;
+pub mod proxy;
 mod query;
+mod redirect;
 mod req;
 pub mod resp;
+pub mod retry;
+mod sse;
+mod timeout;
+mod timings;
+pub mod tls;
+pub mod ws;
+
+use std::{
+    net::IpAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use std::{net::IpAddr, time::Duration};
-
+use bytes::Bytes;
+use indexmap::IndexMap;
 use magnus::{
-    Module, Object, RHash, RModule, Ruby, TryConvert, Value, function, method, typed_data::Obj,
+    DataTypeFunctions, Module, Object, RArray, RHash, RModule, Ruby, TryConvert, Value,
+    block::Proc, function, gc::Marker, method, scan_args, typed_data::Obj, value::ReprValue,
 };
 use serde::Deserialize;
 use wreq::{
-    Proxy,
     header::{HeaderMap, HeaderValue, OrigHeaderMap},
+    tls::{CertStore, Identity, TlsVersion},
 };
 
 use crate::{
-    client::{req::execute_request, resp::Response},
+    client::{
+        connect::ConnectLayer,
+        dns::{self, IpFamily, Overrides, PoolKey, Resolver},
+        middleware::Middleware,
+        parallel::execute_parallel,
+        pending::Pending,
+        proxy::{ProxyPool, ProxySetting},
+        redirect::{RedirectPolicy, Redirects},
+        req::{Request, execute_request, spawn_request},
+        resp::{
+            DownloadOptions, DownloadResult, HeaderOrder, RaiseOnStatus, Response,
+            SentHeadersLayer, extract_header_order,
+        },
+        retry::RetryPolicy,
+        sse::execute_sse,
+        tls::{CertPins, PinLayer},
+        ws::{WebSocket, WebSocketRequest, execute_websocket_request},
+    },
     cookie::Jar,
     emulate::Emulation,
     error::wreq_error_to_magnus,
@@ -26,7 +64,7 @@ use crate::{
 };
 
 /// A builder for `Client`.
-#[derive(Default, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 struct Builder {
     // The emulation option for the client.
     #[serde(skip)]
@@ -40,12 +78,23 @@ struct Builder {
     /// The original headers to use for the client.
     #[serde(skip)]
     orig_headers: Option<OrigHeaderMap>,
+    /// The header names of `orig_headers`, in their original case.
+    #[serde(skip)]
+    header_order: Vec<Bytes>,
     /// Whether to use referer.
     referer: Option<bool>,
     /// Whether to allow redirects.
     allow_redirects: Option<bool>,
     /// The maximum number of redirects to follow.
     max_redirects: Option<usize>,
+    /// The redirect policy for requests.
+    #[serde(skip)]
+    redirect: Option<RedirectPolicy>,
+
+    // ========= Middleware options =========
+    /// The middleware run around every request.
+    #[serde(skip)]
+    middleware: Option<Middleware>,
 
     // ========= Cookie options =========
     /// Whether to use cookie store.
@@ -54,32 +103,61 @@ struct Builder {
     #[serde(skip)]
     cookie_provider: Option<Jar>,
 
+    // ========= Retry options =========
+    /// The default retry policy for requests.
+    #[serde(skip)]
+    retry: Option<RetryPolicy>,
+
+    // ========= Status options =========
+    /// The statuses that raise a `Wreq::StatusError`.
+    #[serde(skip)]
+    raise_on_status: Option<RaiseOnStatus>,
+
     // ========= Timeout options =========
-    /// The timeout to use for the client. (in seconds)
-    timeout: Option<u64>,
-    /// The connect timeout to use for the client. (in seconds)
-    connect_timeout: Option<u64>,
-    /// The read timeout to use for the client. (in seconds)
-    read_timeout: Option<u64>,
+    /// The timeout to use for the client.
+    #[serde(skip)]
+    timeout: Option<Duration>,
+    /// The connect timeout to use for the client.
+    #[serde(skip)]
+    connect_timeout: Option<Duration>,
+    /// The read timeout to use for the client.
+    #[serde(skip)]
+    read_timeout: Option<Duration>,
+    /// The timeout for looking up host names.
+    #[serde(skip)]
+    dns_timeout: Option<Duration>,
+    /// The timeout for waiting for a connection from the pool.
+    #[serde(skip)]
+    pool_timeout: Option<Duration>,
+    /// The timeout for receiving the response headers.
+    #[serde(skip)]
+    first_byte_timeout: Option<Duration>,
 
     // ========= TCP options =========
-    /// Set that all sockets have `SO_KEEPALIVE` set with the supplied duration. (in seconds)
-    tcp_keepalive: Option<u64>,
-    /// Set the interval between TCP keepalive probes. (in seconds)
-    tcp_keepalive_interval: Option<u64>,
+    /// Set that all sockets have `SO_KEEPALIVE` set with the supplied duration.
+    #[serde(skip)]
+    tcp_keepalive: Option<Duration>,
+    /// Set the interval between TCP keepalive probes.
+    #[serde(skip)]
+    tcp_keepalive_interval: Option<Duration>,
     /// Set the number of retries for TCP keepalive.
     tcp_keepalive_retries: Option<u32>,
-    /// Set an optional user timeout for TCP sockets. (in seconds)
+    /// Set an optional user timeout for TCP sockets.
     #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
-    tcp_user_timeout: Option<u64>,
+    #[serde(skip)]
+    tcp_user_timeout: Option<Duration>,
     /// Set that all sockets have `NO_DELAY` set.
     tcp_nodelay: Option<bool>,
     /// Set that all sockets have `SO_REUSEADDR` set.
     tcp_reuse_address: Option<bool>,
+    /// The delay before also trying the other address family, or `None` to disable it.
+    #[serde(skip)]
+    happy_eyeballs_delay: Option<Option<Duration>>,
 
     // ========= Connection pool options =========
-    /// Set an optional timeout for idle sockets being kept-alive. (in seconds)
-    pool_idle_timeout: Option<u64>,
+    /// Set an optional timeout for idle sockets being kept-alive.
+    #[serde(skip)]
+    pool_idle_timeout: Option<Duration>,
     /// Sets the maximum idle connection per host allowed in the pool.
     pool_max_idle_per_host: Option<usize>,
     /// Sets the maximum number of connections in the pool.
@@ -94,19 +172,48 @@ struct Builder {
     https_only: Option<bool>,
 
     // ========= TLS options =========
-    /// Whether to verify the SSL certificate or root certificate file path.
+    /// Whether to verify the server certificate.
     verify: Option<bool>,
+    /// The root certificates to trust instead of the default ones.
+    #[serde(skip)]
+    cert_store: Option<CertStore>,
+    /// The client certificate for mutual TLS.
+    #[serde(skip)]
+    identity: Option<Identity>,
+    /// The minimum TLS version to negotiate.
+    #[serde(skip)]
+    min_tls_version: Option<TlsVersion>,
+    /// The maximum TLS version to negotiate.
+    #[serde(skip)]
+    max_tls_version: Option<TlsVersion>,
+    /// Whether to record TLS session details on responses.
+    tls_info: Option<bool>,
+    /// The public key hashes to pin.
+    #[serde(skip)]
+    pin_sha256: Option<CertPins>,
 
     // ========= Network options =========
     /// Whether to disable the proxy for the client.
     no_proxy: Option<bool>,
-    /// The proxy to use for the client.
+    /// The proxies, or proxy pool, to use for the client.
     #[serde(skip)]
-    proxy: Option<Proxy>,
+    proxy: Option<ProxySetting>,
     /// Bind to a local IP Address.
     local_address: Option<IpAddr>,
     /// Bind to an interface by `SO_BINDTODEVICE`.
     interface: Option<String>,
+    /// Connect through a Unix domain socket instead of TCP.
+    #[cfg(unix)]
+    unix_socket: Option<PathBuf>,
+    /// The static addresses to use for host names.
+    #[serde(skip)]
+    resolve: Option<Overrides>,
+    /// The resolver for host names without static addresses.
+    #[serde(skip)]
+    dns_resolver: Option<Resolver>,
+    /// The address family to connect with.
+    #[serde(skip)]
+    ip_family: Option<IpFamily>,
 
     // ========= Compression options =========
     /// Sets gzip as an accepted encoding.
@@ -119,9 +226,34 @@ struct Builder {
     zstd: Option<bool>,
 }
 
-#[derive(Clone, Default)]
-#[magnus::wrap(class = "Wreq::Client", free_immediately, size)]
-pub struct Client(wreq::Client);
+#[derive(Clone)]
+#[magnus::wrap(class = "Wreq::Client", free_immediately, size, mark)]
+pub struct Client {
+    inner: wreq::Client,
+    retry: Option<RetryPolicy>,
+    redirects: Redirects,
+    middleware: Middleware,
+    resolver: Arc<Resolver>,
+    proxy_pool: Option<ProxyPool>,
+    pool_timeout: Option<Duration>,
+    first_byte_timeout: Option<Duration>,
+    raise_on_status: Option<RaiseOnStatus>,
+    header_order: Arc<HeaderOrder>,
+    dns_clients: Arc<DnsClients>,
+}
+
+/// The clients for requests with their own `resolve:` or `ip_family:`, built with the
+/// options of the client.
+///
+/// Connections are pooled per client, so such requests only reuse connections opened for
+/// the same DNS settings.
+struct DnsClients {
+    builder: Builder,
+    clients: Mutex<IndexMap<PoolKey, wreq::Client>>,
+}
+
+/// The most clients kept for per-request DNS settings, dropping the oldest beyond it.
+const MAX_DNS_CLIENTS: usize = 16;
 
 // ===== impl Builder =====
 
@@ -144,232 +276,581 @@ impl Builder {
 
             // extra original headers handling
             builder.orig_headers = Extractor::<OrigHeaderMap>::try_convert(*keyword)?.into_inner();
+            builder.header_order = extract_header_order(ruby, hash);
 
             // extra proxy handling
-            builder.proxy = Extractor::<Proxy>::try_convert(*keyword)?.into_inner();
+            builder.proxy = Extractor::<ProxySetting>::try_convert(*keyword)?.into_inner();
 
             // extra cookie store handling
             if let Some(jar) = hash.get(ruby.to_symbol("cookie_provider")) {
                 builder.cookie_provider = Some((*Obj::<Jar>::try_convert(jar)?).clone());
             }
 
+            // extra retry handling
+            if let Some(retry) = hash.get(ruby.to_symbol("retry")).filter(|v| !v.is_nil()) {
+                builder.retry = Some(RetryPolicy::new(ruby, retry)?);
+            }
+
+            // extra redirect handling
+            if let Some(redirect) = hash.get(ruby.to_symbol("redirect")).filter(|v| !v.is_nil()) {
+                builder.redirect = Some(RedirectPolicy::new(ruby, redirect)?);
+            }
+
+            // extra status handling
+            if let Some(raise_on_status) = hash
+                .get(ruby.to_symbol("raise_on_status"))
+                .filter(|v| !v.is_nil())
+            {
+                builder.raise_on_status = Some(RaiseOnStatus::new(ruby, raise_on_status)?);
+            }
+
+            // extra TLS handling
+            builder.cert_store = tls::extract_cert_store(ruby, hash)?;
+            builder.identity = tls::extract_identity(ruby, hash)?;
+            builder.min_tls_version = tls::extract_tls_version(ruby, hash, "min_tls_version")?;
+            builder.max_tls_version = tls::extract_tls_version(ruby, hash, "max_tls_version")?;
+            if let Some(pins) = hash
+                .get(ruby.to_symbol("pin_sha256"))
+                .filter(|v| !v.is_nil())
+            {
+                builder.pin_sha256 = Some(CertPins::new(ruby, pins)?);
+            }
+
+            // extra resolve handling
+            if let Some(resolve) = hash.get(ruby.to_symbol("resolve")).filter(|v| !v.is_nil()) {
+                builder.resolve = Some(Overrides::new(ruby, resolve)?);
+            }
+            if let Some(resolver) = hash
+                .get(ruby.to_symbol("dns_resolver"))
+                .filter(|v| !v.is_nil())
+            {
+                builder.dns_resolver = Some(Resolver::new(ruby, resolver)?);
+            }
+            if let Some(ip_family) = hash
+                .get(ruby.to_symbol("ip_family"))
+                .filter(|v| !v.is_nil())
+            {
+                builder.ip_family = Some(IpFamily::new(ruby, ip_family)?);
+            }
+
+            // extra timeout handling, in seconds as any Numeric
+            builder.timeout = timeout::extract_duration(ruby, hash, "timeout")?;
+            builder.connect_timeout = timeout::extract_duration(ruby, hash, "connect_timeout")?;
+            builder.read_timeout = timeout::extract_duration(ruby, hash, "read_timeout")?;
+            builder.dns_timeout = timeout::extract_duration(ruby, hash, "dns_timeout")?;
+            builder.pool_timeout = timeout::extract_duration(ruby, hash, "pool_timeout")?;
+            builder.first_byte_timeout =
+                timeout::extract_duration(ruby, hash, "first_byte_timeout")?;
+            builder.tcp_keepalive = timeout::extract_duration(ruby, hash, "tcp_keepalive")?;
+            builder.tcp_keepalive_interval =
+                timeout::extract_duration(ruby, hash, "tcp_keepalive_interval")?;
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            {
+                builder.tcp_user_timeout =
+                    timeout::extract_duration(ruby, hash, "tcp_user_timeout")?;
+            }
+            builder.pool_idle_timeout = timeout::extract_duration(ruby, hash, "pool_idle_timeout")?;
+
+            // extra happy eyeballs handling
+            if let Some(delay) = hash
+                .get(ruby.to_symbol("happy_eyeballs_delay"))
+                .filter(|v| !v.is_nil())
+            {
+                builder.happy_eyeballs_delay =
+                    Some(if delay.is_kind_of(ruby.class_false_class()) {
+                        None
+                    } else {
+                        let secs = f64::try_convert(delay)?;
+                        Some(Duration::try_from_secs_f64(secs).map_err(|err| {
+                            magnus::Error::new(
+                                ruby.exception_arg_error(),
+                                format!("invalid happy_eyeballs_delay: {err}"),
+                            )
+                        })?)
+                    });
+            }
+
+            // extra middleware handling
+            if let Some(middleware) = hash
+                .get(ruby.to_symbol("middleware"))
+                .filter(|v| !v.is_nil())
+            {
+                builder.middleware = Some(Middleware::new(ruby, middleware)?);
+            }
+
             return Ok(builder);
         }
 
         Ok(Default::default())
     }
+
+    /// Build a [`wreq::Client`] with the options not kept by [`Client`] itself, looking
+    /// up host names with `resolver`.
+    fn build(mut self, resolver: Arc<Resolver>) -> Result<wreq::Client, wreq::Error> {
+        let pins = self
+            .pin_sha256
+            .take()
+            .map(|pins| PinLayer::new(pins, self.tls_info.unwrap_or(false)));
+        let mut builder = wreq::Client::builder();
+
+        // Emulation options.
+        apply_option!(set_if_some_inner, builder, self.emulation, emulation);
+
+        // User agent options.
+        apply_option!(set_if_some, builder, self.user_agent, user_agent);
+
+        // Default headers options.
+        apply_option!(set_if_some, builder, self.headers, default_headers);
+        apply_option!(set_if_some, builder, self.orig_headers, orig_headers);
+
+        // Cookie options.
+        apply_option!(set_if_some, builder, self.cookie_store, cookie_store);
+        apply_option!(
+            set_if_some_inner,
+            builder,
+            self.cookie_provider,
+            cookie_provider
+        );
+
+        // TCP options.
+        apply_option!(set_if_some, builder, self.tcp_keepalive, tcp_keepalive);
+        apply_option!(
+            set_if_some,
+            builder,
+            self.tcp_keepalive_interval,
+            tcp_keepalive_interval
+        );
+        apply_option!(
+            set_if_some,
+            builder,
+            self.tcp_keepalive_retries,
+            tcp_keepalive_retries
+        );
+        #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+        apply_option!(
+            set_if_some,
+            builder,
+            self.tcp_user_timeout,
+            tcp_user_timeout
+        );
+        apply_option!(set_if_some, builder, self.tcp_nodelay, tcp_nodelay);
+        apply_option!(
+            set_if_some,
+            builder,
+            self.tcp_reuse_address,
+            tcp_reuse_address
+        );
+        apply_option!(
+            set_if_some,
+            builder,
+            self.happy_eyeballs_delay,
+            tcp_happy_eyeballs_timeout
+        );
+
+        // Timeout options.
+        apply_option!(set_if_some, builder, self.timeout, timeout);
+        apply_option!(set_if_some, builder, self.connect_timeout, connect_timeout);
+        apply_option!(set_if_some, builder, self.read_timeout, read_timeout);
+
+        // Pool options.
+        apply_option!(
+            set_if_some,
+            builder,
+            self.pool_idle_timeout,
+            pool_idle_timeout
+        );
+        apply_option!(
+            set_if_some,
+            builder,
+            self.pool_max_idle_per_host,
+            pool_max_idle_per_host
+        );
+        apply_option!(set_if_some, builder, self.pool_max_size, pool_max_size);
+
+        // Protocol options.
+        apply_option!(set_if_true, builder, self.http1_only, http1_only, false);
+        apply_option!(set_if_true, builder, self.http2_only, http2_only, false);
+        apply_option!(set_if_some, builder, self.https_only, https_only);
+
+        // TLS options.
+        apply_option!(set_if_some, builder, self.verify, cert_verification);
+        apply_option!(set_if_some, builder, self.cert_store, cert_store);
+        apply_option!(set_if_some, builder, self.identity, identity);
+        apply_option!(set_if_some, builder, self.min_tls_version, min_tls_version);
+        apply_option!(set_if_some, builder, self.max_tls_version, max_tls_version);
+        apply_option!(set_if_some, builder, self.tls_info, tls_info);
+
+        // Network options.
+        #[cfg(unix)]
+        if let Some(path) = self.unix_socket.take() {
+            builder = builder.proxy(wreq::Proxy::unix(path)?);
+        }
+        if let Some(ProxySetting::Fixed(proxies)) = self.proxy.take() {
+            for proxy in proxies.into_vec() {
+                builder = builder.proxy(proxy);
+            }
+        }
+        apply_option!(set_if_true, builder, self.no_proxy, no_proxy, false);
+        apply_option!(set_if_some, builder, self.local_address, local_address);
+        apply_option!(set_if_some, builder, self.interface, interface);
+        builder = builder.dns_resolver(resolver);
+        // Times each connection opened for a request.
+        builder = builder.connector_layer(ConnectLayer);
+        // Records what each request sends, for `Response#request`.
+        builder = builder.layer(SentHeadersLayer);
+        // Pins are checked on the certificates the client attaches to each response.
+        if let Some(pins) = pins {
+            builder = builder.tls_info(true).layer(pins);
+        }
+
+        // Compression options.
+        apply_option!(set_if_some, builder, self.gzip, gzip);
+        apply_option!(set_if_some, builder, self.brotli, brotli);
+        apply_option!(set_if_some, builder, self.deflate, deflate);
+        apply_option!(set_if_some, builder, self.zstd, zstd);
+
+        builder.build()
+    }
+}
+
+// ===== impl DnsClients =====
+
+impl DnsClients {
+    fn new(builder: Builder) -> Self {
+        Self {
+            builder,
+            clients: Mutex::default(),
+        }
+    }
+
+    /// Get the client for `key`, building it with `resolver` the first time.
+    fn get(
+        &self,
+        key: PoolKey,
+        resolver: impl FnOnce() -> Resolver,
+    ) -> Result<wreq::Client, wreq::Error> {
+        let mut clients = self.clients.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let client = self.builder.clone().build(Arc::new(resolver()))?;
+        if clients.len() == MAX_DNS_CLIENTS {
+            clients.shift_remove_index(0);
+        }
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
 }
 
 // ===== impl Client =====
 
+impl Default for Client {
+    fn default() -> Self {
+        // The resolver is always installed, so per-request `resolve:` works on any client.
+        let resolver = Arc::new(Resolver::default());
+        let inner = Builder::default()
+            .build(resolver.clone())
+            .expect("failed to build the default client");
+        Self {
+            inner,
+            retry: None,
+            redirects: Redirects::default(),
+            middleware: Middleware::default(),
+            resolver,
+            proxy_pool: None,
+            pool_timeout: None,
+            first_byte_timeout: None,
+            raise_on_status: None,
+            header_order: Arc::default(),
+            dns_clients: Arc::new(DnsClients::new(Builder::default())),
+        }
+    }
+}
+
+impl DataTypeFunctions for Client {
+    fn mark(&self, marker: &Marker) {
+        self.redirects.mark(marker);
+        self.middleware.mark(marker);
+        self.resolver.mark(marker);
+    }
+}
+
 impl Client {
     /// Create a new [`Client`] with the given keyword arguments.
     pub fn new(ruby: &Ruby, kwargs: &[Value]) -> Result<Self, magnus::Error> {
         if let Some(kwargs) = kwargs.first() {
             let mut params = Builder::new(ruby, kwargs)?;
+            let retry = params.retry.take();
+            // Redirects are followed per request, so they are not handed to wreq.
+            let redirects = Redirects::new(
+                params.allow_redirects.take(),
+                params.max_redirects.take(),
+                params.redirect.take(),
+                params.referer.take(),
+            );
+            let middleware = params.middleware.take().unwrap_or_default();
+            // A Unix socket is reached as a proxy for every request, in place of any other.
+            #[cfg(unix)]
+            if params.unix_socket.is_some() {
+                params.proxy = None;
+            }
+            // A jar of the client's own, so the clients it builds alike share its cookies.
+            if params.cookie_store == Some(true) && params.cookie_provider.is_none() {
+                params.cookie_provider = Some(Jar::default());
+            }
+            // A pool chooses a proxy per request, so it is not handed to wreq.
+            let proxy_pool = match &params.proxy {
+                Some(ProxySetting::Pool(pool)) => Some(pool.clone()),
+                _ => None,
+            };
+            let resolver = Arc::new(
+                params
+                    .dns_resolver
+                    .take()
+                    .unwrap_or_default()
+                    .with_overrides(params.resolve.take())
+                    .with_ip_family(params.ip_family.take())
+                    .with_timeout(params.dns_timeout.take()),
+            );
+            let pool_timeout = params.pool_timeout.take();
+            let first_byte_timeout = params.first_byte_timeout.take();
+            let raise_on_status = params.raise_on_status.take();
+            // Kept to describe the sent request in `Response#request`.
+            let header_order = Arc::new(HeaderOrder::new(std::mem::take(&mut params.header_order)));
+            // Requests with their own `resolve:` or `ip_family:` use clients built alike.
+            let dns_clients = Arc::new(DnsClients::new(params.clone()));
             gvl::nogvl(|| {
-                let mut builder = wreq::Client::builder();
-
-                // Emulation options.
-                apply_option!(set_if_some_inner, builder, params.emulation, emulation);
-
-                // User agent options.
-                apply_option!(set_if_some, builder, params.user_agent, user_agent);
-
-                // Default headers options.
-                apply_option!(set_if_some, builder, params.headers, default_headers);
-                apply_option!(set_if_some, builder, params.orig_headers, orig_headers);
-
-                // Allow redirects options.
-                apply_option!(set_if_some, builder, params.referer, referer);
-                apply_option!(
-                    set_if_true_with,
-                    builder,
-                    params.allow_redirects,
-                    redirect,
-                    false,
-                    params
-                        .max_redirects
-                        .take()
-                        .map(wreq::redirect::Policy::limited)
-                        .unwrap_or_default()
-                );
-
-                // Cookie options.
-                apply_option!(set_if_some, builder, params.cookie_store, cookie_store);
-                apply_option!(
-                    set_if_some_inner,
-                    builder,
-                    params.cookie_provider,
-                    cookie_provider
-                );
-
-                // TCP options.
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.tcp_keepalive,
-                    tcp_keepalive,
-                    Duration::from_secs
-                );
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.tcp_keepalive_interval,
-                    tcp_keepalive_interval,
-                    Duration::from_secs
-                );
-                apply_option!(
-                    set_if_some,
-                    builder,
-                    params.tcp_keepalive_retries,
-                    tcp_keepalive_retries
-                );
-                #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.tcp_user_timeout,
-                    tcp_user_timeout,
-                    Duration::from_secs
-                );
-                apply_option!(set_if_some, builder, params.tcp_nodelay, tcp_nodelay);
-                apply_option!(
-                    set_if_some,
-                    builder,
-                    params.tcp_reuse_address,
-                    tcp_reuse_address
-                );
-
-                // Timeout options.
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.timeout,
-                    timeout,
-                    Duration::from_secs
-                );
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.connect_timeout,
-                    connect_timeout,
-                    Duration::from_secs
-                );
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.read_timeout,
-                    read_timeout,
-                    Duration::from_secs
-                );
-
-                // Pool options.
-                apply_option!(
-                    set_if_some_map,
-                    builder,
-                    params.pool_idle_timeout,
-                    pool_idle_timeout,
-                    Duration::from_secs
-                );
-                apply_option!(
-                    set_if_some,
-                    builder,
-                    params.pool_max_idle_per_host,
-                    pool_max_idle_per_host
-                );
-                apply_option!(set_if_some, builder, params.pool_max_size, pool_max_size);
-
-                // Protocol options.
-                apply_option!(set_if_true, builder, params.http1_only, http1_only, false);
-                apply_option!(set_if_true, builder, params.http2_only, http2_only, false);
-                apply_option!(set_if_some, builder, params.https_only, https_only);
-
-                // TLS options.
-                apply_option!(set_if_some, builder, params.verify, cert_verification);
-
-                // Network options.
-                apply_option!(set_if_some, builder, params.proxy, proxy);
-                apply_option!(set_if_true, builder, params.no_proxy, no_proxy, false);
-                apply_option!(set_if_some, builder, params.local_address, local_address);
-                apply_option!(set_if_some, builder, params.interface, interface);
-
-                // Compression options.
-                apply_option!(set_if_some, builder, params.gzip, gzip);
-                apply_option!(set_if_some, builder, params.brotli, brotli);
-                apply_option!(set_if_some, builder, params.deflate, deflate);
-                apply_option!(set_if_some, builder, params.zstd, zstd);
-
-                builder.build().map(Client).map_err(wreq_error_to_magnus)
+                params.build(resolver.clone()).map(|inner| Client {
+                    inner,
+                    retry,
+                    redirects,
+                    middleware,
+                    resolver,
+                    proxy_pool,
+                    pool_timeout,
+                    first_byte_timeout,
+                    raise_on_status,
+                    header_order,
+                    dns_clients,
+                })
             })
+            .map_err(wreq_error_to_magnus)
         } else {
-            gvl::nogvl(|| Ok(Self(wreq::Client::new())))
+            gvl::nogvl(|| Ok(Self::default()))
         }
     }
 }
 
 impl Client {
+    /// Get the client to send a request with the DNS `settings` on.
+    ///
+    /// A request with its own `resolve:` or `ip_family:` must not reuse a connection opened
+    /// for other addresses, so it is sent on a client for its settings.
+    fn for_dns(&self, settings: &dns::Settings) -> Result<wreq::Client, wreq::Error> {
+        match settings.pool_key() {
+            Some(key) => self
+                .dns_clients
+                .get(key, || self.resolver.for_request(settings)),
+            None => Ok(self.inner.clone()),
+        }
+    }
+
     /// Send a HTTP request.
     #[inline]
-    pub fn request(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn request(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((method, url), request) = extract_request!(args, (Obj<Method>, String));
-        execute_request(rb_self.0.clone(), *method, url, request)
+        execute_request(rb_self.clone(), *method, url, request)
     }
 
     /// Send a GET request.
     #[inline]
-    pub fn get(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn get(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::GET, url, request)
+        execute_request(rb_self.clone(), Method::GET, url, request)
     }
 
     /// Send a POST request.
     #[inline]
-    pub fn post(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn post(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::POST, url, request)
+        execute_request(rb_self.clone(), Method::POST, url, request)
     }
 
     /// Send a PUT request.
     #[inline]
-    pub fn put(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn put(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::PUT, url, request)
+        execute_request(rb_self.clone(), Method::PUT, url, request)
     }
 
     /// Send a DELETE request.
     #[inline]
-    pub fn delete(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn delete(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::DELETE, url, request)
+        execute_request(rb_self.clone(), Method::DELETE, url, request)
     }
 
     /// Send a HEAD request.
     #[inline]
-    pub fn head(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn head(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::HEAD, url, request)
+        execute_request(rb_self.clone(), Method::HEAD, url, request)
     }
 
     /// Send an OPTIONS request.
     #[inline]
-    pub fn options(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn options(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::OPTIONS, url, request)
+        execute_request(rb_self.clone(), Method::OPTIONS, url, request)
     }
 
     /// Send a TRACE request.
     #[inline]
-    pub fn trace(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn trace(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::TRACE, url, request)
+        execute_request(rb_self.clone(), Method::TRACE, url, request)
     }
 
     /// Send a PATCH request.
     #[inline]
-    pub fn patch(rb_self: &Self, args: &[Value]) -> Result<Response, magnus::Error> {
+    pub fn patch(rb_self: &Self, args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        execute_request(rb_self.clone(), Method::PATCH, url, request)
+    }
+}
+
+impl Client {
+    /// Send a HTTP request in the background.
+    #[inline]
+    pub fn request_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((method, url), request) = extract_request!(args, (Obj<Method>, String));
+        spawn_request(rb_self.clone(), *method, url, request)
+    }
+
+    /// Send a GET request in the background.
+    #[inline]
+    pub fn get_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::GET, url, request)
+    }
+
+    /// Send a POST request in the background.
+    #[inline]
+    pub fn post_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::POST, url, request)
+    }
+
+    /// Send a PUT request in the background.
+    #[inline]
+    pub fn put_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::PUT, url, request)
+    }
+
+    /// Send a DELETE request in the background.
+    #[inline]
+    pub fn delete_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::DELETE, url, request)
+    }
+
+    /// Send a HEAD request in the background.
+    #[inline]
+    pub fn head_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
         let ((url,), request) = extract_request!(args, (String,));
-        execute_request(rb_self.0.clone(), Method::PATCH, url, request)
+        spawn_request(rb_self.clone(), Method::HEAD, url, request)
+    }
+
+    /// Send an OPTIONS request in the background.
+    #[inline]
+    pub fn options_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::OPTIONS, url, request)
+    }
+
+    /// Send a TRACE request in the background.
+    #[inline]
+    pub fn trace_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::TRACE, url, request)
+    }
+
+    /// Send a PATCH request in the background.
+    #[inline]
+    pub fn patch_async(rb_self: &Self, args: &[Value]) -> Result<Pending, magnus::Error> {
+        let ((url,), request) = extract_request!(args, (String,));
+        spawn_request(rb_self.clone(), Method::PATCH, url, request)
+    }
+}
+
+impl Client {
+    /// Send a GET request and save the response body to a file.
+    pub fn download(
+        ruby: &Ruby,
+        rb_self: &Self,
+        args: &[Value],
+    ) -> Result<DownloadResult, magnus::Error> {
+        let args = scan_args::scan_args::<(String, Value), (), (), (), RHash, Option<Proc>>(args)?;
+        let (url, path) = args.required;
+        let options = DownloadOptions::new(ruby, args.keywords, args.block)?;
+        let mut request = Request::new(ruby, args.keywords)?;
+        // An error page is not the file asked for; the status is checked before the file
+        // is created.
+        request.default_raise_on_status(RaiseOnStatus::Errors);
+        let path = resp::extract_path(path)?;
+        execute_request(rb_self.clone(), Method::GET, url, request)?.save(path, options)
+    }
+}
+
+impl Client {
+    /// Send many requests concurrently, returning responses or exceptions in input order.
+    pub fn parallel(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<RArray, magnus::Error> {
+        let args = scan_args::scan_args::<(RArray,), (), (), (), RHash, Option<Proc>>(args)?;
+        let (requests,) = args.required;
+        execute_parallel(ruby, rb_self.clone(), requests, args.keywords, args.block)
+    }
+}
+
+impl Client {
+    /// Append a middleware to the client's stack, returning the client.
+    pub fn use_middleware(
+        ruby: &Ruby,
+        rb_self: Obj<Self>,
+        middleware: Value,
+    ) -> Result<Obj<Self>, magnus::Error> {
+        rb_self.middleware.push(ruby, middleware)?;
+        Ok(rb_self)
+    }
+
+    /// Get the hits, misses and live entries of the DNS cache, or nil without one.
+    pub fn dns_cache_stats(ruby: &Ruby, rb_self: &Self) -> Result<Option<RHash>, magnus::Error> {
+        rb_self.resolver.cache_stats(ruby)
+    }
+}
+
+impl Client {
+    /// Open a WebSocket connection.
+    pub fn websocket(
+        ruby: &Ruby,
+        rb_self: &Self,
+        args: &[Value],
+    ) -> Result<WebSocket, magnus::Error> {
+        let args = scan_args::scan_args::<(String,), (), (), (), RHash, ()>(args)?;
+        let (url,) = args.required;
+        let mut request = WebSocketRequest::new(ruby, args.keywords)?;
+        let client = rb_self
+            .for_dns(&request.take_dns())
+            .map_err(wreq_error_to_magnus)?;
+        execute_websocket_request(client, rb_self.proxy_pool.clone(), url, request)
+    }
+}
+
+impl Client {
+    /// Open a Server-Sent Events stream, yielding each event.
+    #[inline]
+    pub fn sse(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<Value, magnus::Error> {
+        execute_sse(ruby, rb_self, args)
     }
 }
 
@@ -385,8 +866,30 @@ pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), magnus::Error> {
     client_class.define_method("options", method!(Client::options, -1))?;
     client_class.define_method("trace", method!(Client::trace, -1))?;
     client_class.define_method("patch", method!(Client::patch, -1))?;
+    client_class.define_method("request_async", method!(Client::request_async, -1))?;
+    client_class.define_method("get_async", method!(Client::get_async, -1))?;
+    client_class.define_method("post_async", method!(Client::post_async, -1))?;
+    client_class.define_method("put_async", method!(Client::put_async, -1))?;
+    client_class.define_method("delete_async", method!(Client::delete_async, -1))?;
+    client_class.define_method("head_async", method!(Client::head_async, -1))?;
+    client_class.define_method("options_async", method!(Client::options_async, -1))?;
+    client_class.define_method("trace_async", method!(Client::trace_async, -1))?;
+    client_class.define_method("patch_async", method!(Client::patch_async, -1))?;
+    client_class.define_method("download", method!(Client::download, -1))?;
+    client_class.define_method("parallel", method!(Client::parallel, -1))?;
+    client_class.define_method("websocket", method!(Client::websocket, -1))?;
+    client_class.define_method("sse", method!(Client::sse, -1))?;
+    client_class.define_method("use", method!(Client::use_middleware, 1))?;
+    client_class.define_method("dns_cache_stats", method!(Client::dns_cache_stats, 0))?;
 
     resp::include(ruby, gem_module)?;
+    timings::include(ruby, gem_module)?;
     body::include(ruby, gem_module)?;
+    ws::include(ruby, gem_module)?;
+    sse::include(ruby, gem_module)?;
+    pending::include(ruby, gem_module)?;
+    redirect::include(ruby, gem_module)?;
+    middleware::include(ruby, gem_module)?;
+    proxy::include(ruby, gem_module)?;
     Ok(())
 }
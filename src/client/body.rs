@@ -1,51 +1,127 @@
 mod form;
 mod json;
+mod multipart;
 mod stream;
 
+use std::{
+    io::SeekFrom,
+    path::{Path, PathBuf},
+};
+
 use bytes::Bytes;
-use futures_util::StreamExt;
 use magnus::{
     Error, Module, Object, RModule, RString, Ruby, TryConvert, Value, function, method,
-    typed_data::Obj,
+    typed_data::Obj, value::ReprValue,
 };
+use tokio::io::AsyncSeekExt;
 
 pub use self::{
     form::Form,
     json::Json,
+    multipart::{Multipart, MultipartBody, Part},
     stream::{BodyReceiver, BodySender, ReceiverStream},
 };
 
 /// Represents the body of an HTTP request.
-/// Supports text, bytes, and streaming bodies (Proc/Enumerator).
+/// Supports text, bytes, files, Ruby IO objects and streaming bodies.
 pub enum Body {
     /// Static bytes body
     Bytes(Bytes),
-    /// Streaming body
-    Stream(ReceiverStream<Bytes>),
+    /// Streaming body, with its length when known
+    Stream(ReceiverStream<std::io::Result<Bytes>>, Option<u64>),
+    /// File body, opened and read on the runtime from the given offset
+    File(PathBuf, u64),
+}
+
+impl Body {
+    /// Copy the body so it can be sent again, unless it is a one-shot stream.
+    ///
+    /// Files are reopened for every attempt, so they can be replayed too.
+    pub fn try_clone(&self) -> Option<Self> {
+        match self {
+            Body::Bytes(bytes) => Some(Body::Bytes(bytes.clone())),
+            Body::File(path, offset) => Some(Body::File(path.clone(), *offset)),
+            Body::Stream(..) => None,
+        }
+    }
+
+    /// Convert into a [`wreq::Body`] along with its length, if known.
+    ///
+    /// Files are opened here so that the disk I/O happens on the runtime without the GVL.
+    pub async fn into_body(self) -> std::io::Result<(wreq::Body, Option<u64>)> {
+        match self {
+            Body::Bytes(b) => Ok((wreq::Body::from(b), None)),
+            Body::Stream(stream, length) => Ok((wreq::Body::wrap_stream(stream), length)),
+            Body::File(path, offset) => {
+                let (file, length) = open_file(&path, offset).await?;
+                Ok((wreq::Body::from(file), Some(length)))
+            }
+        }
+    }
+}
+
+/// Open the file at `path` positioned at `offset`, along with the number of bytes left.
+async fn open_file(path: &Path, offset: u64) -> std::io::Result<(tokio::fs::File, u64)> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let length = file.metadata().await?.len().saturating_sub(offset);
+    if offset > 0 {
+        file.seek(SeekFrom::Start(offset)).await?;
+    }
+    Ok((file, length))
 }
 
 impl TryConvert for Body {
     fn try_convert(val: Value) -> Result<Self, Error> {
+        let ruby = Ruby::get_with(val);
+
         if let Ok(s) = RString::try_convert(val) {
             return Ok(Body::Bytes(s.to_bytes()));
         }
 
-        let obj = Obj::<BodySender>::try_convert(val)?;
-        let stream = ReceiverStream::try_from(&*obj)?;
-        Ok(Body::Stream(stream))
+        if let Ok(obj) = Obj::<BodySender>::try_convert(val) {
+            let stream = ReceiverStream::try_from(&*obj)?;
+            return Ok(Body::Stream(stream, None));
+        }
+
+        // File and Pathname both respond to `to_path`; an open File is sent from its
+        // current position, as reading it would.
+        if val.respond_to("to_path", false)? {
+            let path: String = val.funcall("to_path", ())?;
+            let offset: u64 = if val.respond_to("pos", false)? {
+                val.funcall("pos", ())?
+            } else {
+                0
+            };
+            return Ok(Body::File(PathBuf::from(path), offset));
+        }
+
+        if val.respond_to("readpartial", false)? || val.respond_to("read", false)? {
+            let length = io_remaining_length(val);
+            return Ok(Body::Stream(ReceiverStream::from_io(&ruby, val), length));
+        }
+
+        Err(Error::new(
+            ruby.exception_type_error(),
+            format!(
+                "body must be a String, Pathname, File, IO or Wreq::BodySender, got {}",
+                val.class()
+            ),
+        ))
     }
 }
 
-impl From<Body> for wreq::Body {
-    fn from(body: Body) -> Self {
-        match body {
-            Body::Bytes(b) => wreq::Body::from(b),
-            Body::Stream(stream) => {
-                let try_stream = stream.map(Ok::<Bytes, std::io::Error>);
-                wreq::Body::wrap_stream(try_stream)
-            }
-        }
+/// Get the number of bytes left in a Ruby IO object, if it can report its size.
+fn io_remaining_length(io: Value) -> Option<u64> {
+    if !io.respond_to("size", false).ok()? {
+        return None;
     }
+    let size: u64 = io.funcall("size", ()).ok()?;
+    let pos: u64 = if io.respond_to("pos", false).ok()? {
+        io.funcall("pos", ()).ok()?
+    } else {
+        0
+    };
+    size.checked_sub(pos)
 }
 
 pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
@@ -53,5 +129,13 @@ pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
     sender_class.define_singleton_method("new", function!(BodySender::new, -1))?;
     sender_class.define_method("push", method!(BodySender::push, 1))?;
     sender_class.define_method("close", magnus::method!(BodySender::close, 0))?;
+
+    let part_class = gem_module.define_class("Part", ruby.class_object())?;
+    part_class.define_singleton_method("new", function!(Part::new, -1))?;
+    part_class.define_method("name", method!(Part::name, 0))?;
+
+    let multipart_class = gem_module.define_class("Multipart", ruby.class_object())?;
+    multipart_class.define_singleton_method("new", function!(Multipart::new, -1))?;
+    multipart_class.define_method("add", method!(Multipart::add, 1))?;
     Ok(())
 }
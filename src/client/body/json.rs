@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 /// Represents a JSON value for HTTP requests.
 /// Supports objects, arrays, numbers, strings, booleans, and null.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Json {
     Object(IndexMap<String, Json>),
@@ -0,0 +1,224 @@
+use std::{path::PathBuf, sync::RwLock};
+
+use bytes::Bytes;
+use magnus::{
+    Error, RHash, RString, Ruby, TryConvert, Value, encoding::EncodingCapable, typed_data::Obj,
+    value::ReprValue,
+};
+use wreq::header::HeaderMap;
+
+use super::{Body, open_file, stream::ReceiverStream};
+use crate::{
+    error::{RequestError, memory_error},
+    extractor::Extractor,
+};
+
+/// A `multipart/form-data` request body.
+#[magnus::wrap(class = "Wreq::Multipart", free_immediately, size)]
+pub struct Multipart(RwLock<Option<Vec<InnerPart>>>);
+
+/// A single field of a `multipart/form-data` request body.
+#[magnus::wrap(class = "Wreq::Part", free_immediately, size)]
+pub struct Part(RwLock<Option<InnerPart>>);
+
+/// The parts taken out of a [`Multipart`], ready to be sent.
+pub struct MultipartBody(Vec<InnerPart>);
+
+struct InnerPart {
+    name: String,
+    value: PartValue,
+    filename: Option<String>,
+    mime: Option<String>,
+    length: Option<u64>,
+    headers: Option<HeaderMap>,
+}
+
+/// Represents the value of a multipart field.
+enum PartValue {
+    /// UTF-8 text value
+    Text(String),
+    /// Binary value
+    Bytes(Bytes),
+    /// File read from disk on the runtime, from the given offset
+    File(PathBuf, u64),
+    /// Streaming value (BodySender or Ruby IO), with its length when known
+    Stream(ReceiverStream<std::io::Result<Bytes>>, Option<u64>),
+}
+
+// ===== impl Part =====
+
+impl Part {
+    /// Ruby: `Wreq::Part.new(name, value, filename: nil, mime: nil, length: nil, headers: nil)`
+    pub fn new(ruby: &Ruby, args: &[Value]) -> Result<Self, Error> {
+        let args = magnus::scan_args::scan_args::<(String, Value), (), (), (), RHash, ()>(args)?;
+        #[allow(clippy::type_complexity)]
+        let keywords: magnus::scan_args::KwArgs<
+            (),
+            (Option<String>, Option<String>, Option<u64>, Option<Value>),
+            (),
+        > = magnus::scan_args::get_kwargs(
+            args.keywords,
+            &[],
+            &["filename", "mime", "length", "headers"],
+        )?;
+
+        let (name, value) = args.required;
+        let headers = Extractor::<HeaderMap>::try_convert(args.keywords.as_value())?.into_inner();
+
+        Ok(Part(RwLock::new(Some(InnerPart {
+            name,
+            value: PartValue::new(ruby, value)?,
+            filename: keywords.optional.0,
+            mime: keywords.optional.1,
+            length: keywords.optional.2,
+            headers,
+        }))))
+    }
+
+    /// Get the field name of the part.
+    pub fn name(&self) -> Result<String, Error> {
+        self.0
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|part| part.name.clone())
+            .ok_or_else(memory_error)
+    }
+}
+
+// ===== impl PartValue =====
+
+impl PartValue {
+    fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        if let Some(s) = RString::from_value(value) {
+            return if s.enc_get() == ruby.ascii8bit_encindex() {
+                Ok(PartValue::Bytes(s.to_bytes()))
+            } else {
+                Ok(PartValue::Text(s.to_string()?))
+            };
+        }
+
+        match Body::try_convert(value)? {
+            Body::Bytes(bytes) => Ok(PartValue::Bytes(bytes)),
+            Body::Stream(stream, length) => Ok(PartValue::Stream(stream, length)),
+            Body::File(path, offset) => Ok(PartValue::File(path, offset)),
+        }
+    }
+}
+
+// ===== impl Multipart =====
+
+impl Multipart {
+    /// Ruby: `Wreq::Multipart.new(*parts)`
+    pub fn new(args: &[Value]) -> Result<Self, Error> {
+        let mut parts = Vec::with_capacity(args.len());
+        for arg in args {
+            let part = Obj::<Part>::try_convert(*arg)?;
+            parts.push(part.0.write().unwrap().take().ok_or_else(memory_error)?);
+        }
+        Ok(Multipart(RwLock::new(Some(parts))))
+    }
+
+    /// Ruby: `add(part)` to append a part to the form.
+    pub fn add(&self, part: &Part) -> Result<(), Error> {
+        let part = part.0.write().unwrap().take().ok_or_else(memory_error)?;
+        self.0
+            .write()
+            .unwrap()
+            .as_mut()
+            .ok_or_else(memory_error)?
+            .push(part);
+        Ok(())
+    }
+}
+
+impl TryFrom<&Multipart> for MultipartBody {
+    type Error = magnus::Error;
+
+    fn try_from(multipart: &Multipart) -> Result<Self, Self::Error> {
+        multipart
+            .0
+            .write()
+            .unwrap()
+            .take()
+            .map(MultipartBody)
+            .ok_or_else(memory_error)
+    }
+}
+
+// ===== impl InnerPart =====
+
+impl InnerPart {
+    /// Copy the part so it can be sent again, unless its value is a one-shot stream.
+    fn try_clone(&self) -> Option<Self> {
+        let value = match &self.value {
+            PartValue::Text(text) => PartValue::Text(text.clone()),
+            PartValue::Bytes(bytes) => PartValue::Bytes(bytes.clone()),
+            PartValue::File(path, offset) => PartValue::File(path.clone(), *offset),
+            PartValue::Stream(..) => return None,
+        };
+        Some(Self {
+            name: self.name.clone(),
+            value,
+            filename: self.filename.clone(),
+            mime: self.mime.clone(),
+            length: self.length,
+            headers: self.headers.clone(),
+        })
+    }
+}
+
+// ===== impl MultipartBody =====
+
+impl MultipartBody {
+    /// Copy the form so it can be sent again, unless it contains a one-shot stream.
+    pub fn try_clone(&self) -> Option<Self> {
+        self.0
+            .iter()
+            .map(InnerPart::try_clone)
+            .collect::<Option<Vec<_>>>()
+            .map(MultipartBody)
+    }
+
+    /// Build the [`wreq::multipart::Form`], opening any file parts on the runtime.
+    pub async fn into_form(self) -> Result<wreq::multipart::Form, RequestError> {
+        let mut form = wreq::multipart::Form::new();
+        for part in self.0 {
+            let mut inner = match part.value {
+                PartValue::Text(text) => wreq::multipart::Part::text(text),
+                PartValue::Bytes(bytes) => wreq::multipart::Part::bytes(Vec::from(bytes)),
+                PartValue::File(path, offset) => {
+                    let (file, length) = open_file(&path, offset).await?;
+                    // Named and typed after the file, unless `filename:` or `mime:` say otherwise.
+                    let mime = mime_guess::from_path(&path).first_or_octet_stream();
+                    let inner = wreq::multipart::Part::stream_with_length(file, length)
+                        .mime_str(mime.as_ref())?;
+                    match path.file_name() {
+                        Some(name) => inner.file_name(name.to_string_lossy().into_owned()),
+                        None => inner,
+                    }
+                }
+                PartValue::Stream(stream, length) => {
+                    let body = wreq::Body::wrap_stream(stream);
+                    match part.length.or(length) {
+                        Some(length) => wreq::multipart::Part::stream_with_length(body, length),
+                        None => wreq::multipart::Part::stream(body),
+                    }
+                }
+            };
+
+            if let Some(filename) = part.filename {
+                inner = inner.file_name(filename);
+            }
+            if let Some(mime) = part.mime {
+                inner = inner.mime_str(&mime)?;
+            }
+            if let Some(headers) = part.headers {
+                inner = inner.headers(headers);
+            }
+
+            form = form.part(part.name, inner);
+        }
+        Ok(form)
+    }
+}
@@ -6,17 +6,21 @@ use std::{
 
 use bytes::Bytes;
 use futures_util::{Stream, StreamExt, TryFutureExt};
-use magnus::{Error, RString, TryConvert, Value};
+use magnus::{Error, RString, Ruby, TryConvert, Value, value::Opaque, value::ReprValue};
 use tokio::sync::{
     Mutex,
     mpsc::{self},
+    oneshot,
 };
 
 use crate::{
-    error::{memory_error, mpsc_send_error_to_magnus},
+    error::{memory_error, mpsc_send_error_to_magnus, wreq_error_to_magnus},
     rt,
 };
 
+/// The chunk size used when draining a Ruby IO object into a request body.
+const IO_CHUNK_SIZE: usize = 64 * 1024;
+
 /// A receiver for streaming HTTP response bodies.
 pub struct BodyReceiver(Mutex<Pin<Box<dyn Stream<Item = wreq::Result<Bytes>> + Send>>>);
 
@@ -25,8 +29,8 @@ pub struct BodyReceiver(Mutex<Pin<Box<dyn Stream<Item = wreq::Result<Bytes>> + S
 pub struct BodySender(RwLock<InnerBodySender>);
 
 struct InnerBodySender {
-    tx: Option<mpsc::Sender<Bytes>>,
-    rx: Option<mpsc::Receiver<Bytes>>,
+    tx: Option<mpsc::Sender<std::io::Result<Bytes>>>,
+    rx: Option<mpsc::Receiver<std::io::Result<Bytes>>>,
 }
 
 // ===== impl BodyReceiver =====
@@ -37,21 +41,12 @@ impl BodyReceiver {
     pub fn new(stream: impl Stream<Item = wreq::Result<Bytes>> + Send + 'static) -> BodyReceiver {
         BodyReceiver(Mutex::new(Box::pin(stream)))
     }
-}
 
-impl Iterator for BodyReceiver {
-    type Item = Bytes;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        rt::maybe_block_on(async {
-            self.0
-                .lock()
-                .await
-                .as_mut()
-                .next()
-                .await
-                .and_then(|r| r.ok())
-        })
+    /// Wait for the next chunk, returning `None` at the end of the body.
+    pub fn recv(&self) -> Result<Option<Bytes>, Error> {
+        rt::try_block_on(async { Ok(self.0.lock().await.as_mut().next().await) })?
+            .transpose()
+            .map_err(wreq_error_to_magnus)
     }
 }
 
@@ -78,7 +73,7 @@ impl BodySender {
         let bytes = data.to_bytes();
         let inner = rb_self.0.read().unwrap();
         if let Some(ref tx) = inner.tx {
-            rt::try_block_on(tx.send(bytes).map_err(mpsc_send_error_to_magnus))?;
+            rt::try_block_on(tx.send(Ok(bytes)).map_err(mpsc_send_error_to_magnus))?;
         }
         Ok(())
     }
@@ -91,7 +86,7 @@ impl BodySender {
     }
 }
 
-impl TryFrom<&BodySender> for ReceiverStream<Bytes> {
+impl TryFrom<&BodySender> for ReceiverStream<std::io::Result<Bytes>> {
     type Error = magnus::Error;
 
     fn try_from(sender: &BodySender) -> Result<Self, Self::Error> {
@@ -106,16 +101,77 @@ impl TryFrom<&BodySender> for ReceiverStream<Bytes> {
     }
 }
 
+impl ReceiverStream<std::io::Result<Bytes>> {
+    /// Create a [`ReceiverStream`] fed by a Ruby thread that drains the given IO object.
+    ///
+    /// The thread reads with `readpartial` (or `read`) and blocks without the GVL
+    /// while the channel is full, so the upload is never buffered in Ruby memory.
+    /// It only starts reading once the stream is first polled, so a body that is never
+    /// sent leaves the IO untouched. A failed read ends the stream with the error.
+    pub fn from_io(ruby: &Ruby, io: Value) -> Self {
+        let (tx, rx) = mpsc::channel(8);
+        let (start, started) = oneshot::channel();
+        let io = Opaque::from(io);
+        ruby.thread_create_from_fn(move |ruby| {
+            // The sender is dropped with a stream that was never polled.
+            if !matches!(
+                rt::try_block_on(async { Ok(started.await.is_ok()) }),
+                Ok(true)
+            ) {
+                return;
+            }
+            let io = ruby.get_inner(io);
+            loop {
+                let chunk = match read_io_chunk(ruby, io) {
+                    Ok(Some(chunk)) => Ok(chunk),
+                    Ok(None) => break,
+                    Err(err) => Err(std::io::Error::other(format!(
+                        "failed to read the request body: {err}"
+                    ))),
+                };
+                let failed = chunk.is_err();
+                let sent = rt::try_block_on(async { Ok(tx.send(chunk).await.is_ok()) });
+                if failed || !matches!(sent, Ok(true)) {
+                    break;
+                }
+            }
+        });
+        ReceiverStream {
+            inner: rx,
+            start: Some(start),
+        }
+    }
+}
+
+/// Read the next chunk from a Ruby IO object, returning `None` at EOF.
+fn read_io_chunk(ruby: &Ruby, io: Value) -> Result<Option<Bytes>, Error> {
+    let chunk: Option<RString> = if io.respond_to("readpartial", false)? {
+        match io.funcall("readpartial", (IO_CHUNK_SIZE,)) {
+            Ok(chunk) => chunk,
+            Err(err) if err.is_kind_of(ruby.exception_eof_error()) => None,
+            Err(err) => return Err(err),
+        }
+    } else {
+        io.funcall("read", (IO_CHUNK_SIZE,))?
+    };
+    Ok(chunk.map(|chunk| chunk.to_bytes()))
+}
+
 /// A wrapper around [`tokio::sync::mpsc::Receiver`] that implements [`Stream`].
 pub struct ReceiverStream<T> {
     inner: mpsc::Receiver<T>,
+    /// Tells the producer to start on the first poll, for producers started lazily.
+    start: Option<oneshot::Sender<()>>,
 }
 
 impl<T> ReceiverStream<T> {
     /// Create a new [`ReceiverStream`].
     #[inline]
     pub fn new(recv: mpsc::Receiver<T>) -> Self {
-        Self { inner: recv }
+        Self {
+            inner: recv,
+            start: None,
+        }
     }
 }
 
@@ -124,6 +180,9 @@ impl<T> Stream for ReceiverStream<T> {
 
     #[inline]
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(start) = self.start.take() {
+            let _ = start.send(());
+        }
         self.inner.poll_recv(cx)
     }
 
@@ -0,0 +1,65 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use tower::{Layer, Service};
+
+use crate::client::timings;
+
+/// Wraps the connector to time each connection opened for a request, from the lookup
+/// to the end of its TLS handshake.
+///
+/// Pooled connections skip the connector, but were timed when they were opened.
+#[derive(Clone, Default)]
+pub struct ConnectLayer;
+
+/// The connector wrapped by [`ConnectLayer`].
+#[derive(Clone)]
+pub struct Connect<S> {
+    inner: S,
+}
+
+// ===== impl ConnectLayer =====
+
+impl<S> Layer<S> for ConnectLayer {
+    type Service = Connect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Connect { inner }
+    }
+}
+
+impl<S, R> Service<R> for Connect<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let recorder = timings::current();
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.connecting();
+        }
+        let started = Instant::now();
+        let connecting = self.inner.call(req);
+        Box::pin(async move {
+            let conn = connecting.await?;
+            if let Some(recorder) = recorder {
+                recorder.connect(started);
+            }
+            Ok(conn)
+        })
+    }
+}
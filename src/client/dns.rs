@@ -0,0 +1,515 @@
+mod cache;
+mod doh;
+
+use std::{
+    collections::HashMap,
+    future::{self, Future},
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use magnus::{
+    Error, RArray, RHash, Ruby, Symbol, TryConvert, Value,
+    gc::Marker,
+    value::{Opaque, ReprValue},
+};
+use wreq::{
+    Uri,
+    dns::{Addrs, Name, Resolve, Resolving},
+};
+
+use self::{cache::Cache, doh::Doh};
+use crate::{
+    client::timings,
+    error::{CallbackError, PhaseElapsed, TimeoutPhase},
+    gvl,
+};
+
+tokio::task_local! {
+    /// The lookup timeout and the port of the request being sent.
+    static TARGET: Target;
+}
+
+/// Static addresses for host names, set with the `resolve:` option.
+///
+/// Entries are keyed by host name, or by host name and port to only apply to that port,
+/// like `curl --resolve`.
+#[derive(Clone, Default)]
+pub struct Overrides(Arc<HashMap<(String, Option<u16>), Vec<IpAddr>>>);
+
+/// The address family to connect with, set with the `ip_family:` option.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IpFamily {
+    V4,
+    V6,
+    Any,
+}
+
+/// The DNS settings of a single request.
+#[derive(Clone, Default)]
+pub struct Settings {
+    pub overrides: Option<Overrides>,
+    pub ip_family: Option<IpFamily>,
+    pub timeout: Option<Duration>,
+}
+
+/// The per-request DNS settings that decide which addresses a request connects to.
+///
+/// Requests with equal keys send on the same client, so they share its connections.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PoolKey {
+    overrides: Vec<((String, Option<u16>), Vec<IpAddr>)>,
+    ip_family: Option<IpFamily>,
+}
+
+/// Where the request being sent connects to.
+struct Target {
+    timeout: Option<Duration>,
+    port: u16,
+}
+
+/// Resolves host names with the static overrides, and with the resolver set with the
+/// `dns_resolver:` option otherwise.
+///
+/// The addresses only replace the DNS lookup, so the URL, the `Host` header and the
+/// TLS server name are left untouched.
+#[derive(Clone, Default)]
+pub struct Resolver {
+    /// The `resolve:` overrides of the requests sent with this resolver.
+    request_overrides: Overrides,
+    overrides: Overrides,
+    ip_family: Option<IpFamily>,
+    timeout: Option<Duration>,
+    source: Source,
+    cache: Option<Arc<Cache>>,
+}
+
+/// Where host names without overrides are looked up.
+#[derive(Clone, Default)]
+enum Source {
+    /// The system resolver (`getaddrinfo`).
+    #[default]
+    System,
+    /// A Ruby object responding to `call(host)`.
+    Callable(Opaque<Value>),
+    /// A DNS-over-HTTPS endpoint.
+    Doh(Doh),
+}
+
+/// The addresses found for a host name.
+pub struct Answer {
+    addrs: Vec<IpAddr>,
+    /// How long the answer may be cached, if the source says so.
+    ttl: Option<Duration>,
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+type Lookup = Pin<Box<dyn Future<Output = Result<Answer, BoxError>> + Send>>;
+
+// ===== impl Overrides =====
+
+impl Overrides {
+    /// Parse the `resolve:` option, a Hash of `"host"` or `"host:port"` to one address
+    /// or an Array of them.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        let hash = RHash::try_convert(value)?;
+        let mut overrides = HashMap::new();
+        for (key, value) in hash.to_hash_map::<String, Value>()? {
+            let addrs = match RArray::from_value(value) {
+                Some(addrs) => addrs.to_vec::<String>()?,
+                None => vec![String::try_convert(value)?],
+            };
+            if addrs.is_empty() {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("resolve addresses for {key} must not be empty"),
+                ));
+            }
+            let addrs = addrs
+                .iter()
+                .map(|addr| {
+                    addr.trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .parse::<IpAddr>()
+                        .map_err(|_| {
+                            Error::new(
+                                ruby.exception_arg_error(),
+                                format!("invalid resolve address for {key}: {addr}"),
+                            )
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            overrides.insert(parse_key(&key), addrs);
+        }
+        Ok(Self(Arc::new(overrides)))
+    }
+
+    /// Look up the addresses for `host`, preferring an entry for the given port.
+    fn get(&self, host: &str, port: Option<u16>) -> Option<&[IpAddr]> {
+        port.and_then(|port| self.0.get(&(host.to_owned(), Some(port))))
+            .or_else(|| self.0.get(&(host.to_owned(), None)))
+            .map(Vec::as_slice)
+    }
+}
+
+/// Split a `resolve:` key into its normalized host name and optional port.
+fn parse_key(key: &str) -> (String, Option<u16>) {
+    let (host, port) = match key.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+            match port.parse::<u16>() {
+                Ok(port) => (host, Some(port)),
+                Err(_) => (key, None),
+            }
+        }
+        _ => (key, None),
+    };
+    (normalize(host), port)
+}
+
+/// Normalize a host name, as DNS names are case-insensitive and may be fully qualified.
+fn normalize(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+// ===== impl Settings =====
+
+impl Settings {
+    /// Get the key of the connections for these settings, or `None` when the request
+    /// resolves host names like the client and can use its connections.
+    pub fn pool_key(&self) -> Option<PoolKey> {
+        if self.overrides.is_none() && self.ip_family.is_none() {
+            return None;
+        }
+        let mut overrides = self
+            .overrides
+            .iter()
+            .flat_map(|overrides| overrides.0.iter())
+            .map(|(key, addrs)| (key.clone(), addrs.clone()))
+            .collect::<Vec<_>>();
+        overrides.sort();
+        Some(PoolKey {
+            overrides,
+            ip_family: self.ip_family,
+        })
+    }
+}
+
+// ===== impl IpFamily =====
+
+impl IpFamily {
+    /// Parse the `ip_family:` option, given as `:v4`, `:v6` or `:any`.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        let name = match Symbol::from_value(value) {
+            Some(symbol) => symbol.name()?.into_owned(),
+            None => String::try_convert(value)?,
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "v4" | "ipv4" => Ok(Self::V4),
+            "v6" | "ipv6" => Ok(Self::V6),
+            "any" => Ok(Self::Any),
+            _ => Err(Error::new(
+                ruby.exception_arg_error(),
+                format!("invalid ip_family: {name}, expected one of :v4, :v6, :any"),
+            )),
+        }
+    }
+
+    /// Keep the addresses of this family.
+    fn filter(self, host: &str, addrs: Vec<IpAddr>) -> Result<Vec<IpAddr>, String> {
+        let name = match self {
+            Self::V4 => "IPv4",
+            Self::V6 => "IPv6",
+            Self::Any => return Ok(addrs),
+        };
+        let addrs = addrs
+            .into_iter()
+            .filter(|addr| addr.is_ipv4() == (self == Self::V4))
+            .collect::<Vec<_>>();
+        if addrs.is_empty() {
+            return Err(format!("no {name} addresses found for {host}"));
+        }
+        Ok(addrs)
+    }
+}
+
+/// Run `future`, which sends a request to `uri`, with the DNS `timeout` of the request.
+///
+/// Host names are resolved while the connection is opened, within the future, so the
+/// resolver finds the port and timeout of the request there.
+pub async fn scope<F: Future>(timeout: Option<Duration>, uri: &Uri, future: F) -> F::Output {
+    let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+        Some("https" | "wss") => 443,
+        _ => 80,
+    });
+    TARGET.scope(Target { timeout, port }, future).await
+}
+
+// ===== impl Resolver =====
+
+impl Resolver {
+    /// Parse the `dns_resolver:` option.
+    ///
+    /// Accepts `:system`, an object responding to `call(host)`, or a Hash with either
+    /// `call:` or `doh:` (a DNS-over-HTTPS endpoint URL), and optionally `cache:`.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        if let Some(symbol) = Symbol::from_value(value) {
+            return match symbol.name()?.as_ref() {
+                "system" => Ok(Self::default()),
+                name => Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("unknown dns_resolver :{name}"),
+                )),
+            };
+        }
+        if RHash::from_value(value).is_none() && value.respond_to("call", false)? {
+            return Ok(Self {
+                source: Source::Callable(value.into()),
+                ..Self::default()
+            });
+        }
+
+        let hash = RHash::try_convert(value)?;
+        let callable = get(ruby, hash, "call");
+        let doh = get(ruby, hash, "doh");
+        let source = match (callable, doh) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    "dns_resolver call and doh cannot be combined",
+                ));
+            }
+            (Some(callable), None) if callable.respond_to("call", false)? => {
+                Source::Callable(callable.into())
+            }
+            (Some(_), None) => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    "dns_resolver call must respond to call",
+                ));
+            }
+            (None, Some(url)) => Source::Doh(Doh::new(ruby, String::try_convert(url)?)?),
+            (None, None) => Source::System,
+        };
+        let cache = match get(ruby, hash, "cache") {
+            Some(cache) => Cache::new(ruby, cache)?.map(Arc::new),
+            None => None,
+        };
+        Ok(Self {
+            source,
+            cache,
+            ..Self::default()
+        })
+    }
+
+    /// Use the client's `resolve:` overrides before looking up host names.
+    pub fn with_overrides(mut self, overrides: Option<Overrides>) -> Self {
+        self.overrides = overrides.unwrap_or_default();
+        self
+    }
+
+    /// Only connect to addresses of the client's `ip_family:`.
+    pub fn with_ip_family(mut self, ip_family: Option<IpFamily>) -> Self {
+        self.ip_family = ip_family;
+        self
+    }
+
+    /// Give up on lookups taking longer than the client's `dns_timeout:`.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Copy the resolver for requests with their own `resolve:` or `ip_family:`, which
+    /// take precedence over the client's.
+    pub fn for_request(&self, settings: &Settings) -> Self {
+        Self {
+            request_overrides: settings.overrides.clone().unwrap_or_default(),
+            ip_family: settings.ip_family.or(self.ip_family),
+            ..self.clone()
+        }
+    }
+
+    /// Returns `true` if host names are looked up by a Ruby object, which can only be
+    /// called on the thread sending the request.
+    pub fn calls_ruby(&self) -> bool {
+        matches!(self.source, Source::Callable(_))
+    }
+
+    /// Mark the Ruby resolver object so it is not collected while the client is alive.
+    pub fn mark(&self, marker: &Marker) {
+        if let Source::Callable(callable) = &self.source {
+            marker.mark(*callable);
+        }
+    }
+
+    /// Get the counters of the cache, or `None` without one.
+    pub fn cache_stats(&self, ruby: &Ruby) -> Result<Option<RHash>, Error> {
+        let Some(cache) = self.cache.as_ref() else {
+            return Ok(None);
+        };
+        let stats = cache.stats();
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("hits"), stats.hits)?;
+        hash.aset(ruby.to_symbol("misses"), stats.misses)?;
+        hash.aset(ruby.to_symbol("entries"), stats.entries)?;
+        Ok(Some(hash))
+    }
+
+    /// Look up the overrides for `host`; per-request entries take precedence.
+    fn overridden(&self, host: &str) -> Option<Vec<IpAddr>> {
+        let port = TARGET.try_with(|target| target.port).ok();
+        self.request_overrides
+            .get(host, port)
+            .or_else(|| self.overrides.get(host, port))
+            .map(<[IpAddr]>::to_vec)
+    }
+}
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let Some(recorder) = timings::current() else {
+            return self.lookup(name);
+        };
+        let started = Instant::now();
+        let resolving = self.lookup(name);
+        Box::pin(async move {
+            let result = resolving.await;
+            recorder.dns(started);
+            result
+        })
+    }
+}
+
+impl Resolver {
+    /// Resolve `name` with the overrides, the cache or the source, in that order.
+    fn lookup(&self, name: Name) -> Resolving {
+        let host = normalize(name.as_str());
+        let ip_family = self.ip_family.unwrap_or(IpFamily::Any);
+        // The port is replaced with the one of the URL when connecting.
+        let finish = move |host: &str,
+                           result: Result<Vec<IpAddr>, BoxError>|
+              -> Result<Addrs, BoxError> {
+            result
+                .and_then(|addrs| ip_family.filter(host, addrs).map_err(Into::into))
+                .map(|addrs| Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0))) as Addrs)
+                .map_err(|err| io::Error::other(err).into())
+        };
+
+        if let Some(addrs) = self.overridden(&host) {
+            return Box::pin(future::ready(finish(&host, Ok(addrs))));
+        }
+        if let Some(cached) = self.cache.as_ref().and_then(|cache| cache.get(&host)) {
+            return Box::pin(future::ready(finish(&host, cached.map_err(Into::into))));
+        }
+
+        let timeout = TARGET
+            .try_with(|target| target.timeout)
+            .ok()
+            .flatten()
+            .or(self.timeout);
+        let lookup = self.source.lookup(host.clone());
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let result = match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, lookup).await {
+                    Ok(result) => result,
+                    // Not cached: the next lookup may well be faster.
+                    Err(_) => return Err(Box::new(PhaseElapsed(TimeoutPhase::Dns)) as BoxError),
+                },
+                None => lookup.await,
+            };
+            if let Some(cache) = cache {
+                // An exception raised by a Ruby resolver is raised again, not remembered.
+                if !result.as_ref().is_err_and(|err| err.is::<CallbackError>()) {
+                    cache.insert(host.clone(), &result);
+                }
+            }
+            finish(&host, result.map(|answer| answer.addrs))
+        })
+    }
+}
+
+// ===== impl Source =====
+
+impl Source {
+    /// Look up the addresses of `host`.
+    ///
+    /// Ruby resolvers are called right away, on the thread sending the request.
+    fn lookup(&self, host: String) -> Lookup {
+        match self {
+            Source::System => system_lookup(host),
+            Source::Callable(callable) => match call(*callable, &host) {
+                Ok(Some(addrs)) => Box::pin(future::ready(Ok(Answer { addrs, ttl: None }))),
+                Ok(None) => system_lookup(host),
+                Err(err) => Box::pin(future::ready(Err(err))),
+            },
+            Source::Doh(doh) => {
+                let doh = doh.clone();
+                Box::pin(async move { Ok(doh.lookup(&host).await?) })
+            }
+        }
+    }
+}
+
+/// Look up `host` with the system resolver.
+fn system_lookup(host: String) -> Lookup {
+    Box::pin(async move {
+        let addrs = tokio::net::lookup_host((host.as_str(), 0))
+            .await
+            .map_err(|err| format!("failed to look up {host}: {err}"))?;
+        Ok(Answer {
+            addrs: addrs.map(|addr| addr.ip()).collect(),
+            ttl: None,
+        })
+    })
+}
+
+/// Call a Ruby resolver, which returns an address, an Array of them, or nil to use the
+/// system resolver.
+///
+/// An exception raised by the resolver is kept in a [`CallbackError`], to be raised as
+/// is by the request.
+fn call(callable: Opaque<Value>, host: &str) -> Result<Option<Vec<IpAddr>>, BoxError> {
+    let raised = |err| Box::new(CallbackError::new(err)) as BoxError;
+    gvl::with_gvl(|| {
+        let ruby = ruby!();
+        let value = ruby
+            .get_inner(callable)
+            .funcall::<_, _, Value>("call", (host,))
+            .map_err(raised)?;
+        if value.is_nil() {
+            return Ok(None);
+        }
+        let values = match RArray::from_value(value) {
+            Some(values) => values.into_iter().collect(),
+            None => vec![value],
+        };
+        if values.is_empty() {
+            return Err(format!("dns_resolver returned no addresses for {host}").into());
+        }
+        values
+            .into_iter()
+            .map(|value| -> Result<IpAddr, BoxError> {
+                let addr = value.to_s().map_err(raised)?;
+                addr.parse::<IpAddr>().map_err(|_| {
+                    format!("dns_resolver returned an invalid address for {host}: {addr}").into()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    })
+    .unwrap_or_else(|| Err("dns_resolver callables cannot run in background requests".into()))
+}
+
+/// Read an optional setting from the resolver hash.
+fn get(ruby: &Ruby, hash: RHash, key: &str) -> Option<Value> {
+    hash.get(ruby.to_symbol(key))
+        .filter(|value| !value.is_nil())
+}
@@ -0,0 +1,164 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use magnus::{Error, RHash, Ruby, TryConvert, Value, value::ReprValue};
+
+use super::{Answer, BoxError};
+
+/// How long answers without a TTL of their own are cached.
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// The default maximum number of cached host names.
+const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+/// A cache of lookups, set with the `cache:` setting of the `dns_resolver:` option.
+pub struct Cache {
+    /// How long answers are cached, instead of their own TTL.
+    ttl: Option<Duration>,
+    /// How long failed lookups are cached; zero disables negative caching.
+    negative_ttl: Duration,
+    /// The maximum number of cached host names.
+    max_entries: usize,
+    entries: Mutex<HashMap<String, Entry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// A cached lookup.
+struct Entry {
+    result: Result<Vec<IpAddr>, String>,
+    expires: Instant,
+}
+
+/// A snapshot of the cache counters.
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl Cache {
+    /// Parse the `cache:` setting, `true` for the defaults or a Hash with `ttl:`,
+    /// `negative_ttl:` (in seconds) and `max_entries:`.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Option<Self>, Error> {
+        let mut cache = Self {
+            ttl: None,
+            negative_ttl: Duration::ZERO,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            entries: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+        if value.is_kind_of(ruby.class_true_class()) {
+            return Ok(Some(cache));
+        }
+        if value.is_kind_of(ruby.class_false_class()) {
+            return Ok(None);
+        }
+
+        let hash = RHash::try_convert(value)?;
+        if let Some(ttl) = get::<f64>(ruby, hash, "ttl")? {
+            cache.ttl = Some(extract_duration(ruby, "ttl", ttl)?);
+        }
+        if let Some(negative_ttl) = get::<f64>(ruby, hash, "negative_ttl")? {
+            cache.negative_ttl = extract_duration(ruby, "negative_ttl", negative_ttl)?;
+        }
+        if let Some(max_entries) = get::<usize>(ruby, hash, "max_entries")? {
+            if max_entries == 0 {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    "dns cache max_entries must be at least 1",
+                ));
+            }
+            cache.max_entries = max_entries;
+        }
+        Ok(Some(cache))
+    }
+
+    /// Look up a cached answer for `host`, counting the hit or miss.
+    pub fn get(&self, host: &str) -> Option<Result<Vec<IpAddr>, String>> {
+        let entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        let cached = entries
+            .get(host)
+            .filter(|entry| entry.expires > Instant::now())
+            .map(|entry| entry.result.clone());
+        let counter = match cached {
+            Some(_) => &self.hits,
+            None => &self.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    /// Cache the result of looking up `host`.
+    pub fn insert(&self, host: String, result: &Result<Answer, BoxError>) {
+        let (result, ttl) = match result {
+            Ok(answer) => (
+                Ok(answer.addrs.clone()),
+                self.ttl.or(answer.ttl).unwrap_or(DEFAULT_TTL),
+            ),
+            Err(err) => (Err(err.to_string()), self.negative_ttl),
+        };
+        if ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        if entries.len() >= self.max_entries && !entries.contains_key(&host) {
+            entries.retain(|_, entry| entry.expires > now);
+            // Still full: evict the entry closest to expiring.
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires)
+                    .map(|(host, _)| host.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(
+            host,
+            Entry {
+                result,
+                expires: now + ttl,
+            },
+        );
+    }
+
+    /// Get the hit and miss counters and the number of live entries.
+    pub fn stats(&self) -> Stats {
+        let now = Instant::now();
+        let entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        Stats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: entries.values().filter(|entry| entry.expires > now).count(),
+        }
+    }
+}
+
+/// Read an optional setting from the cache hash.
+fn get<T: TryConvert>(ruby: &Ruby, hash: RHash, key: &str) -> Result<Option<T>, Error> {
+    match hash.get(ruby.to_symbol(key)) {
+        Some(value) if !value.is_nil() => T::try_convert(value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn extract_duration(ruby: &Ruby, key: &str, secs: f64) -> Result<Duration, Error> {
+    Duration::try_from_secs_f64(secs).map_err(|err| {
+        Error::new(
+            ruby.exception_arg_error(),
+            format!("invalid dns cache {key}: {err}"),
+        )
+    })
+}
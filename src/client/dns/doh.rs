@@ -0,0 +1,122 @@
+use std::{net::IpAddr, time::Duration};
+
+use magnus::{Error, Ruby};
+use serde::Deserialize;
+use url::Url;
+
+use super::Answer;
+use crate::error::RequestError;
+
+/// The record types of IPv4 and IPv6 addresses.
+const RECORD_A: u16 = 1;
+const RECORD_AAAA: u16 = 28;
+
+/// A DNS-over-HTTPS resolver, using the JSON API (`application/dns-json`) served by
+/// Cloudflare, Google and most public resolvers.
+#[derive(Clone)]
+pub struct Doh {
+    client: wreq::Client,
+    url: String,
+}
+
+/// A response of the JSON API.
+#[derive(Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohRecord>,
+}
+
+/// A record of a response of the JSON API.
+#[derive(Deserialize)]
+struct DohRecord {
+    #[serde(rename = "type")]
+    kind: u16,
+    #[serde(rename = "TTL", default)]
+    ttl: u64,
+    data: String,
+}
+
+impl Doh {
+    /// Create a resolver querying the endpoint at `url`, e.g.
+    /// `https://cloudflare-dns.com/dns-query`.
+    pub fn new(ruby: &Ruby, url: String) -> Result<Self, Error> {
+        match Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            _ => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("invalid DNS-over-HTTPS URL: {url}"),
+                ));
+            }
+        }
+        // The endpoint itself is resolved with the system resolver.
+        let client = wreq::Client::builder()
+            .build()
+            .map_err(RequestError::from)?;
+        Ok(Self { client, url })
+    }
+
+    /// Look up the IPv4 and IPv6 addresses of `host`.
+    pub async fn lookup(&self, host: &str) -> Result<Answer, String> {
+        let (v4, v6) = tokio::join!(
+            self.query(host, RECORD_A, "A"),
+            self.query(host, RECORD_AAAA, "AAAA")
+        );
+        let mut records = match (v4, v6) {
+            (Err(err), _) | (Ok(_), Err(err)) => return Err(err),
+            (Ok(v4), Ok(v6)) => v4.into_iter().chain(v6).peekable(),
+        };
+        if records.peek().is_none() {
+            return Err(format!("no addresses found for {host}"));
+        }
+
+        let mut answer = Answer {
+            addrs: Vec::new(),
+            ttl: None,
+        };
+        for (addr, ttl) in records {
+            answer.addrs.push(addr);
+            answer.ttl = Some(answer.ttl.map_or(ttl, |min: Duration| min.min(ttl)));
+        }
+        Ok(answer)
+    }
+
+    /// Query the records of one type, returning the addresses with their TTL.
+    async fn query(
+        &self,
+        host: &str,
+        kind: u16,
+        name: &str,
+    ) -> Result<Vec<(IpAddr, Duration)>, String> {
+        let failed =
+            |err: &dyn std::fmt::Display| format!("DNS-over-HTTPS lookup of {host} failed: {err}");
+        let response = self
+            .client
+            .get(&self.url)
+            .query(&[("name", host), ("type", name)])
+            .header("accept", "application/dns-json")
+            .send()
+            .await
+            .and_then(wreq::Response::error_for_status)
+            .map_err(|err| failed(&err))?;
+        let response = response
+            .json::<DohResponse>()
+            .await
+            .map_err(|err| failed(&err))?;
+        if response.status != 0 {
+            return Err(failed(&format_args!("status {}", response.status)));
+        }
+
+        Ok(response
+            .answer
+            .into_iter()
+            .filter(|record| record.kind == kind)
+            .filter_map(|record| {
+                let addr = record.data.parse::<IpAddr>().ok()?;
+                Some((addr, Duration::from_secs(record.ttl)))
+            })
+            .collect())
+    }
+}
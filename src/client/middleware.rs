@@ -0,0 +1,255 @@
+use std::{
+    cell::{Cell, RefCell},
+    sync::{Arc, RwLock},
+};
+
+use magnus::{
+    DataTypeFunctions, Error, Module, RArray, RModule, Ruby, TryConvert, Value,
+    gc::Marker,
+    method,
+    typed_data::Obj,
+    value::{BoxValue, Opaque, ReprValue},
+};
+
+use super::{
+    body::Body,
+    req::Request,
+    resp::{RaiseOnStatus, Response},
+};
+use crate::{error::memory_error, header::Headers, http::Method};
+
+/// The middleware stack of a client, set with the `middleware:` option or `Client#use`.
+///
+/// Each middleware is a Ruby object responding to `call(request)`, run before the
+/// request is sent, and/or `on_response(request, response)`, run after it.
+#[derive(Clone, Default)]
+pub struct Middleware(Arc<RwLock<Vec<Opaque<Value>>>>);
+
+/// The middleware that saw a request, kept to run their `on_response` hooks, and the
+/// statuses its response raises on.
+#[derive(Default)]
+pub struct Context {
+    stack: Vec<Opaque<Value>>,
+    request: Option<BoxValue<Obj<MiddlewareRequest>>>,
+    raise_on_status: RaiseOnStatus,
+}
+
+/// The request passed to middleware, which may change its URL, headers and body.
+#[magnus::wrap(class = "Wreq::Request", free_immediately, size, mark)]
+pub struct MiddlewareRequest {
+    method: Method,
+    url: RefCell<String>,
+    headers: Opaque<Obj<Headers>>,
+    body: RefCell<Option<Opaque<Value>>>,
+    body_changed: Cell<bool>,
+}
+
+// ===== impl Middleware =====
+
+impl Middleware {
+    /// Parse the `middleware:` option, either a single middleware or an Array of them.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        let middleware = Self::default();
+        match RArray::from_value(value) {
+            Some(stack) => {
+                for value in stack {
+                    middleware.push(ruby, value)?;
+                }
+            }
+            None => middleware.push(ruby, value)?,
+        }
+        Ok(middleware)
+    }
+
+    /// Append a middleware to the stack.
+    pub fn push(&self, ruby: &Ruby, value: Value) -> Result<(), Error> {
+        if !value.respond_to("call", false)? && !value.respond_to("on_response", false)? {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                format!(
+                    "middleware must respond to `call` or `on_response`, got {}",
+                    value.inspect()
+                ),
+            ));
+        }
+        self.0
+            .write()
+            .map_err(|_| memory_error())?
+            .push(Opaque::from(value));
+        Ok(())
+    }
+
+    /// Mark the middleware so they are not garbage collected.
+    pub fn mark(&self, marker: &Marker) {
+        if let Ok(stack) = self.0.read() {
+            for middleware in stack.iter() {
+                marker.mark(*middleware);
+            }
+        }
+    }
+
+    /// Run the `call` hooks in order, applying their changes to the request.
+    ///
+    /// Returns the URL to send the request to, and the [`Context`] for the response.
+    pub fn before(
+        &self,
+        ruby: &Ruby,
+        method: Method,
+        url: String,
+        request: &mut Request,
+    ) -> Result<(String, Context), Error> {
+        let stack = self.0.read().map_err(|_| memory_error())?.clone();
+        if stack.is_empty() {
+            return Ok((url, Context::default()));
+        }
+
+        let rb_request = ruby.obj_wrap(MiddlewareRequest::new(ruby, method, url, request));
+        for middleware in stack.iter() {
+            let middleware = ruby.get_inner(*middleware);
+            if middleware.respond_to("call", false)? {
+                let _: Value = middleware.funcall("call", (rb_request,))?;
+            }
+        }
+        let url = rb_request.apply(ruby, request)?;
+
+        Ok((
+            url,
+            Context {
+                stack,
+                request: Some(BoxValue::new(rb_request)),
+                raise_on_status: RaiseOnStatus::Off,
+            },
+        ))
+    }
+}
+
+// ===== impl Context =====
+
+impl Context {
+    /// Run the `on_response` hooks in reverse order, returning the response to hand to Ruby.
+    ///
+    /// A hook returning a `Wreq::Response` replaces the response; other values are ignored.
+    pub fn after(&self, ruby: &Ruby, response: Response) -> Result<Obj<Response>, Error> {
+        let mut response = ruby.obj_wrap(response);
+        let Some(request) = self.request.as_ref() else {
+            return Ok(response);
+        };
+        for middleware in self.stack.iter().rev() {
+            let middleware = ruby.get_inner(*middleware);
+            if middleware.respond_to("on_response", false)? {
+                let result: Value = middleware.funcall("on_response", (**request, response))?;
+                if let Ok(replacement) = Obj::<Response>::try_convert(result) {
+                    response = replacement;
+                }
+            }
+        }
+        Ok(response)
+    }
+
+    /// Set the statuses that raise a `Wreq::StatusError` in [`Context::check_status`].
+    pub fn with_raise_on_status(mut self, raise_on_status: RaiseOnStatus) -> Self {
+        self.raise_on_status = raise_on_status;
+        self
+    }
+
+    /// Raise a `Wreq::StatusError` if the status of the response, as returned by the
+    /// `on_response` hooks, is one the request raises on.
+    pub fn check_status(&self, response: Obj<Response>) -> Result<Obj<Response>, Error> {
+        self.raise_on_status.check(response)
+    }
+
+    /// Mark the middleware so they are not garbage collected.
+    pub fn mark(&self, marker: &Marker) {
+        for middleware in self.stack.iter() {
+            marker.mark(*middleware);
+        }
+    }
+}
+
+// ===== impl MiddlewareRequest =====
+
+impl DataTypeFunctions for MiddlewareRequest {
+    fn mark(&self, marker: &Marker) {
+        marker.mark(self.headers);
+        if let Some(body) = *self.body.borrow() {
+            marker.mark(body);
+        }
+    }
+}
+
+impl MiddlewareRequest {
+    /// Expose the URL, headers and in-memory body of a request to middleware.
+    fn new(ruby: &Ruby, method: Method, url: String, request: &mut Request) -> Self {
+        let headers = ruby.obj_wrap(Headers::from(request.take_headers()));
+        let body = request
+            .body_bytes()
+            .map(|bytes| Opaque::from(ruby.str_from_slice(&bytes).as_value()));
+        Self {
+            method,
+            url: RefCell::new(url),
+            headers: Opaque::from(headers),
+            body: RefCell::new(body),
+            body_changed: Cell::new(false),
+        }
+    }
+
+    /// Write the changes made by middleware back to the request, returning its URL.
+    fn apply(&self, ruby: &Ruby, request: &mut Request) -> Result<String, Error> {
+        request.set_headers(ruby.get_inner(self.headers).to_header_map());
+        if self.body_changed.get() {
+            let body = match *self.body.borrow() {
+                Some(body) => Some(Body::try_convert(ruby.get_inner(body))?),
+                None => None,
+            };
+            request.set_body(body);
+        }
+        Ok(self.url.borrow().clone())
+    }
+
+    /// Get the request method.
+    #[inline]
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Get the request URL.
+    #[inline]
+    pub fn url(&self) -> String {
+        self.url.borrow().clone()
+    }
+
+    /// Set the request URL.
+    #[inline]
+    pub fn set_url(&self, url: String) {
+        *self.url.borrow_mut() = url;
+    }
+
+    /// Get the request headers, which can be changed in place.
+    #[inline]
+    pub fn headers(ruby: &Ruby, rb_self: &Self) -> Obj<Headers> {
+        ruby.get_inner(rb_self.headers)
+    }
+
+    /// Get the request body, if it was given as a String.
+    #[inline]
+    pub fn body(ruby: &Ruby, rb_self: &Self) -> Option<Value> {
+        rb_self.body.borrow().map(|body| ruby.get_inner(body))
+    }
+
+    /// Replace the request body, dropping any `form:`, `json:` or `multipart:` body.
+    pub fn set_body(&self, body: Option<Value>) {
+        *self.body.borrow_mut() = body.map(Opaque::from);
+        self.body_changed.set(true);
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let request_class = gem_module.define_class("Request", ruby.class_object())?;
+    request_class.define_method("method", method!(MiddlewareRequest::method, 0))?;
+    request_class.define_method("url", method!(MiddlewareRequest::url, 0))?;
+    request_class.define_method("url=", method!(MiddlewareRequest::set_url, 1))?;
+    request_class.define_method("headers", method!(MiddlewareRequest::headers, 0))?;
+    request_class.define_method("body", method!(MiddlewareRequest::body, 0))?;
+    request_class.define_method("body=", method!(MiddlewareRequest::set_body, 1))?;
+    Ok(())
+}
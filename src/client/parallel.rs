@@ -0,0 +1,150 @@
+use futures_util::{StreamExt, stream};
+use magnus::{
+    Error, IntoValue, RArray, RHash, Ruby, TryConvert, Value, block::Proc, typed_data::Obj,
+    value::ReprValue,
+};
+
+use super::{
+    Client,
+    middleware::Context,
+    req::{Request, prepare_request, send_request},
+    resp::Response,
+};
+use crate::{
+    error::{RequestError, exception_of},
+    http::Method,
+    rt,
+};
+
+/// A single request of a parallel batch.
+struct ParallelRequest {
+    method: Method,
+    url: String,
+    request: Request,
+}
+
+impl ParallelRequest {
+    /// Create a [`ParallelRequest`] from a Ruby hash with `:url`, an optional `:method`,
+    /// and any other request options, running it through the client's middleware.
+    fn new(ruby: &Ruby, client: &Client, spec: Value) -> Result<(Self, Context), Error> {
+        let hash = RHash::try_convert(spec)?;
+        let url = hash
+            .get(ruby.to_symbol("url"))
+            .ok_or_else(|| Error::new(ruby.exception_arg_error(), "missing request :url"))
+            .and_then(String::try_convert)?;
+        let method = match hash.get(ruby.to_symbol("method")) {
+            Some(method) if !method.is_nil() => *Obj::<Method>::try_convert(method)?,
+            _ => Method::GET,
+        };
+        let mut request = Request::new(ruby, hash)?;
+        let (url, context) = prepare_request(ruby, client, method, url, &mut request)?;
+        Ok((
+            Self {
+                method,
+                url,
+                request,
+            },
+            context,
+        ))
+    }
+}
+
+/// Extract the `concurrency:` option, defaulting to running every request at once.
+fn extract_concurrency(ruby: &Ruby, keywords: RHash, len: usize) -> Result<usize, Error> {
+    match keywords.get(ruby.to_symbol("concurrency")) {
+        Some(value) if !value.is_nil() => match usize::try_convert(value)? {
+            0 => Err(Error::new(
+                ruby.exception_arg_error(),
+                "concurrency must be greater than zero",
+            )),
+            concurrency => Ok(concurrency),
+        },
+        _ => Ok(len.max(1)),
+    }
+}
+
+/// Convert a request result into a Ruby value, turning errors into exception objects.
+///
+/// The `on_response` hooks of `context` run for successful responses, and a response
+/// whose status raises is represented by its `Wreq::StatusError`.
+fn result_to_value(
+    ruby: &Ruby,
+    result: Result<Response, RequestError>,
+    context: &Context,
+) -> Result<Value, Error> {
+    // An exception raised by `on_response` is the result of its request only.
+    let err = match result.map_err(Error::from).and_then(|response| {
+        context
+            .after(ruby, response)
+            .and_then(|response| context.check_status(response))
+    }) {
+        Ok(response) => return Ok(response.into_value_with(ruby)),
+        Err(err) => err,
+    };
+    exception_of(err).map(|exception| exception.as_value())
+}
+
+/// Send all requests concurrently on the runtime, at most `concurrency:` at a time.
+///
+/// Without a block, every request is driven to completion in a single GVL release.
+/// With one, the GVL is reacquired as each request completes to yield its result
+/// and index to the block. Either way the results are returned in input order,
+/// failed requests being represented by their exception objects.
+pub fn execute_parallel(
+    ruby: &Ruby,
+    client: Client,
+    requests: RArray,
+    keywords: RHash,
+    block: Option<Proc>,
+) -> Result<RArray, Error> {
+    if client.resolver.calls_ruby() {
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            "dns_resolver callables cannot be used in parallel requests",
+        ));
+    }
+    let (requests, contexts): (Vec<_>, Vec<_>) = requests
+        .into_iter()
+        .map(|spec| ParallelRequest::new(ruby, &client, spec))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    let len = requests.len();
+    let concurrency = extract_concurrency(ruby, keywords, len)?;
+
+    let mut pending = stream::iter(requests.into_iter().enumerate().map(|(index, spec)| {
+        let client = client.clone();
+        async move {
+            let result = send_request(client, spec.method, spec.url, spec.request).await;
+            (index, result)
+        }
+    }))
+    .buffer_unordered(concurrency);
+
+    let results = ruby.ary_new_capa(len);
+    match block {
+        None => {
+            let mut completed = rt::try_block_on(async {
+                let mut completed = Vec::with_capacity(len);
+                while let Some(item) = pending.next().await {
+                    completed.push(item);
+                }
+                Ok(completed)
+            })?;
+            completed.sort_unstable_by_key(|(index, _)| *index);
+            for (index, result) in completed {
+                results.push(result_to_value(ruby, result, &contexts[index])?)?;
+            }
+        }
+        Some(block) => {
+            while let Some((index, result)) = rt::try_block_on(async { Ok(pending.next().await) })?
+            {
+                let value = result_to_value(ruby, result, &contexts[index])?;
+                results.store(index as isize, value)?;
+                block.call::<_, Value>((value, index))?;
+            }
+        }
+    }
+
+    Ok(results)
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 pub type Params = IndexMap<String, ParamValue>;
 
 /// Represents a single parameter value that can be automatically converted from Python types.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ParamValue {
     /// A boolean value from Python `bool`.
@@ -0,0 +1,208 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::future;
+use magnus::{
+    DataTypeFunctions, Error, Module, Object, RArray, RModule, Ruby, TryConvert, Value, function,
+    gc::Marker, method, scan_args, typed_data::Obj,
+};
+use tokio::{sync::watch, task::AbortHandle};
+
+use super::{middleware::Context, resp::Response};
+use crate::{
+    error::{RequestError, cancelled_error, memory_error},
+    rt,
+};
+
+/// A request running in the background on the runtime.
+#[magnus::wrap(class = "Wreq::Pending", free_immediately, size, mark)]
+pub struct Pending {
+    state: Arc<Mutex<State>>,
+    done: watch::Receiver<bool>,
+    abort: AbortHandle,
+    context: Context,
+}
+
+/// The state of a [`Pending`] request.
+enum State {
+    /// The request is still in flight.
+    Running,
+    /// The request finished; the error stays unconverted until read on a Ruby thread.
+    Done(Result<Response, RequestError>),
+    /// The request was cancelled before it finished.
+    Cancelled,
+    /// The result has already been handed to Ruby.
+    Taken,
+}
+
+impl Pending {
+    /// Spawn a request future on the runtime and return a handle to it.
+    ///
+    /// The `on_response` hooks of `context` run when the response is read.
+    pub fn spawn<F>(future: F, context: Context) -> Self
+    where
+        F: Future<Output = Result<Response, RequestError>> + Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(State::Running));
+        let (tx, done) = watch::channel(false);
+
+        let task_state = state.clone();
+        let handle = rt::spawn(async move {
+            let result = future.await;
+            if let Ok(mut state) = task_state.lock() {
+                if matches!(*state, State::Running) {
+                    *state = State::Done(result);
+                }
+            }
+            let _ = tx.send(true);
+        });
+
+        Self {
+            state,
+            done,
+            abort: handle.abort_handle(),
+            context,
+        }
+    }
+
+    /// Returns `true` if the request has finished or was cancelled.
+    pub fn is_ready(&self) -> bool {
+        let finished = self
+            .state
+            .lock()
+            .is_ok_and(|state| !matches!(*state, State::Running));
+        // A closed channel means the task is gone, which also counts as ready.
+        finished || self.done.has_changed().is_err()
+    }
+
+    /// Wait for the request to finish, returning `false` if the timeout expires first.
+    pub fn wait(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<bool, Error> {
+        let args = scan_args::scan_args::<(), (Option<f64>,), (), (), (), ()>(args)?;
+        let (timeout,) = args.optional;
+        with_timeout(ruby, timeout, wait_done(rb_self.done.clone())).map(|done| done.is_some())
+    }
+
+    /// Wait for the request and return its [`Response`], raising the mapped error on failure.
+    pub fn value(ruby: &Ruby, rb_self: &Self) -> Result<Obj<Response>, Error> {
+        rt::try_block_on(async {
+            wait_done(rb_self.done.clone()).await;
+            Ok(())
+        })?;
+
+        let result = {
+            let mut state = rb_self.state.lock().map_err(|_| memory_error())?;
+            match std::mem::replace(&mut *state, State::Taken) {
+                State::Done(result) => result,
+                State::Cancelled | State::Running => {
+                    *state = State::Cancelled;
+                    return Err(cancelled_error());
+                }
+                State::Taken => return Err(memory_error()),
+            }
+        };
+        // The hooks run without the lock held, as they may call back into this handle.
+        let response = rb_self.context.after(ruby, result?)?;
+        rb_self.context.check_status(response)
+    }
+
+    /// Cancel the request, returning `true` if it was still in flight.
+    pub fn cancel(&self) -> Result<bool, Error> {
+        let mut state = self.state.lock().map_err(|_| memory_error())?;
+        if matches!(*state, State::Running) {
+            *state = State::Cancelled;
+            self.abort.abort();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Wait until any of the given requests finishes, returning it,
+    /// or `nil` if the timeout expires first.
+    pub fn wait_any(ruby: &Ruby, args: &[Value]) -> Result<Option<Value>, Error> {
+        let args = scan_args::scan_args::<(RArray,), (Option<f64>,), (), (), (), ()>(args)?;
+        let (pendings,) = args.required;
+        let (timeout,) = args.optional;
+
+        let waits = pendings
+            .into_iter()
+            .map(|value| {
+                let pending = Obj::<Pending>::try_convert(value)?;
+                Ok(Box::pin(wait_done(pending.done.clone())))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        if waits.is_empty() {
+            return Ok(None);
+        }
+
+        let index = with_timeout(ruby, timeout, async {
+            let ((), index, _) = future::select_all(waits).await;
+            index
+        })?;
+        index
+            .map(|index| pendings.entry(index as isize))
+            .transpose()
+    }
+
+    /// Wait until all of the given requests finish,
+    /// returning `false` if the timeout expires first.
+    pub fn wait_all(ruby: &Ruby, args: &[Value]) -> Result<bool, Error> {
+        let args = scan_args::scan_args::<(RArray,), (Option<f64>,), (), (), (), ()>(args)?;
+        let (pendings,) = args.required;
+        let (timeout,) = args.optional;
+
+        let waits = pendings
+            .into_iter()
+            .map(|value| {
+                let pending = Obj::<Pending>::try_convert(value)?;
+                Ok(wait_done(pending.done.clone()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        with_timeout(ruby, timeout, future::join_all(waits)).map(|done| done.is_some())
+    }
+}
+
+impl DataTypeFunctions for Pending {
+    fn mark(&self, marker: &Marker) {
+        self.context.mark(marker);
+    }
+}
+
+/// Resolve once the request task has finished or gone away.
+async fn wait_done(mut done: watch::Receiver<bool>) {
+    let _ = done.wait_for(|done| *done).await;
+}
+
+/// Block on `future` without the GVL, giving up after `timeout` seconds if one is given.
+fn with_timeout<F: Future>(
+    ruby: &Ruby,
+    timeout: Option<f64>,
+    future: F,
+) -> Result<Option<F::Output>, Error> {
+    match timeout {
+        None => rt::try_block_on(async { Ok(Some(future.await)) }),
+        Some(secs) => {
+            let timeout = Duration::try_from_secs_f64(secs).map_err(|err| {
+                Error::new(
+                    ruby.exception_arg_error(),
+                    format!("invalid timeout: {err}"),
+                )
+            })?;
+            rt::try_block_on(async { Ok(tokio::time::timeout(timeout, future).await.ok()) })
+        }
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let pending_class = gem_module.define_class("Pending", ruby.class_object())?;
+    pending_class.define_singleton_method("wait_any", function!(Pending::wait_any, -1))?;
+    pending_class.define_singleton_method("wait_all", function!(Pending::wait_all, -1))?;
+    pending_class.define_method("ready?", method!(Pending::is_ready, 0))?;
+    pending_class.define_method("wait", method!(Pending::wait, -1))?;
+    pending_class.define_method("value", method!(Pending::value, 0))?;
+    pending_class.define_method("cancel", method!(Pending::cancel, 0))?;
+    Ok(())
+}
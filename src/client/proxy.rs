@@ -0,0 +1,164 @@
+mod pool;
+
+#[cfg(unix)]
+use std::path::PathBuf;
+
+use http::Uri;
+use magnus::{
+    Error, RArray, RHash, RModule, RString, Ruby, TryConvert, Value, typed_data::Obj,
+    value::ReprValue,
+};
+use wreq::{NoProxy, Proxy, header::HeaderMap};
+
+pub use self::pool::{Checkout, ProxyPool};
+use crate::{error::wreq_error_to_magnus, extractor::Extractor};
+
+/// The `proxy:` option: fixed proxies, or a pool choosing one for each request.
+#[derive(Clone)]
+pub enum ProxySetting {
+    Fixed(Proxies),
+    Pool(ProxyPool),
+}
+
+/// The proxies set with the `proxy:` option, for all schemes or per scheme.
+#[derive(Clone)]
+pub struct Proxies {
+    all: Option<Proxy>,
+    http: Option<Proxy>,
+    https: Option<Proxy>,
+}
+
+// ===== impl ProxySetting =====
+
+impl ProxySetting {
+    /// Parse the `proxy:` option, a `Wreq::ProxyPool` or the proxies accepted by
+    /// [`Proxies::new`].
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        match Obj::<ProxyPool>::from_value(value) {
+            Some(pool) => Ok(Self::Pool((*pool).clone())),
+            None => Proxies::new(ruby, value).map(Self::Fixed),
+        }
+    }
+
+    /// The proxy for a request to `uri`, or `None` to connect directly, with the
+    /// checkout to report its outcome to when it comes from a pool.
+    pub fn for_uri(&self, uri: &Uri) -> Option<(Proxy, Option<Checkout>)> {
+        match self {
+            Self::Fixed(proxies) => proxies.for_url(&uri.to_string()).map(|proxy| (proxy, None)),
+            Self::Pool(pool) => pool
+                .checkout(uri)
+                .map(|(proxy, checkout)| (proxy, Some(checkout))),
+        }
+    }
+}
+
+// ===== impl Proxies =====
+
+impl Proxies {
+    /// Parse the `proxy:` option.
+    ///
+    /// Accepts a proxy URL for all schemes, or a Hash with `url:` (all schemes),
+    /// `http:` and `https:` URLs, and the `username:`, `password:`, `headers:` and
+    /// `no_proxy:` settings shared by them.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        if let Some(url) = RString::from_value(value) {
+            return Ok(Self {
+                all: Some(Proxy::all(url.to_bytes().as_ref()).map_err(wreq_error_to_magnus)?),
+                http: None,
+                https: None,
+            });
+        }
+
+        let hash = RHash::try_convert(value)?;
+        let build = |key: &str, new: fn(&str) -> Result<Proxy, wreq::Error>| {
+            get::<String>(ruby, hash, key)?
+                .map(|url| new(&url).map_err(wreq_error_to_magnus))
+                .transpose()
+        };
+        let proxies = Self {
+            all: build("url", |url| Proxy::all(url))?,
+            http: build("http", |url| Proxy::http(url))?,
+            https: build("https", |url| Proxy::https(url))?,
+        };
+        if proxies.all.is_none() && proxies.http.is_none() && proxies.https.is_none() {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "proxy must have a url, http or https entry",
+            ));
+        }
+
+        let username = get::<String>(ruby, hash, "username")?;
+        let password = get::<String>(ruby, hash, "password")?;
+        let headers = Extractor::<HeaderMap>::try_convert(hash.as_value())?.into_inner();
+        let no_proxy = match get::<Value>(ruby, hash, "no_proxy")? {
+            Some(value) => match RArray::from_value(value) {
+                Some(hosts) => Some(hosts.to_vec::<String>()?.join(",")),
+                None => Some(String::try_convert(value)?),
+            },
+            None => None,
+        };
+
+        let configure = |mut proxy: Proxy| {
+            if username.is_some() || password.is_some() {
+                proxy = proxy.basic_auth(
+                    username.as_deref().unwrap_or_default(),
+                    password.as_deref().unwrap_or_default(),
+                );
+            }
+            // Sent with the CONNECT request of tunneled (HTTPS) requests.
+            if let Some(headers) = headers.clone() {
+                proxy = proxy.custom_http_headers(headers);
+            }
+            if let Some(no_proxy) = no_proxy.as_deref() {
+                proxy = proxy.no_proxy(NoProxy::from_string(no_proxy));
+            }
+            proxy
+        };
+        Ok(Self {
+            all: proxies.all.map(configure),
+            http: proxies.http.map(configure),
+            https: proxies.https.map(configure),
+        })
+    }
+
+    /// Connect to the Unix socket at `path` for all schemes, set with the `unix_socket:` option.
+    #[cfg(unix)]
+    pub fn unix(path: PathBuf) -> Result<Self, wreq::Error> {
+        Proxy::unix(path).map(|proxy| Self {
+            all: Some(proxy),
+            http: None,
+            https: None,
+        })
+    }
+
+    /// The proxies to configure a client with; scheme-specific ones take precedence.
+    pub fn into_vec(self) -> Vec<Proxy> {
+        [self.http, self.https, self.all]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// The proxy for a request to `url`, or `None` to connect directly.
+    pub fn for_url(&self, url: &str) -> Option<Proxy> {
+        let scheme = url.split_once("://").map(|(scheme, _)| scheme);
+        let specific = match scheme.map(str::to_ascii_lowercase).as_deref() {
+            Some("https" | "wss") => self.https.as_ref(),
+            Some("http" | "ws") => self.http.as_ref(),
+            _ => None,
+        };
+        specific.or(self.all.as_ref()).cloned()
+    }
+}
+
+/// Read an optional setting from the proxy hash.
+fn get<T: TryConvert>(ruby: &Ruby, hash: RHash, key: &str) -> Result<Option<T>, Error> {
+    match hash.get(ruby.to_symbol(key)) {
+        Some(value) if !value.is_nil() => T::try_convert(value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    pool::include(ruby, gem_module)
+}
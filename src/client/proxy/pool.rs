@@ -0,0 +1,374 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use http::Uri;
+use magnus::{
+    Error, Module, Object, RArray, RHash, RModule, Ruby, Symbol, TryConvert, Value, function,
+    method, scan_args,
+};
+use url::Url;
+use wreq::Proxy;
+
+use super::{Proxies, get};
+use crate::error::{ErrorKind, RequestError, TimeoutPhase};
+
+/// How long an ejected proxy is skipped by default.
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How many hosts the sticky strategy remembers before forgetting one.
+const MAX_STICKY_HOSTS: usize = 1024;
+
+/// A pool of proxies rotated between requests, skipping the ones that keep failing.
+#[derive(Clone)]
+#[magnus::wrap(class = "Wreq::ProxyPool", free_immediately, size)]
+pub struct ProxyPool(Arc<Inner>);
+
+struct Inner {
+    entries: Vec<Entry>,
+    strategy: Strategy,
+    /// How many failures in a row eject a proxy.
+    max_failures: u32,
+    /// How long an ejected proxy is skipped.
+    cooldown: Duration,
+    /// The rotation counter, also seeding random picks.
+    next: AtomicUsize,
+    /// The proxy assigned to each host by the sticky strategy, for up to
+    /// [`MAX_STICKY_HOSTS`] hosts.
+    sticky: Mutex<HashMap<String, usize>>,
+}
+
+/// A proxy of the pool.
+struct Entry {
+    /// The proxy URL without credentials, as reported in stats.
+    url: String,
+    proxies: Proxies,
+    weight: u32,
+    health: Mutex<Health>,
+}
+
+/// The outcomes of the requests sent through a proxy.
+#[derive(Default)]
+struct Health {
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    /// The total time to a response of the successful requests.
+    latency: Duration,
+    ejected_until: Option<Instant>,
+}
+
+/// How the proxy of each request is chosen.
+#[derive(Clone, Copy)]
+enum Strategy {
+    RoundRobin,
+    Random,
+    Weighted,
+    Sticky,
+}
+
+/// A proxy taken from the pool for one request, to report its outcome with.
+pub struct Checkout {
+    pool: Arc<Inner>,
+    index: usize,
+    started: Instant,
+}
+
+// ===== impl ProxyPool =====
+
+impl ProxyPool {
+    /// Create a pool from an Array of proxies, each a URL or a Hash as accepted by
+    /// `proxy:` with an optional `weight:`, and the `strategy:`, `max_failures:` and
+    /// `cooldown:` (in seconds) options.
+    fn new(ruby: &Ruby, args: &[Value]) -> Result<Self, Error> {
+        let args = scan_args::scan_args::<(RArray,), (), (), (), RHash, ()>(args)?;
+        let (proxies,) = args.required;
+        let options = args.keywords;
+
+        let entries = proxies
+            .into_iter()
+            .map(|proxy| Entry::new(ruby, proxy))
+            .collect::<Result<Vec<_>, Error>>()?;
+        if entries.is_empty() {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "proxy pool must have at least one proxy",
+            ));
+        }
+
+        let strategy = match get::<Value>(ruby, options, "strategy")? {
+            Some(strategy) => Strategy::new(ruby, strategy)?,
+            None => Strategy::RoundRobin,
+        };
+        let max_failures = get::<u32>(ruby, options, "max_failures")?.unwrap_or(1);
+        if max_failures == 0 {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "proxy pool max_failures must be at least 1",
+            ));
+        }
+        let cooldown = match get::<f64>(ruby, options, "cooldown")? {
+            Some(secs) => Duration::try_from_secs_f64(secs).map_err(|err| {
+                Error::new(
+                    ruby.exception_arg_error(),
+                    format!("invalid proxy pool cooldown: {err}"),
+                )
+            })?,
+            None => DEFAULT_COOLDOWN,
+        };
+
+        Ok(Self(Arc::new(Inner {
+            entries,
+            strategy,
+            max_failures,
+            cooldown,
+            next: AtomicUsize::new(0),
+            sticky: Mutex::default(),
+        })))
+    }
+
+    /// Take a proxy for a request to `uri`, or `None` when the chosen entry has no
+    /// proxy for its scheme.
+    pub fn checkout(&self, uri: &Uri) -> Option<(Proxy, Checkout)> {
+        let index = self.0.pick(uri.host());
+        let proxy = self.0.entries[index].proxies.for_url(&uri.to_string())?;
+        let checkout = Checkout {
+            pool: self.0.clone(),
+            index,
+            started: Instant::now(),
+        };
+        Some((proxy, checkout))
+    }
+
+    /// Get the number of proxies in the pool.
+    pub fn size(&self) -> usize {
+        self.0.entries.len()
+    }
+
+    /// Get the URL, weight, successes, failures, average latency (in seconds) and
+    /// ejection state of each proxy.
+    pub fn stats(ruby: &Ruby, rb_self: &Self) -> Result<RArray, Error> {
+        let now = Instant::now();
+        let stats = ruby.ary_new_capa(rb_self.0.entries.len());
+        for entry in &rb_self.0.entries {
+            let health = entry.health();
+            let average_latency = (health.successes > 0)
+                .then(|| health.latency.as_secs_f64() / health.successes as f64);
+            let hash = ruby.hash_new();
+            hash.aset(ruby.to_symbol("url"), entry.url.as_str())?;
+            hash.aset(ruby.to_symbol("weight"), entry.weight)?;
+            hash.aset(ruby.to_symbol("successes"), health.successes)?;
+            hash.aset(ruby.to_symbol("failures"), health.failures)?;
+            hash.aset(ruby.to_symbol("average_latency"), average_latency)?;
+            hash.aset(ruby.to_symbol("ejected"), health.is_ejected(now))?;
+            stats.push(hash)?;
+        }
+        Ok(stats)
+    }
+}
+
+// ===== impl Inner =====
+
+impl Inner {
+    /// Choose the proxy for a request to `host` by the pool's strategy, among the
+    /// proxies that are not ejected.
+    fn pick(&self, host: Option<&str>) -> usize {
+        let now = Instant::now();
+        let available = (0..self.entries.len())
+            .filter(|&index| !self.entries[index].health().is_ejected(now))
+            .collect::<Vec<_>>();
+        // All ejected: use the one coming back first rather than failing outright.
+        if available.is_empty() {
+            return (0..self.entries.len())
+                .min_by_key(|&index| self.entries[index].health().ejected_until)
+                .unwrap_or_default();
+        }
+
+        match (self.strategy, host) {
+            (Strategy::RoundRobin, _) | (Strategy::Sticky, None) => self.rotate(&available),
+            (Strategy::Random, _) => available[self.random() % available.len()],
+            (Strategy::Weighted, _) => {
+                let total = available
+                    .iter()
+                    .map(|&index| self.entries[index].weight as usize)
+                    .sum::<usize>();
+                let mut point = self.random() % total;
+                for &index in &available {
+                    let weight = self.entries[index].weight as usize;
+                    if point < weight {
+                        return index;
+                    }
+                    point -= weight;
+                }
+                available[0]
+            }
+            (Strategy::Sticky, Some(host)) => {
+                let mut sticky = self.sticky.lock().unwrap_or_else(|err| err.into_inner());
+                match sticky.get(host) {
+                    Some(index) if available.contains(index) => *index,
+                    // Move the host to another proxy when its own is ejected.
+                    _ => {
+                        if sticky.len() >= MAX_STICKY_HOSTS && !sticky.contains_key(host) {
+                            if let Some(oldest) = sticky.keys().next().cloned() {
+                                sticky.remove(&oldest);
+                            }
+                        }
+                        let index = self.rotate(&available);
+                        sticky.insert(host.to_owned(), index);
+                        index
+                    }
+                }
+            }
+        }
+    }
+
+    /// Take the next available proxy in order.
+    fn rotate(&self, available: &[usize]) -> usize {
+        let len = self.entries.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|index| available.contains(index))
+            .unwrap_or(available[0])
+    }
+
+    fn random(&self) -> usize {
+        RandomState::new().hash_one(self.next.fetch_add(1, Ordering::Relaxed)) as usize
+    }
+}
+
+// ===== impl Entry =====
+
+impl Entry {
+    fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        let proxies = Proxies::new(ruby, value)?;
+        let (url, weight) = match RHash::from_value(value) {
+            Some(hash) => {
+                let url = ["url", "https", "http"]
+                    .into_iter()
+                    .find_map(|key| get::<String>(ruby, hash, key).transpose())
+                    .transpose()?
+                    .unwrap_or_default();
+                (url, get::<u32>(ruby, hash, "weight")?.unwrap_or(1))
+            }
+            None => (String::try_convert(value)?, 1),
+        };
+        if weight == 0 {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "proxy pool weight must be at least 1",
+            ));
+        }
+        Ok(Self {
+            url: redact(url),
+            proxies,
+            weight,
+            health: Mutex::default(),
+        })
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, Health> {
+        self.health.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Remove the credentials from a proxy URL.
+fn redact(url: String) -> String {
+    match Url::parse(&url) {
+        Ok(mut parsed) if parsed.password().is_some() || !parsed.username().is_empty() => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.into()
+        }
+        _ => url,
+    }
+}
+
+// ===== impl Health =====
+
+impl Health {
+    fn is_ejected(&self, now: Instant) -> bool {
+        self.ejected_until.is_some_and(|until| until > now)
+    }
+}
+
+// ===== impl Strategy =====
+
+impl Strategy {
+    /// Parse the `strategy:` option, given as `:round_robin`, `:random`, `:weighted` or
+    /// `:sticky`.
+    fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        let name = match Symbol::from_value(value) {
+            Some(symbol) => symbol.name()?.into_owned(),
+            None => String::try_convert(value)?,
+        };
+        match name.as_str() {
+            "round_robin" => Ok(Self::RoundRobin),
+            "random" => Ok(Self::Random),
+            "weighted" => Ok(Self::Weighted),
+            "sticky" => Ok(Self::Sticky),
+            _ => Err(Error::new(
+                ruby.exception_arg_error(),
+                format!(
+                    "invalid proxy pool strategy: {name}, expected one of :round_robin, :random, :weighted, :sticky"
+                ),
+            )),
+        }
+    }
+}
+
+// ===== impl Checkout =====
+
+impl Checkout {
+    /// Record the outcome of the request, given the error it failed with: any response
+    /// counts as a success, while failing or timing out to connect or to look up a host
+    /// counts against the proxy and ejects it after `max_failures` in a row. Other
+    /// errors, such as a slow origin server, are not the proxy's doing.
+    pub fn finish(self, failure: Option<&RequestError>) {
+        let mut health = self.pool.entries[self.index].health();
+        match failure {
+            None => {
+                health.successes += 1;
+                health.latency += self.started.elapsed();
+                health.consecutive_failures = 0;
+                health.ejected_until = None;
+            }
+            Some(err) if is_proxy_failure(err) => {
+                health.failures += 1;
+                health.consecutive_failures += 1;
+                if health.consecutive_failures >= self.pool.max_failures {
+                    health.consecutive_failures = 0;
+                    health.ejected_until = Some(Instant::now() + self.pool.cooldown);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Returns `true` if `err` is a failure to reach the server through the proxy: a
+/// connection or proxy tunnel failure, or a lookup or connect timeout.
+fn is_proxy_failure(err: &RequestError) -> bool {
+    match err.kind() {
+        ErrorKind::ProxyConnection | ErrorKind::Connection => true,
+        ErrorKind::Timeout => matches!(
+            err.timeout_phase(),
+            Some(TimeoutPhase::Dns | TimeoutPhase::Connect)
+        ),
+        _ => false,
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let pool_class = gem_module.define_class("ProxyPool", ruby.class_object())?;
+    pool_class.define_singleton_method("new", function!(ProxyPool::new, -1))?;
+    pool_class.define_method("size", method!(ProxyPool::size, 0))?;
+    pool_class.define_method("stats", method!(ProxyPool::stats, 0))?;
+    Ok(())
+}
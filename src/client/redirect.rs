@@ -0,0 +1,404 @@
+use magnus::{
+    Error, Module, RHash, RModule, Ruby, TryConvert, Value, block::Proc, gc::Marker, method,
+    value::Opaque, value::ReprValue,
+};
+use url::Url;
+use wreq::{
+    Uri,
+    header::{AUTHORIZATION, COOKIE, HeaderMap, HeaderValue, LOCATION, PROXY_AUTHORIZATION},
+};
+
+use crate::{
+    error::{CallbackError, RequestError},
+    gvl,
+    header::Headers,
+    http::StatusCode,
+};
+
+/// The number of redirects followed when no limit is given.
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// A redirect policy, set with the `redirect:` option.
+///
+/// Either a set of declarative rules, or a Ruby callable deciding each hop.
+#[derive(Clone)]
+pub struct RedirectPolicy {
+    /// The maximum number of redirects to follow.
+    max: Option<usize>,
+    /// Only follow redirects to the same scheme, host and port.
+    same_origin: bool,
+    /// Refuse redirects from `https` to `http`.
+    deny_downgrade: bool,
+    /// Remove credentials when a redirect leaves the original host.
+    strip_sensitive_headers: bool,
+    /// Only follow redirects to these hosts.
+    allowed_hosts: Option<Vec<String>>,
+    /// A Ruby callable receiving each [`RedirectHop`] and returning whether to follow it.
+    callback: Option<Opaque<Proc>>,
+}
+
+/// The redirect settings of a request, merged with the client's.
+#[derive(Clone, Default)]
+pub struct Redirects {
+    allow: Option<bool>,
+    max: Option<usize>,
+    policy: Option<RedirectPolicy>,
+    referer: Option<bool>,
+}
+
+/// How the next request differs from the original one after following redirects.
+#[derive(Default)]
+pub struct Hop {
+    /// The URL to request instead of the original one.
+    pub uri: Option<Uri>,
+    /// The method to use instead of the original one.
+    pub method: Option<wreq::Method>,
+    /// Whether the body is dropped, after a redirect switched to `GET`.
+    pub drop_body: bool,
+    /// Whether credentials are removed, after a redirect left the original host.
+    pub strip_sensitive_headers: bool,
+    /// The `Referer` header to send.
+    pub referer: Option<HeaderValue>,
+}
+
+/// A redirect response, as listed by `Response#history` and passed to redirect callbacks.
+#[derive(Clone)]
+#[magnus::wrap(class = "Wreq::RedirectHop", free_immediately, size)]
+pub struct RedirectHop {
+    status: StatusCode,
+    url: String,
+    location: String,
+    headers: HeaderMap,
+}
+
+// ===== impl RedirectPolicy =====
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            max: None,
+            same_origin: false,
+            deny_downgrade: false,
+            strip_sensitive_headers: true,
+            allowed_hosts: None,
+            callback: None,
+        }
+    }
+}
+
+impl RedirectPolicy {
+    /// Parse the `redirect:` option, either a callable or a Hash of rules.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        if RHash::from_value(value).is_none() && value.respond_to("call", false)? {
+            let callback = match Proc::from_value(value) {
+                Some(callback) => callback,
+                None => value
+                    .funcall::<_, _, Value>("method", ("call",))?
+                    .funcall("to_proc", ())?,
+            };
+            return Ok(Self {
+                callback: Some(Opaque::from(callback)),
+                ..Self::default()
+            });
+        }
+
+        let hash = RHash::try_convert(value)?;
+        let mut policy = Self::default();
+        if let Some(max) = get::<usize>(ruby, hash, "max")? {
+            policy.max = Some(max);
+        }
+        if let Some(same_origin) = get::<bool>(ruby, hash, "same_origin")? {
+            policy.same_origin = same_origin;
+        }
+        if let Some(deny_downgrade) = get::<bool>(ruby, hash, "deny_downgrade")? {
+            policy.deny_downgrade = deny_downgrade;
+        }
+        if let Some(strip) = get::<bool>(ruby, hash, "strip_sensitive_headers")? {
+            policy.strip_sensitive_headers = strip;
+        }
+        if let Some(hosts) = get::<Vec<String>>(ruby, hash, "allowed_hosts")? {
+            let hosts = hosts.iter().map(|host| host.to_ascii_lowercase()).collect();
+            policy.allowed_hosts = Some(hosts);
+        }
+        Ok(policy)
+    }
+
+    /// Mark the Ruby callback, if any, so it is not garbage collected.
+    pub fn mark(&self, marker: &Marker) {
+        if let Some(callback) = self.callback {
+            marker.mark(callback);
+        }
+    }
+
+    /// Check the declarative rules, returning why the redirect is refused, if it is.
+    fn check(&self, current: &Url, next: &Url) -> Option<&'static str> {
+        if self.deny_downgrade && current.scheme() == "https" && next.scheme() != "https" {
+            return Some("scheme downgrade");
+        }
+        if self.same_origin && current.origin() != next.origin() {
+            return Some("cross-origin redirect");
+        }
+        if let Some(hosts) = self.allowed_hosts.as_ref() {
+            let host = next.host_str().unwrap_or_default().to_ascii_lowercase();
+            if !hosts.contains(&host) {
+                return Some("host not allowed");
+            }
+        }
+        None
+    }
+
+    /// Ask the Ruby callback whether to follow `hop`.
+    fn ask(&self, callback: Opaque<Proc>, hop: RedirectHop) -> Result<bool, RequestError> {
+        gvl::with_gvl(|| {
+            let ruby = ruby!();
+            ruby.get_inner(callback)
+                .call::<_, Value>((hop,))
+                .map(|follow| follow.to_bool())
+                .map_err(|err| RequestError::Callback(CallbackError::new(err)))
+        })
+        .unwrap_or_else(|| {
+            Err(RequestError::Redirect(
+                "redirect callbacks cannot run in background requests".to_owned(),
+            ))
+        })
+    }
+}
+
+// ===== impl Redirects =====
+
+impl Redirects {
+    /// Create [`Redirects`] from the `allow_redirects:`, `max_redirects:`, `redirect:`
+    /// and `referer:` options.
+    pub fn new(
+        allow: Option<bool>,
+        max: Option<usize>,
+        policy: Option<RedirectPolicy>,
+        referer: Option<bool>,
+    ) -> Self {
+        Self {
+            allow,
+            max,
+            policy,
+            referer,
+        }
+    }
+
+    /// Fill the settings not given for a request with the client's.
+    pub fn or(self, client: &Redirects) -> Self {
+        Self {
+            allow: self.allow.or(client.allow),
+            max: self.max.or(client.max),
+            policy: self.policy.or_else(|| client.policy.clone()),
+            referer: self.referer.or(client.referer),
+        }
+    }
+
+    /// Mark the Ruby callback, if any, so it is not garbage collected.
+    pub fn mark(&self, marker: &Marker) {
+        if let Some(policy) = self.policy.as_ref() {
+            policy.mark(marker);
+        }
+    }
+
+    /// Returns `true` if a Ruby callback decides which redirects to follow.
+    pub fn asks(&self) -> bool {
+        self.follows()
+            && self
+                .policy
+                .as_ref()
+                .is_some_and(|policy| policy.callback.is_some())
+    }
+
+    /// Returns `true` if redirects may be followed at all.
+    ///
+    /// They are followed when asked to, or when a redirect policy is given.
+    pub fn follows(&self) -> bool {
+        self.allow.unwrap_or(self.policy.is_some())
+    }
+
+    /// Decide how to follow `response`, or `None` if it should be returned as is.
+    ///
+    /// `method` is the method of the original request, and `followed` the number
+    /// of redirects followed so far.
+    pub fn next_hop(
+        &self,
+        method: &wreq::Method,
+        hop: &Hop,
+        followed: usize,
+        response: &wreq::Response,
+    ) -> Result<Option<Hop>, RequestError> {
+        if !self.follows() {
+            return Ok(None);
+        }
+
+        let status = response.status();
+        if !matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308) {
+            return Ok(None);
+        }
+        let Some(location) = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+        else {
+            return Ok(None);
+        };
+
+        let current = Url::parse(&response.uri().to_string())
+            .map_err(|err| RequestError::Redirect(format!("invalid URL: {err}")))?;
+        let next = current
+            .join(location)
+            .map_err(|err| RequestError::Redirect(format!("invalid redirect location: {err}")))?;
+
+        let policy = self.policy.clone().unwrap_or_default();
+        let max = policy.max.or(self.max).unwrap_or(DEFAULT_MAX_REDIRECTS);
+        if followed >= max {
+            return Err(RequestError::Redirect(format!(
+                "too many redirects (max {max})"
+            )));
+        }
+        if let Some(reason) = policy.check(&current, &next) {
+            return Err(RequestError::Redirect(format!(
+                "redirect to {next} refused: {reason}"
+            )));
+        }
+        if let Some(callback) = policy.callback {
+            let redirect = RedirectHop::new(response, next.to_string());
+            if !policy.ask(callback, redirect)? {
+                return Ok(None);
+            }
+        }
+
+        let uri = next
+            .as_str()
+            .parse::<Uri>()
+            .map_err(|err| RequestError::Redirect(format!("invalid redirect location: {err}")))?;
+
+        // 303 always switches to GET; 301 and 302 do so for POST, like browsers.
+        let current_method = hop.method.as_ref().unwrap_or(method);
+        let switch_to_get = match status.as_u16() {
+            303 => *current_method != wreq::Method::HEAD,
+            301 | 302 => *current_method == wreq::Method::POST,
+            _ => false,
+        };
+
+        let cross_host = current.host_str() != next.host_str()
+            || current.port_or_known_default() != next.port_or_known_default();
+
+        // Never leak the previous URL from HTTPS to HTTP.
+        let referer = (self.referer.unwrap_or(true)
+            && !(current.scheme() == "https" && next.scheme() == "http"))
+            .then(|| {
+                let mut referer = current.clone();
+                referer.set_fragment(None);
+                let _ = referer.set_username("");
+                let _ = referer.set_password(None);
+                HeaderValue::from_str(referer.as_str()).ok()
+            })
+            .flatten();
+
+        Ok(Some(Hop {
+            uri: Some(uri),
+            method: switch_to_get
+                .then_some(wreq::Method::GET)
+                .or_else(|| hop.method.clone()),
+            drop_body: hop.drop_body || switch_to_get,
+            strip_sensitive_headers: hop.strip_sensitive_headers
+                || (cross_host && policy.strip_sensitive_headers),
+            referer,
+        }))
+    }
+}
+
+// ===== impl Hop =====
+
+impl Hop {
+    /// The URL this hop requests, or an empty string for the original request.
+    pub fn location(&self) -> String {
+        self.uri
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    }
+
+    /// Apply this hop to a request built from the original options.
+    pub fn apply(&self, request: &mut wreq::Request) {
+        if let Some(uri) = self.uri.as_ref() {
+            *request.uri_mut() = uri.clone();
+        }
+        if let Some(method) = self.method.as_ref() {
+            *request.method_mut() = method.clone();
+        }
+        if self.drop_body {
+            *request.body_mut() = None;
+        }
+        let headers = request.headers_mut();
+        if self.drop_body {
+            headers.remove(wreq::header::CONTENT_TYPE);
+            headers.remove(wreq::header::CONTENT_LENGTH);
+            headers.remove(wreq::header::TRANSFER_ENCODING);
+        }
+        if self.strip_sensitive_headers {
+            headers.remove(AUTHORIZATION);
+            headers.remove(COOKIE);
+            headers.remove(PROXY_AUTHORIZATION);
+        }
+        if let Some(referer) = self.referer.as_ref() {
+            headers.insert(wreq::header::REFERER, referer.clone());
+        }
+    }
+}
+
+// ===== impl RedirectHop =====
+
+impl RedirectHop {
+    /// Record a redirect response and where it points to.
+    pub fn new(response: &wreq::Response, location: String) -> Self {
+        Self {
+            status: StatusCode::from(response.status()),
+            url: response.uri().to_string(),
+            location,
+            headers: response.headers().clone(),
+        }
+    }
+
+    /// The status of the redirect response.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The URL that answered with the redirect.
+    #[inline]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// The absolute URL the redirect points to.
+    #[inline]
+    pub fn location(&self) -> String {
+        self.location.clone()
+    }
+
+    /// The headers of the redirect response.
+    #[inline]
+    pub fn headers(&self) -> Headers {
+        Headers::from(self.headers.clone())
+    }
+}
+
+/// Read an optional setting from the policy hash.
+fn get<T: TryConvert>(ruby: &Ruby, hash: RHash, key: &str) -> Result<Option<T>, Error> {
+    match hash.get(ruby.to_symbol(key)) {
+        Some(value) if !value.is_nil() => T::try_convert(value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let hop_class = gem_module.define_class("RedirectHop", ruby.class_object())?;
+    hop_class.define_method("status", method!(RedirectHop::status, 0))?;
+    hop_class.define_method("url", method!(RedirectHop::url, 0))?;
+    hop_class.define_method("location", method!(RedirectHop::location, 0))?;
+    hop_class.define_method("headers", method!(RedirectHop::headers, 0))?;
+    Ok(())
+}
@@ -1,18 +1,40 @@
-use std::{net::IpAddr, time::Duration};
+use std::{
+    future::{Future, poll_fn},
+    net::IpAddr,
+    path::PathBuf,
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
 
-use http::{HeaderValue, header};
+use bytes::Bytes;
+use http::{HeaderValue, Uri, header};
 use magnus::{RHash, TryConvert, typed_data::Obj, value::ReprValue};
 use serde::Deserialize;
 use wreq::{
-    Client, Proxy, Version,
+    RequestBuilder, Version,
     header::{HeaderMap, OrigHeaderMap},
 };
 
-use super::body::{Body, Form, Json};
+use super::body::{Body, Form, Json, Multipart, MultipartBody};
 use crate::{
-    client::{query::Query, resp::Response},
+    client::{
+        Client,
+        dns::{self, IpFamily, Overrides},
+        middleware::Context,
+        pending::Pending,
+        proxy::{Proxies, ProxySetting},
+        query::Query,
+        redirect::{Hop, RedirectHop, RedirectPolicy, Redirects},
+        resp::{
+            HeaderOrder, RaiseOnStatus, RequestInfo, Response, SentHeaders, extract_header_order,
+        },
+        retry::RetryPolicy,
+        timeout,
+        timings::{self, Recorder},
+    },
     emulate::Emulation,
-    error::wreq_error_to_magnus,
+    error::{RequestError, TimeoutPhase},
     extractor::Extractor,
     http::Method,
     rt,
@@ -26,9 +48,9 @@ pub struct Request {
     #[serde(skip)]
     emulation: Option<Emulation>,
 
-    /// The proxy to use for the request.
+    /// The proxies, or proxy pool, to use for the request.
     #[serde(skip)]
-    proxy: Option<Proxy>,
+    proxy: Option<ProxySetting>,
 
     /// Bind to a local IP Address.
     local_address: Option<IpAddr>,
@@ -36,11 +58,37 @@ pub struct Request {
     /// Bind to an interface by `SO_BINDTODEVICE`.
     interface: Option<String>,
 
+    /// Connect through a Unix domain socket instead of TCP.
+    #[cfg(unix)]
+    unix_socket: Option<PathBuf>,
+
+    /// The static addresses to use for host names.
+    #[serde(skip)]
+    resolve: Option<Overrides>,
+
+    /// The address family to connect with.
+    #[serde(skip)]
+    ip_family: Option<IpFamily>,
+
     /// The timeout to use for the request.
-    timeout: Option<u64>,
+    #[serde(skip)]
+    timeout: Option<Duration>,
 
     /// The read timeout to use for the request.
-    read_timeout: Option<u64>,
+    #[serde(skip)]
+    read_timeout: Option<Duration>,
+
+    /// The timeout for looking up host names.
+    #[serde(skip)]
+    dns_timeout: Option<Duration>,
+
+    /// The timeout for receiving the response headers.
+    #[serde(skip)]
+    first_byte_timeout: Option<Duration>,
+
+    /// The timeout for waiting for a connection from the pool.
+    #[serde(skip)]
+    pool_timeout: Option<Duration>,
 
     /// The HTTP version to use for the request.
     #[serde(skip)]
@@ -54,6 +102,10 @@ pub struct Request {
     #[serde(skip)]
     orig_headers: Option<OrigHeaderMap>,
 
+    /// The header names of `orig_headers`, in their original case.
+    #[serde(skip)]
+    header_order: Option<Vec<Bytes>>,
+
     /// The option enables default headers.
     default_headers: Option<bool>,
 
@@ -67,6 +119,10 @@ pub struct Request {
     /// The maximum number of redirects to follow.
     max_redirects: Option<usize>,
 
+    /// The redirect policy for the request.
+    #[serde(skip)]
+    redirect: Option<RedirectPolicy>,
+
     /// Sets gzip as an accepted encoding.
     gzip: Option<bool>,
 
@@ -97,9 +153,21 @@ pub struct Request {
     /// The JSON body to use for the request.
     json: Option<Json>,
 
+    /// The multipart form to use for the request.
+    #[serde(skip)]
+    multipart: Option<MultipartBody>,
+
     /// The body to use for the request.
     #[serde(skip)]
     body: Option<Body>,
+
+    /// The retry policy for the request.
+    #[serde(skip)]
+    retry: Option<RetryPolicy>,
+
+    /// The statuses that raise a `Wreq::StatusError`.
+    #[serde(skip)]
+    raise_on_status: Option<RaiseOnStatus>,
 }
 
 impl Request {
@@ -122,130 +190,544 @@ impl Request {
 
         // extra original headers handling
         builder.orig_headers = Extractor::<OrigHeaderMap>::try_convert(kwargs)?.into_inner();
+        builder.header_order = builder
+            .orig_headers
+            .is_some()
+            .then(|| extract_header_order(ruby, hash));
 
         // extra cookies handling
         builder.cookies = Extractor::<Vec<HeaderValue>>::try_convert(kwargs)?.into_inner();
 
         // extra proxy handling
-        builder.proxy = Extractor::<Proxy>::try_convert(kwargs)?.into_inner();
+        builder.proxy = Extractor::<ProxySetting>::try_convert(kwargs)?.into_inner();
+
+        // extra multipart handling
+        if let Some(multipart) = hash.get(ruby.to_symbol("multipart")) {
+            let multipart = Obj::<Multipart>::try_convert(multipart)?;
+            builder.multipart = Some(MultipartBody::try_from(&*multipart)?);
+        }
 
         // extra body handling
         if let Some(body) = hash.get(ruby.to_symbol("body")) {
             builder.body = Some(Body::try_convert(body)?);
         }
 
+        // extra retry handling
+        if let Some(retry) = hash.get(ruby.to_symbol("retry")).filter(|v| !v.is_nil()) {
+            builder.retry = Some(RetryPolicy::new(ruby, retry)?);
+        }
+
+        // extra redirect handling
+        if let Some(redirect) = hash.get(ruby.to_symbol("redirect")).filter(|v| !v.is_nil()) {
+            builder.redirect = Some(RedirectPolicy::new(ruby, redirect)?);
+        }
+
+        // extra resolve handling
+        if let Some(resolve) = hash.get(ruby.to_symbol("resolve")).filter(|v| !v.is_nil()) {
+            builder.resolve = Some(Overrides::new(ruby, resolve)?);
+        }
+
+        // extra IP family handling
+        if let Some(ip_family) = hash
+            .get(ruby.to_symbol("ip_family"))
+            .filter(|v| !v.is_nil())
+        {
+            builder.ip_family = Some(IpFamily::new(ruby, ip_family)?);
+        }
+
+        // extra timeout handling, in seconds as any Numeric
+        builder.timeout = timeout::extract_duration(ruby, hash, "timeout")?;
+        builder.read_timeout = timeout::extract_duration(ruby, hash, "read_timeout")?;
+        builder.dns_timeout = timeout::extract_duration(ruby, hash, "dns_timeout")?;
+        builder.first_byte_timeout = timeout::extract_duration(ruby, hash, "first_byte_timeout")?;
+        builder.pool_timeout = timeout::extract_duration(ruby, hash, "pool_timeout")?;
+
+        // extra status handling
+        if let Some(raise_on_status) = hash
+            .get(ruby.to_symbol("raise_on_status"))
+            .filter(|v| !v.is_nil())
+        {
+            builder.raise_on_status = Some(RaiseOnStatus::new(ruby, raise_on_status)?);
+        }
+
         Ok(builder)
     }
+
+    /// Raise for the statuses of `raise_on_status` unless the request set its own
+    /// `raise_on_status:`.
+    pub fn default_raise_on_status(&mut self, raise_on_status: RaiseOnStatus) {
+        self.raise_on_status.get_or_insert(raise_on_status);
+    }
+
+    /// Take the headers set for the request.
+    pub fn take_headers(&mut self) -> HeaderMap {
+        self.headers.take().unwrap_or_default()
+    }
+
+    /// Replace the headers set for the request.
+    pub fn set_headers(&mut self, headers: HeaderMap) {
+        self.headers = Some(headers);
+    }
+
+    /// Get the request body, if it is held in memory.
+    pub fn body_bytes(&self) -> Option<Bytes> {
+        match self.body.as_ref() {
+            Some(Body::Bytes(bytes)) => Some(bytes.clone()),
+            _ => None,
+        }
+    }
+
+    /// Replace the request body, dropping any form, JSON or multipart body.
+    pub fn set_body(&mut self, body: Option<Body>) {
+        self.form = None;
+        self.json = None;
+        self.multipart = None;
+        self.body = body;
+    }
+
+    /// Copy the request to send it again, or `None` if its body is a one-shot stream.
+    pub fn try_clone(&self) -> Option<Self> {
+        let multipart = match &self.multipart {
+            Some(multipart) => Some(multipart.try_clone()?),
+            None => None,
+        };
+        let body = match &self.body {
+            Some(body) => Some(body.try_clone()?),
+            None => None,
+        };
+        Some(Self {
+            emulation: self.emulation.clone(),
+            proxy: self.proxy.clone(),
+            local_address: self.local_address.clone(),
+            interface: self.interface.clone(),
+            #[cfg(unix)]
+            unix_socket: self.unix_socket.clone(),
+            resolve: self.resolve.clone(),
+            ip_family: self.ip_family.clone(),
+            timeout: self.timeout.clone(),
+            read_timeout: self.read_timeout.clone(),
+            dns_timeout: self.dns_timeout.clone(),
+            first_byte_timeout: self.first_byte_timeout.clone(),
+            pool_timeout: self.pool_timeout.clone(),
+            version: self.version.clone(),
+            headers: self.headers.clone(),
+            orig_headers: self.orig_headers.clone(),
+            header_order: self.header_order.clone(),
+            default_headers: self.default_headers.clone(),
+            cookies: self.cookies.clone(),
+            allow_redirects: self.allow_redirects.clone(),
+            max_redirects: self.max_redirects.clone(),
+            redirect: self.redirect.clone(),
+            gzip: self.gzip.clone(),
+            brotli: self.brotli.clone(),
+            deflate: self.deflate.clone(),
+            zstd: self.zstd.clone(),
+            auth: self.auth.clone(),
+            bearer_auth: self.bearer_auth.clone(),
+            basic_auth: self.basic_auth.clone(),
+            query: self.query.clone(),
+            form: self.form.clone(),
+            json: self.json.clone(),
+            retry: self.retry.clone(),
+            raise_on_status: self.raise_on_status.clone(),
+            multipart,
+            body,
+        })
+    }
+}
+
+/// Run a request through the client's middleware, returning the URL to send it to and
+/// the [`Context`] to finish its response with.
+pub fn prepare_request(
+    ruby: &magnus::Ruby,
+    client: &Client,
+    method: Method,
+    url: String,
+    request: &mut Request,
+) -> Result<(String, Context), magnus::Error> {
+    let (url, context) = client.middleware.before(ruby, method, url, request)?;
+    let raise_on_status = request
+        .raise_on_status
+        .take()
+        .or_else(|| client.raise_on_status.clone())
+        .unwrap_or_default();
+    Ok((url, context.with_raise_on_status(raise_on_status)))
+}
+
+/// Send a request through the client's middleware, blocking until the response headers
+/// are received.
+pub fn execute_request(
+    client: Client,
+    method: Method,
+    url: String,
+    mut request: Request,
+) -> Result<Obj<Response>, magnus::Error> {
+    let ruby = ruby!();
+    let (url, context) = prepare_request(&ruby, &client, method, url, &mut request)?;
+    let response = rt::try_block_on_request(send_request(client, method, url, request))?;
+    context.check_status(context.after(&ruby, response)?)
+}
+
+/// Send a request through the client's middleware in the background.
+pub fn spawn_request(
+    client: Client,
+    method: Method,
+    url: String,
+    mut request: Request,
+) -> Result<Pending, magnus::Error> {
+    let ruby = ruby!();
+    // Ruby callbacks are called on the thread sending the request, which a background
+    // request does not have.
+    if client.resolver.calls_ruby() {
+        return Err(magnus::Error::new(
+            ruby.exception_arg_error(),
+            "dns_resolver callables cannot be used in background requests",
+        ));
+    }
+    let redirects = Redirects::new(
+        request.allow_redirects,
+        request.max_redirects,
+        request.redirect.clone(),
+        None,
+    )
+    .or(&client.redirects);
+    if redirects.asks() {
+        return Err(magnus::Error::new(
+            ruby.exception_arg_error(),
+            "redirect callbacks cannot be used in background requests",
+        ));
+    }
+    let (url, context) = prepare_request(&ruby, &client, method, url, &mut request)?;
+    Ok(Pending::spawn(
+        send_request(client, method, url, request),
+        context,
+    ))
 }
 
-pub fn execute_request<U: AsRef<str>>(
+/// Build and send a request on the runtime, retrying it as allowed by its retry policy
+/// and following redirects as allowed by its redirect settings.
+///
+/// Errors are returned as [`RequestError`] so the future can run on any runtime thread.
+pub async fn send_request<U: AsRef<str>>(
     client: Client,
     method: Method,
     url: U,
     mut request: Request,
-) -> Result<Response, magnus::Error> {
-    rt::try_block_on(async move {
-        let mut builder = client.request(method.into_ffi(), url.as_ref());
-
-        // Emulation options.
-        apply_option!(set_if_some_inner, builder, request.emulation, emulation);
-
-        // Version options.
-        apply_option!(set_if_some, builder, request.version, version);
-
-        // Timeout options.
-        apply_option!(
-            set_if_some_map,
-            builder,
-            request.timeout,
-            timeout,
-            Duration::from_secs
-        );
-        apply_option!(
-            set_if_some_map,
-            builder,
-            request.read_timeout,
-            read_timeout,
-            Duration::from_secs
-        );
-
-        // Network options.
-        apply_option!(set_if_some, builder, request.proxy, proxy);
-        apply_option!(set_if_some, builder, request.local_address, local_address);
-        apply_option!(set_if_some, builder, request.interface, interface);
-
-        // Headers options.
-        apply_option!(set_if_some, builder, request.headers, headers);
-        apply_option!(set_if_some, builder, request.orig_headers, orig_headers);
-        apply_option!(
-            set_if_some,
-            builder,
-            request.default_headers,
-            default_headers
-        );
-
-        // Authentication options.
-        apply_option!(
-            set_if_some_map_ref,
-            builder,
-            request.auth,
-            auth,
-            AsRef::<str>::as_ref
-        );
-        apply_option!(set_if_some, builder, request.bearer_auth, bearer_auth);
-        if let Some(basic_auth) = request.basic_auth.take() {
-            builder = builder.basic_auth(basic_auth.0, basic_auth.1);
+) -> Result<Response, RequestError> {
+    let policy = request.retry.take().or(client.retry);
+    let max_attempts = policy
+        .as_ref()
+        .map_or(1, |policy| policy.max_attempts(method));
+    let redirects = Redirects::new(
+        request.allow_redirects,
+        request.max_redirects,
+        request.redirect.take(),
+        None,
+    )
+    .or(&client.redirects);
+    let header_order = client.header_order.for_request(request.header_order.take());
+    let dns = dns::Settings {
+        overrides: request.resolve.take(),
+        ip_family: request.ip_family.take(),
+        timeout: request.dns_timeout.take(),
+    };
+    let inner = client.for_dns(&dns)?;
+    let mut builder = inner
+        .request(method.into_ffi(), url.as_ref())
+        .redirect(wreq::redirect::Policy::none());
+
+    // Emulation options.
+    apply_option!(set_if_some_inner, builder, request.emulation, emulation);
+
+    // Version options.
+    apply_option!(set_if_some, builder, request.version, version);
+
+    // Timeout options.
+    apply_option!(set_if_some, builder, request.timeout, timeout);
+    apply_option!(set_if_some, builder, request.read_timeout, read_timeout);
+
+    // Network options.
+    apply_option!(set_if_some, builder, request.local_address, local_address);
+    apply_option!(set_if_some, builder, request.interface, interface);
+    // A Unix socket is reached as a proxy, in place of the proxies of the request and client.
+    #[cfg(unix)]
+    if let Some(path) = request.unix_socket.take() {
+        request.proxy = Some(ProxySetting::Fixed(Proxies::unix(path)?));
+    }
+
+    // Headers options.
+    let has_content_length = request
+        .headers
+        .as_ref()
+        .is_some_and(|headers| headers.contains_key(header::CONTENT_LENGTH));
+    apply_option!(set_if_some, builder, request.headers, headers);
+    apply_option!(set_if_some, builder, request.orig_headers, orig_headers);
+    apply_option!(
+        set_if_some,
+        builder,
+        request.default_headers,
+        default_headers
+    );
+
+    // Authentication options.
+    apply_option!(
+        set_if_some_map_ref,
+        builder,
+        request.auth,
+        auth,
+        AsRef::<str>::as_ref
+    );
+    apply_option!(set_if_some, builder, request.bearer_auth, bearer_auth);
+    if let Some(basic_auth) = request.basic_auth.take() {
+        builder = builder.basic_auth(basic_auth.0, basic_auth.1);
+    }
+
+    // Cookies options.
+    if let Some(cookies) = request.cookies.take() {
+        for cookie in cookies {
+            builder = builder.header(header::COOKIE, cookie);
         }
+    }
 
-        // Cookies options.
-        if let Some(cookies) = request.cookies.take() {
-            for cookie in cookies {
-                builder = builder.header(header::COOKIE, cookie);
+    // Compression options.
+    apply_option!(set_if_some, builder, request.gzip, gzip);
+    apply_option!(set_if_some, builder, request.brotli, brotli);
+    apply_option!(set_if_some, builder, request.deflate, deflate);
+    apply_option!(set_if_some, builder, request.zstd, zstd);
+
+    // Query options.
+    apply_option!(set_if_some_ref, builder, request.query, query);
+
+    // Form options.
+    apply_option!(set_if_some_ref, builder, request.form, form);
+
+    // JSON options.
+    apply_option!(set_if_some_ref, builder, request.json, json);
+
+    // Proxies are chosen for each attempt, so a pool can rotate and track them.
+    let route = Route {
+        uri: url.as_ref().parse().unwrap_or_default(),
+        proxy: request
+            .proxy
+            .take()
+            .or_else(|| client.proxy_pool.clone().map(ProxySetting::Pool)),
+        dns_timeout: dns.timeout,
+        first_byte_timeout: request
+            .first_byte_timeout
+            .take()
+            .or(client.first_byte_timeout),
+        pool_timeout: request.pool_timeout.take().or(client.pool_timeout),
+        header_order,
+    };
+    let mut payload = Payload {
+        multipart: request.multipart.take(),
+        body: request.body.take(),
+    };
+    let mut history = Vec::new();
+    let mut hop = Hop::default();
+    let mut attempt = 1;
+    loop {
+        // Keep a copy to send again, unless the body can only be sent once.
+        let retry = attempt < max_attempts;
+        let resend = retry || redirects.follows();
+        let next_builder = resend.then(|| builder.try_clone()).flatten();
+        let next_payload = resend.then(|| payload.try_clone()).flatten();
+        let result = send_once(&inner, builder, payload, &hop, &route, has_content_length).await;
+
+        let delay = match (&policy, &result) {
+            (Some(policy), Ok(response)) => policy.delay_for_response(attempt, response),
+            (Some(policy), Err(err @ (RequestError::Wreq(_) | RequestError::Timeout(_)))) => {
+                policy.delay_for_error(attempt, err.kind())
             }
+            _ => None,
         }
-
-        // Allow redirects options.
-        match request.allow_redirects {
-            Some(false) => {
-                builder = builder.redirect(wreq::redirect::Policy::none());
+        .filter(|_| retry);
+        let (next_builder, next_payload) = match (delay, next_builder, next_payload) {
+            (Some(delay), Some(next_builder), Some(next_payload)) => {
+                drop(result);
+                tokio::time::sleep(delay).await;
+                (builder, payload) = (next_builder, next_payload);
+                attempt += 1;
+                continue;
             }
-            Some(true) => {
-                builder = builder.redirect(
-                    request
-                        .max_redirects
-                        .take()
-                        .map(wreq::redirect::Policy::limited)
-                        .unwrap_or_default(),
-                );
+            (_, next_builder, next_payload) => (next_builder, next_payload),
+        };
+
+        let response = result?;
+        let next_hop = match next_builder {
+            Some(next_builder) => redirects
+                .next_hop(&method.into_ffi(), &hop, history.len(), &response)?
+                .map(|next_hop| (next_builder, next_hop)),
+            None => None,
+        };
+        // A body switched to `GET` is dropped, otherwise it must be sent again.
+        let next = match next_hop {
+            Some((next_builder, next_hop)) if next_hop.drop_body => {
+                Some((next_builder, Payload::default(), next_hop))
             }
-            None => {}
+            Some((next_builder, next_hop)) => match next_payload {
+                Some(next_payload) => Some((next_builder, next_payload, next_hop)),
+                None => {
+                    return Err(RequestError::Redirect(format!(
+                        "cannot follow the {} redirect to {}: the request body is a stream \
+                         that cannot be sent again",
+                        response.status().as_u16(),
+                        next_hop.location()
+                    )));
+                }
+            },
+            None => None,
+        };
+        let Some((next_builder, next_payload, next_hop)) = next else {
+            return Ok(Response::new(response)
+                .with_attempts(attempt)
+                .with_history(history));
         };
 
-        // Compression options.
-        apply_option!(set_if_some, builder, request.gzip, gzip);
-        apply_option!(set_if_some, builder, request.brotli, brotli);
-        apply_option!(set_if_some, builder, request.deflate, deflate);
-        apply_option!(set_if_some, builder, request.zstd, zstd);
+        history.push(RedirectHop::new(&response, next_hop.location()));
+        (builder, payload, hop) = (next_builder, next_payload, next_hop);
+        attempt = 1;
+    }
+}
+
+/// How each attempt of a request reaches the server.
+struct Route {
+    /// The URL of the request, before following redirects.
+    uri: Uri,
+    proxy: Option<ProxySetting>,
+    /// How long each host name lookup may take.
+    dns_timeout: Option<Duration>,
+    /// How long to wait for the response headers of each attempt.
+    first_byte_timeout: Option<Duration>,
+    /// How long each attempt may wait for a connection from the pool.
+    pool_timeout: Option<Duration>,
+    /// The order of the headers named in `orig_headers:`, to describe what was sent.
+    header_order: HeaderOrder,
+}
+
+/// The multipart form or body of a request, attached to it only when it is sent.
+#[derive(Default)]
+struct Payload {
+    multipart: Option<MultipartBody>,
+    body: Option<Body>,
+}
+
+impl Payload {
+    /// Copy the payload to send it again, or `None` if it cannot be replayed.
+    fn try_clone(&self) -> Option<Self> {
+        let multipart = match &self.multipart {
+            Some(multipart) => Some(multipart.try_clone()?),
+            None => None,
+        };
+        let body = match &self.body {
+            Some(body) => Some(body.try_clone()?),
+            None => None,
+        };
+        Some(Self { multipart, body })
+    }
+}
 
-        // Query options.
-        apply_option!(set_if_some_ref, builder, request.query, query);
+/// Attach the multipart form or body, opening files on the runtime, and send the request
+/// as changed by the redirects followed so far, through the proxy of its route.
+async fn send_once(
+    client: &wreq::Client,
+    mut builder: RequestBuilder,
+    payload: Payload,
+    hop: &Hop,
+    route: &Route,
+    has_content_length: bool,
+) -> Result<wreq::Response, RequestError> {
+    // Multipart options.
+    if let Some(multipart) = payload.multipart {
+        builder = builder.multipart(multipart.into_form().await?);
+    }
 
-        // Form options.
-        apply_option!(set_if_some_ref, builder, request.form, form);
+    // Body options.
+    if let Some(body) = payload.body {
+        let (body, content_length) = body.into_body().await?;
+        if let Some(content_length) = content_length.filter(|_| !has_content_length) {
+            builder = builder.header(header::CONTENT_LENGTH, content_length);
+        }
+        builder = builder.body(body);
+    }
 
-        // JSON options.
-        apply_option!(set_if_some_ref, builder, request.json, json);
+    // Proxy options.
+    let target = hop.uri.as_ref().unwrap_or(&route.uri);
+    let mut checkout = None;
+    if let Some((proxy, pool_checkout)) =
+        route.proxy.as_ref().and_then(|proxy| proxy.for_uri(target))
+    {
+        builder = builder.proxy(proxy);
+        checkout = pool_checkout;
+    }
 
-        // Body options.
-        if let Some(body) = request.body.take() {
-            builder = builder.body(wreq::Body::from(body));
+    // Send request.
+    let mut request = builder.build()?;
+    hop.apply(&mut request);
+    let info = RequestInfo::new(&request, &route.header_order);
+    let uri = request.uri().clone();
+    let recorder = Recorder::new();
+    let body = request.body_mut().take();
+    *request.body_mut() = Some(timings::watch_request(recorder.clone(), body));
+    let sent = SentHeaders::default();
+    let execute = timings::scope(
+        recorder.clone(),
+        dns::scope(
+            route.dns_timeout,
+            &uri,
+            // Created within the scopes, so the client hands the request over in them.
+            sent.scope(async move { client.execute(request).await }),
+        ),
+    );
+    let execute = async {
+        match route.pool_timeout {
+            Some(timeout) => check_out(&recorder, timeout, execute).await,
+            None => execute.await.map_err(Into::into),
         }
+    };
+    let result = match route.first_byte_timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, execute).await {
+            Ok(result) => result,
+            Err(_) => Err(RequestError::Timeout(TimeoutPhase::FirstByte)),
+        },
+        None => execute.await,
+    };
+    if let Some(checkout) = checkout {
+        checkout.finish(result.as_ref().err());
+    }
+    result.map(|mut response| {
+        recorder.first_byte();
+        response
+            .extensions_mut()
+            .insert(info.with_sent_headers(sent.take()));
+        response.extensions_mut().insert(recorder);
+        response
+    })
+}
 
-        // Send request.
-        builder
-            .send()
-            .await
-            .map(Response::new)
-            .map_err(wreq_error_to_magnus)
+/// Send a request with `execute`, failing it if it waits for a connection from the pool
+/// for longer than `timeout`.
+///
+/// The wait ends once the connector is asked for a new connection or a connection
+/// starts sending the request, as `recorder` reports.
+async fn check_out<F>(
+    recorder: &Recorder,
+    timeout: Duration,
+    execute: F,
+) -> Result<wreq::Response, RequestError>
+where
+    F: Future<Output = wreq::Result<wreq::Response>>,
+{
+    let mut execute = pin!(execute);
+    let mut deadline = pin!(tokio::time::sleep(timeout));
+    poll_fn(|cx| {
+        if let Poll::Ready(result) = execute.as_mut().poll(cx) {
+            return Poll::Ready(result.map_err(Into::into));
+        }
+        if !recorder.checked_out() && deadline.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Err(RequestError::Timeout(TimeoutPhase::Pool)));
+        }
+        Poll::Pending
     })
+    .await
 }
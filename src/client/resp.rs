@@ -1,23 +1,45 @@
-use std::{net::SocketAddr, sync::Arc};
+mod download;
+mod request_info;
+mod status;
+mod tls_info;
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
 use arc_swap::ArcSwapOption;
-use bytes::Bytes;
-use futures_util::TryFutureExt;
+use bytes::{Bytes, BytesMut};
+use futures_util::{Stream, StreamExt, TryFutureExt, future, stream};
 use http::{Extensions, HeaderMap, response::Response as HttpResponse};
 use http_body_util::BodyExt;
-use magnus::{Error, Module, RArray, RModule, Ruby, Value, block::Yield};
+use magnus::{
+    Error, Module, RArray, RHash, RModule, Ruby, Symbol, Value, block::Proc, scan_args,
+    typed_data::Obj, value::ReprValue,
+};
 use wreq::Uri;
 
+pub use self::download::{DownloadOptions, DownloadResult, extract_path};
+pub use self::request_info::{
+    HeaderOrder, RequestInfo, SentHeaders, SentHeadersLayer, extract_header_order,
+};
+pub use self::status::RaiseOnStatus;
+use self::tls_info::TlsInfo;
 use crate::{
-    client::body::{BodyReceiver, Json},
+    client::{
+        body::{BodyReceiver, Json},
+        redirect::RedirectHop,
+        sse::EventReceiver,
+        timings::{self, Recorder, Timings},
+    },
     cookie::Cookie,
-    error::{memory_error, wreq_error_to_magnus},
+    error::{RequestError, memory_error, status_error},
     gvl,
     header::Headers,
     http::{StatusCode, Version},
     rt,
 };
 
+/// How many bytes of the body a `StatusError` carries.
+const BODY_PREVIEW_LIMIT: usize = 1024;
+
 /// A response from a request.
 #[magnus::wrap(class = "Wreq::Response", free_immediately, size)]
 pub struct Response {
@@ -30,6 +52,10 @@ pub struct Response {
     remote_addr: Option<SocketAddr>,
     body: ArcSwapOption<Body>,
     extensions: Extensions,
+    attempts: u32,
+    history: Vec<RedirectHop>,
+    request: Option<RequestInfo>,
+    recorder: Option<Arc<Recorder>>,
 }
 
 /// Represents the state of the HTTP response body.
@@ -48,7 +74,17 @@ impl Response {
         let local_addr = response.local_addr();
         let remote_addr = response.remote_addr();
         let response = HttpResponse::from(response);
-        let (parts, body) = response.into_parts();
+        let (mut parts, body) = response.into_parts();
+        let version = Version::from_ffi(parts.version);
+        let request = parts
+            .extensions
+            .remove::<RequestInfo>()
+            .map(|request| request.with_version(version));
+        let recorder = parts.extensions.remove::<Arc<Recorder>>();
+        let body = match recorder.clone() {
+            Some(recorder) => timings::watch_body(recorder, body),
+            None => body,
+        };
 
         Response {
             uri,
@@ -56,13 +92,29 @@ impl Response {
             remote_addr,
             content_length,
             extensions: parts.extensions,
-            version: Version::from_ffi(parts.version),
+            version,
             status: StatusCode::from(parts.status),
             headers: parts.headers,
             body: ArcSwapOption::from_pointee(Body::Streamable(body)),
+            attempts: 1,
+            history: Vec::new(),
+            request,
+            recorder,
         }
     }
 
+    /// Record how many attempts it took to get this response.
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Record the redirects followed to get this response.
+    pub fn with_history(mut self, history: Vec<RedirectHop>) -> Self {
+        self.history = history;
+        self
+    }
+
     /// Internal method to get the wreq::Response, optionally streaming the body.
     fn response(&self, stream: bool) -> Result<wreq::Response, Error> {
         let build_response = |body: wreq::Body| -> wreq::Response {
@@ -80,10 +132,10 @@ impl Response {
                     return if stream {
                         Ok(build_response(body))
                     } else {
-                        let bytes = rt::try_block_on(
+                        let bytes = rt::try_block_on_request(
                             BodyExt::collect(body)
                                 .map_ok(|buf| buf.to_bytes())
-                                .map_err(wreq_error_to_magnus),
+                                .map_err(RequestError::from),
                         )?;
 
                         self.body
@@ -106,6 +158,76 @@ impl Response {
 
         Err(memory_error())
     }
+
+    /// Internal method to read up to `limit` bytes of the body, leaving it readable.
+    fn peek(&self, limit: usize) -> Result<Bytes, Error> {
+        let Some(arc) = self.body.swap(None) else {
+            return Err(memory_error());
+        };
+        let body = match Arc::try_unwrap(arc) {
+            Ok(Body::Streamable(body)) => body,
+            Ok(Body::Reusable(bytes)) => {
+                let preview = bytes.slice(..bytes.len().min(limit));
+                self.body.store(Some(Arc::new(Body::Reusable(bytes))));
+                return Ok(preview);
+            }
+            Err(_) => return Err(memory_error()),
+        };
+
+        let (read, rest) = rt::try_block_on_request(async move {
+            let mut body = body;
+            let mut read = BytesMut::new();
+            while read.len() < limit {
+                match body.frame().await {
+                    Some(frame) => {
+                        if let Ok(data) = frame?.into_data() {
+                            read.extend_from_slice(&data);
+                        }
+                    }
+                    None => return Ok((read.freeze(), None)),
+                }
+            }
+            Ok((read.freeze(), Some(body)))
+        })?;
+
+        // Put back what was read in front of the rest of the body, if any.
+        let body = match rest {
+            None => Body::Reusable(read.clone()),
+            Some(rest) => Body::Streamable(wreq::Body::wrap_stream(
+                stream::once(future::ready(Ok::<_, wreq::Error>(read.clone())))
+                    .chain(rest.into_data_stream()),
+            )),
+        };
+        self.body.store(Some(Arc::new(body)));
+        Ok(read.slice(..read.len().min(limit)))
+    }
+
+    /// Internal method to build the `Wreq::StatusError` for this response, with a
+    /// preview of its body.
+    fn status_error(&self) -> Error {
+        let kind = if self.status.is_client_error() {
+            "client error"
+        } else if self.status.is_server_error() {
+            "server error"
+        } else {
+            "unexpected status"
+        };
+        let message = format!(
+            "HTTP status {kind} ({}) for url ({})",
+            self.status.0, self.uri
+        );
+        let body = self
+            .peek(BODY_PREVIEW_LIMIT)
+            .ok()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+        status_error(message, self.status, self.headers(), self.url(), body)
+    }
+
+    /// Internal method to stream the body into a file at `path`.
+    pub fn save(&self, path: PathBuf, options: DownloadOptions) -> Result<DownloadResult, Error> {
+        let stream = self.response(true)?.bytes_stream();
+        download::save_to(Box::pin(stream), path, self.content_length, options)
+    }
 }
 
 impl Response {
@@ -167,46 +289,136 @@ impl Response {
         self.remote_addr.map(|addr| addr.to_string())
     }
 
+    /// Get the address family of the remote socket address, `:v4` or `:v6`.
+    pub fn ip_family(ruby: &Ruby, rb_self: &Self) -> Option<Symbol> {
+        rb_self.remote_addr.map(|addr| match addr {
+            SocketAddr::V4(_) => ruby.to_symbol("v4"),
+            SocketAddr::V6(_) => ruby.to_symbol("v6"),
+        })
+    }
+
+    /// Get the details of the TLS session, if the client records them.
+    #[inline]
+    pub fn tls_info(&self) -> Option<TlsInfo> {
+        self.extensions
+            .get::<wreq::tls::TlsInfo>()
+            .map(|info| TlsInfo::new(info, &self.uri, self.version))
+    }
+
+    /// Get the number of attempts made, including retries.
+    #[inline]
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Get the redirects followed to get this response, oldest first.
+    #[inline]
+    pub fn history(&self) -> Vec<RedirectHop> {
+        self.history.clone()
+    }
+
+    /// Get the request sent for this response, frozen.
+    pub fn request(ruby: &Ruby, rb_self: &Self) -> Option<Obj<RequestInfo>> {
+        rb_self.request.clone().map(|request| {
+            let request = ruby.obj_wrap(request);
+            request.freeze();
+            request
+        })
+    }
+
+    /// Get the durations of the phases of the request, as measured so far.
+    #[inline]
+    pub fn timings(&self) -> Option<Timings> {
+        self.recorder.as_ref().map(|recorder| recorder.timings())
+    }
+
+    /// Raise a `Wreq::StatusError` if the status is 4xx or 5xx, returning the response
+    /// otherwise.
+    pub fn raise_for_status(rb_self: Obj<Response>) -> Result<Obj<Response>, Error> {
+        RaiseOnStatus::Errors.check(rb_self)
+    }
+
     /// Get the response body as bytes.
     pub fn bytes(&self) -> Result<Bytes, Error> {
         let response = self.response(false)?;
-        rt::try_block_on(response.bytes().map_err(wreq_error_to_magnus))
+        rt::try_block_on_request(response.bytes().map_err(RequestError::from))
     }
 
     /// Get the response body as a UTF-8 string.
     pub fn text(&self) -> Result<String, Error> {
         let response = self.response(false)?;
-        rt::try_block_on(response.text().map_err(wreq_error_to_magnus))
+        rt::try_block_on_request(response.text().map_err(RequestError::from))
     }
 
     ///  Get the full response text given a specific encoding.
     pub fn text_with_charset(&self, default_encoding: String) -> Result<String, Error> {
         let response = self.response(false)?;
-        rt::try_block_on(
+        rt::try_block_on_request(
             response
                 .text_with_charset(default_encoding)
-                .map_err(wreq_error_to_magnus),
+                .map_err(RequestError::from),
         )
     }
 
     /// Get the response body as JSON.
     pub fn json(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
         let response = rb_self.response(false)?;
-        rt::try_block_on(async move {
-            let json = response
-                .json::<Json>()
-                .await
-                .map_err(wreq_error_to_magnus)?;
-            serde_magnus::serialize(ruby, &json)
-        })
+        let json = rt::try_block_on_request(response.json::<Json>().map_err(RequestError::from))?;
+        serde_magnus::serialize(ruby, &json)
+    }
+
+    /// Yield each chunk of the response body to the block.
+    ///
+    /// Without a block, returns an Enumerator over the chunks.
+    pub fn chunks(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
+        if !ruby.block_given() {
+            let receiver = ruby.current_receiver::<Value>()?;
+            return Ok(receiver.enumeratorize("chunks", ()).as_value());
+        }
+        let receiver = BodyReceiver::new(rb_self.bytes_stream()?);
+        while let Some(chunk) = receiver.recv()? {
+            let _: Value = ruby.yield_value(chunk)?;
+        }
+        Ok(ruby.qnil().as_value())
+    }
+
+    /// Yield each Server-Sent Event of the response body to the block.
+    ///
+    /// Without a block, returns an Enumerator over the events.
+    pub fn each_event(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
+        if !ruby.block_given() {
+            let receiver = ruby.current_receiver::<Value>()?;
+            return Ok(receiver.enumeratorize("each_event", ()).as_value());
+        }
+        let mut receiver = EventReceiver::new(rb_self.bytes_stream()?);
+        while let Some(event) = receiver.recv()? {
+            let _: Value = ruby.yield_value(event)?;
+        }
+        Ok(ruby.qnil().as_value())
     }
 
-    /// Get a chunk iterator for the response body.
-    pub fn chunks(&self) -> Result<Yield<BodyReceiver>, Error> {
-        self.response(true)
-            .map(wreq::Response::bytes_stream)
-            .map(BodyReceiver::new)
-            .map(Yield::Iter)
+    /// Take the response body as a stream of chunks.
+    pub fn bytes_stream(
+        &self,
+    ) -> Result<impl Stream<Item = wreq::Result<Bytes>> + Send + 'static, Error> {
+        self.response(true).map(wreq::Response::bytes_stream)
+    }
+
+    /// Save the response body to a file, streaming it on the runtime without the GVL.
+    pub fn save_to(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<DownloadResult, Error> {
+        let args = scan_args::scan_args::<(Value,), (), (), (), RHash, Option<Proc>>(args)?;
+        let (path,) = args.required;
+        let options = DownloadOptions::new(ruby, args.keywords, args.block)?;
+        rb_self.save(download::extract_path(path)?, options)
+    }
+
+    /// Copy the response body into a Ruby IO object, chunk by chunk.
+    pub fn copy_to(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<DownloadResult, Error> {
+        let args = scan_args::scan_args::<(Value,), (), (), (), RHash, Option<Proc>>(args)?;
+        let (io,) = args.required;
+        let options = DownloadOptions::new(ruby, args.keywords, args.block)?;
+        let stream = rb_self.response(true)?.bytes_stream();
+        download::copy_to(ruby, Box::pin(stream), io, rb_self.content_length, options)
     }
 
     /// Close the response body, dropping any resources.
@@ -237,6 +449,16 @@ pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
     response_class.define_method("headers", magnus::method!(Response::headers, 0))?;
     response_class.define_method("local_addr", magnus::method!(Response::local_addr, 0))?;
     response_class.define_method("remote_addr", magnus::method!(Response::remote_addr, 0))?;
+    response_class.define_method("ip_family", magnus::method!(Response::ip_family, 0))?;
+    response_class.define_method("tls_info", magnus::method!(Response::tls_info, 0))?;
+    response_class.define_method("attempts", magnus::method!(Response::attempts, 0))?;
+    response_class.define_method("history", magnus::method!(Response::history, 0))?;
+    response_class.define_method("request", magnus::method!(Response::request, 0))?;
+    response_class.define_method("timings", magnus::method!(Response::timings, 0))?;
+    response_class.define_method(
+        "raise_for_status",
+        magnus::method!(Response::raise_for_status, 0),
+    )?;
     response_class.define_method("bytes", magnus::method!(Response::bytes, 0))?;
     response_class.define_method("text", magnus::method!(Response::text, 0))?;
     response_class.define_method(
@@ -245,6 +467,17 @@ pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
     )?;
     response_class.define_method("json", magnus::method!(Response::json, 0))?;
     response_class.define_method("chunks", magnus::method!(Response::chunks, 0))?;
+    response_class.define_method("each_event", magnus::method!(Response::each_event, 0))?;
+    response_class.define_method("save_to", magnus::method!(Response::save_to, -1))?;
+    response_class.define_method("copy_to", magnus::method!(Response::copy_to, -1))?;
     response_class.define_method("close", magnus::method!(Response::close, 0))?;
+
+    let download_class = gem_module.define_class("DownloadResult", ruby.class_object())?;
+    download_class.define_method("path", magnus::method!(DownloadResult::path, 0))?;
+    download_class.define_method("bytes", magnus::method!(DownloadResult::bytes, 0))?;
+    download_class.define_method("digest", magnus::method!(DownloadResult::digest, 0))?;
+
+    request_info::include(ruby, gem_module)?;
+    tls_info::include(ruby, gem_module)?;
     Ok(())
 }
@@ -0,0 +1,289 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use magnus::{Error, RHash, RString, Ruby, Value, block::Proc, value::ReprValue};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use tokio::{fs::File, io::AsyncWriteExt};
+
+use crate::{error::RequestError, rt};
+
+/// Counter used to keep temporary file names unique within the process.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// The result of saving or copying a response body.
+#[magnus::wrap(class = "Wreq::DownloadResult", free_immediately, size)]
+pub struct DownloadResult {
+    path: Option<String>,
+    bytes: u64,
+    digest: Option<String>,
+}
+
+/// Options accepted by `save_to`, `copy_to` and `download`.
+pub struct DownloadOptions {
+    atomic: bool,
+    digest: Option<Hasher>,
+    progress: Option<Proc>,
+}
+
+/// A running digest over the body bytes.
+enum Hasher {
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+}
+
+/// A file being written, optionally through a temporary file that is renamed on success.
+struct FileSink {
+    file: File,
+    path: PathBuf,
+    temp_path: Option<PathBuf>,
+    finished: bool,
+}
+
+// ===== impl DownloadResult =====
+
+impl DownloadResult {
+    /// The path the body was written to, or `nil` for `copy_to`.
+    #[inline]
+    pub fn path(&self) -> Option<String> {
+        self.path.clone()
+    }
+
+    /// The number of body bytes written.
+    #[inline]
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// The hex-encoded digest of the body, if requested.
+    #[inline]
+    pub fn digest(&self) -> Option<String> {
+        self.digest.clone()
+    }
+}
+
+// ===== impl DownloadOptions =====
+
+impl DownloadOptions {
+    /// Create [`DownloadOptions`] from Ruby keyword arguments and an optional progress block.
+    pub fn new(ruby: &Ruby, keywords: RHash, progress: Option<Proc>) -> Result<Self, Error> {
+        let atomic = match keywords.get(ruby.to_symbol("atomic")) {
+            Some(value) => value.to_bool(),
+            None => false,
+        };
+
+        let digest = match keywords.get(ruby.to_symbol("digest")) {
+            Some(value) if !value.is_nil() => {
+                let name: String = value.funcall("to_s", ())?;
+                Some(Hasher::new(ruby, &name)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            atomic,
+            digest,
+            progress,
+        })
+    }
+}
+
+// ===== impl Hasher =====
+
+impl Hasher {
+    fn new(ruby: &Ruby, name: &str) -> Result<Self, Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "sha256" => Ok(Hasher::Sha256(Sha256::new())),
+            "sha384" => Ok(Hasher::Sha384(Sha384::new())),
+            "sha512" => Ok(Hasher::Sha512(Sha512::new())),
+            _ => Err(Error::new(
+                ruby.exception_arg_error(),
+                format!("unsupported digest algorithm: {name} (expected sha256, sha384 or sha512)"),
+            )),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha384(hasher) => hasher.update(data),
+            Hasher::Sha512(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> String {
+        let digest = match self {
+            Hasher::Sha256(hasher) => hasher.finalize().to_vec(),
+            Hasher::Sha384(hasher) => hasher.finalize().to_vec(),
+            Hasher::Sha512(hasher) => hasher.finalize().to_vec(),
+        };
+        digest.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+// ===== impl FileSink =====
+
+impl FileSink {
+    async fn create(path: PathBuf, atomic: bool) -> std::io::Result<Self> {
+        let temp_path = atomic.then(|| temp_path_for(&path));
+        let file = File::create(temp_path.as_ref().unwrap_or(&path)).await?;
+        Ok(Self {
+            file,
+            path,
+            temp_path,
+            finished: false,
+        })
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> std::io::Result<()> {
+        self.file.write_all(chunk).await
+    }
+
+    async fn finish(mut self) -> std::io::Result<()> {
+        self.file.flush().await?;
+        self.file.sync_all().await?;
+        if let Some(temp_path) = self.temp_path.as_ref() {
+            tokio::fs::rename(temp_path, &self.path).await?;
+        }
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for FileSink {
+    fn drop(&mut self) {
+        // Never leave a partial temporary file behind.
+        if !self.finished {
+            if let Some(temp_path) = self.temp_path.take() {
+                let _ = std::fs::remove_file(temp_path);
+            }
+        }
+    }
+}
+
+/// Build a unique temporary path in the same directory as `path`, so the rename is atomic.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let counter = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{file_name}.{}.{nanos:x}{counter:x}.tmp",
+        std::process::id()
+    ))
+}
+
+/// Write the body stream to `path`.
+///
+/// Without a progress block the whole copy runs on the runtime in a single GVL release;
+/// with one, the GVL is reacquired between chunks to report progress.
+pub fn save_to<S>(
+    mut stream: S,
+    path: PathBuf,
+    total: Option<u64>,
+    mut options: DownloadOptions,
+) -> Result<DownloadResult, Error>
+where
+    S: Stream<Item = wreq::Result<Bytes>> + Unpin,
+{
+    let display_path = path.to_string_lossy().into_owned();
+    let mut sink =
+        rt::try_block_on_request(async { Ok(FileSink::create(path, options.atomic).await?) })?;
+
+    let mut written = 0u64;
+    let hasher = &mut options.digest;
+    match options.progress {
+        None => rt::try_block_on_request(async {
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                sink.write(&chunk).await?;
+                if let Some(hasher) = hasher.as_mut() {
+                    hasher.update(&chunk);
+                }
+                written += chunk.len() as u64;
+            }
+            Ok(())
+        })?,
+        Some(progress) => loop {
+            let len = rt::try_block_on_request(async {
+                match stream.next().await {
+                    Some(chunk) => {
+                        let chunk = chunk?;
+                        sink.write(&chunk).await?;
+                        if let Some(hasher) = hasher.as_mut() {
+                            hasher.update(&chunk);
+                        }
+                        Ok(Some(chunk.len() as u64))
+                    }
+                    None => Ok(None),
+                }
+            })?;
+            match len {
+                Some(len) => {
+                    written += len;
+                    progress.call::<_, Value>((written, total))?;
+                }
+                None => break,
+            }
+        },
+    }
+
+    rt::try_block_on_request(async { Ok(sink.finish().await?) })?;
+
+    Ok(DownloadResult {
+        path: Some(display_path),
+        bytes: written,
+        digest: options.digest.map(Hasher::finalize),
+    })
+}
+
+/// Write the body stream to a Ruby IO object, reading each chunk without the GVL.
+pub fn copy_to<S>(
+    ruby: &Ruby,
+    mut stream: S,
+    io: Value,
+    total: Option<u64>,
+    mut options: DownloadOptions,
+) -> Result<DownloadResult, Error>
+where
+    S: Stream<Item = wreq::Result<Bytes>> + Unpin,
+{
+    let mut written = 0u64;
+    while let Some(chunk) =
+        rt::try_block_on_request(async { Ok(stream.next().await.transpose()?) })?
+    {
+        let _: Value = io.funcall("write", (ruby.str_from_slice(&chunk),))?;
+        if let Some(hasher) = options.digest.as_mut() {
+            hasher.update(&chunk);
+        }
+        written += chunk.len() as u64;
+        if let Some(progress) = options.progress.as_ref() {
+            progress.call::<_, Value>((written, total))?;
+        }
+    }
+
+    Ok(DownloadResult {
+        path: None,
+        bytes: written,
+        digest: options.digest.map(Hasher::finalize),
+    })
+}
+
+/// Convert a Ruby path-like value (String or Pathname) into a [`PathBuf`].
+pub fn extract_path(path: Value) -> Result<PathBuf, Error> {
+    if let Some(s) = RString::from_value(path) {
+        return Ok(PathBuf::from(s.to_string()?));
+    }
+    let path: String = path.funcall("to_path", ())?;
+    Ok(PathBuf::from(path))
+}
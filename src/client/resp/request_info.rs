@@ -0,0 +1,277 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use magnus::{Error, Module, RArray, RHash, RModule, RString, Ruby, method, value::ReprValue};
+use tower::{Layer, Service};
+use wreq::header::{CONTENT_LENGTH, HOST, HeaderMap, HeaderName, HeaderValue};
+
+use crate::http::{Method, Version};
+
+tokio::task_local! {
+    /// Where the headers of the request attempt being sent are recorded.
+    static SENT: SentHeaders;
+}
+
+/// The order a client sends the headers named in `orig_headers:` in, with their
+/// original case.
+#[derive(Clone, Default)]
+pub struct HeaderOrder(Vec<Bytes>);
+
+/// The headers of a request attempt, recorded as the client hands it to be sent.
+///
+/// They include everything the client adds itself, such as its default headers, the
+/// emulation's, cookies and `Accept-Encoding`.
+#[derive(Clone, Default)]
+pub struct SentHeaders(Arc<Mutex<Option<HeaderMap>>>);
+
+/// Records the headers of each request sent through the client, see [`SentHeaders`].
+#[derive(Clone, Default)]
+pub struct SentHeadersLayer;
+
+/// The service wrapped by [`SentHeadersLayer`].
+#[derive(Clone)]
+pub struct RecordSentHeaders<S> {
+    inner: S,
+}
+
+/// The request sent for a response, as returned by `Response#request`.
+#[derive(Clone)]
+#[magnus::wrap(class = "Wreq::RequestInfo", free_immediately, size)]
+pub struct RequestInfo {
+    method: Method,
+    url: String,
+    host: Option<HeaderValue>,
+    headers: HeaderMap,
+    order: Vec<Bytes>,
+    version: Version,
+    body_size: Option<u64>,
+}
+
+/// Read the `orig_headers:` option as the header names in their original case.
+pub fn extract_header_order(ruby: &Ruby, hash: RHash) -> Vec<Bytes> {
+    hash.get(ruby.to_symbol("orig_headers"))
+        .and_then(RArray::from_value)
+        .map(|names| {
+            names
+                .into_iter()
+                .flat_map(RString::from_value)
+                .map(|name| name.to_bytes())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ===== impl HeaderOrder =====
+
+impl HeaderOrder {
+    /// Create the order of a client from its `orig_headers:` option.
+    pub fn new(order: Vec<Bytes>) -> Self {
+        Self(order)
+    }
+
+    /// Get the order of one request: the names of its `orig_headers:` option first, then
+    /// the client's.
+    pub fn for_request(&self, order: Option<Vec<Bytes>>) -> Self {
+        let mut names = order.unwrap_or_default();
+        names.extend(self.0.iter().cloned());
+        Self(names)
+    }
+}
+
+// ===== impl SentHeaders =====
+
+impl SentHeaders {
+    /// Run `future`, which sends one request attempt, recording the headers it sends.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        SENT.scope(self.clone(), future).await
+    }
+
+    /// Take the headers recorded, if the request was handed to be sent.
+    pub fn take(&self) -> Option<HeaderMap> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).take()
+    }
+
+    fn record(&self, headers: &HeaderMap) {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner()) = Some(headers.clone());
+    }
+}
+
+// ===== impl SentHeadersLayer =====
+
+impl<S> Layer<S> for SentHeadersLayer {
+    type Service = RecordSentHeaders<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RecordSentHeaders { inner }
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for RecordSentHeaders<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let _ = SENT.try_with(|sent| sent.record(req.headers()));
+        self.inner.call(req)
+    }
+}
+
+// ===== impl RequestInfo =====
+
+impl RequestInfo {
+    /// Describe `request` before it is sent, with its own headers until those the client
+    /// sent are known.
+    pub fn new(request: &wreq::Request, order: &HeaderOrder) -> Self {
+        let headers = request.headers().clone();
+        let body_size = match request.body() {
+            None => Some(0),
+            Some(body) => body.as_bytes().map(|bytes| bytes.len() as u64).or_else(|| {
+                headers
+                    .get(CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse().ok())
+            }),
+        };
+        let uri = request.uri();
+        let host = uri.host().and_then(|host| {
+            let host = match uri.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_owned(),
+            };
+            HeaderValue::from_str(&host).ok()
+        });
+
+        Self {
+            method: Method::from_ffi(request.method().clone()),
+            url: uri.to_string(),
+            host,
+            headers,
+            order: order.0.clone(),
+            version: Version::from_ffi(request.version()),
+            body_size,
+        }
+    }
+
+    /// Record the headers the client sent, as recorded by [`SentHeaders`].
+    pub fn with_sent_headers(mut self, headers: Option<HeaderMap>) -> Self {
+        if let Some(headers) = headers {
+            self.headers = headers;
+        }
+        self
+    }
+
+    /// Record the HTTP version the request was sent with.
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Get the header names and values in the order they were sent.
+    ///
+    /// Names listed in `orig_headers:` come first with their original case, as the
+    /// client orders them; HTTP/2 and later send every name in lowercase.
+    fn sent_headers(&self) -> Vec<(Bytes, HeaderValue)> {
+        let lowercase = !matches!(
+            self.version,
+            Version::HTTP_09 | Version::HTTP_10 | Version::HTTP_11
+        );
+        let mut headers = self.headers.clone();
+        // HTTP/1 sends the host as a header, HTTP/2 as the `:authority` pseudo-header.
+        if !lowercase && !headers.contains_key(HOST) {
+            if let Some(host) = self.host.clone() {
+                headers.insert(HOST, host);
+            }
+        }
+
+        let mut sent = Vec::with_capacity(headers.len());
+        let mut seen = HashSet::new();
+        for orig in &self.order {
+            let Ok(name) = HeaderName::from_bytes(orig) else {
+                continue;
+            };
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            let orig = if lowercase {
+                Bytes::copy_from_slice(name.as_str().as_bytes())
+            } else {
+                orig.clone()
+            };
+            for value in headers.get_all(&name) {
+                sent.push((orig.clone(), value.clone()));
+            }
+        }
+        for (name, value) in headers.iter() {
+            if !seen.contains(name) {
+                sent.push((
+                    Bytes::copy_from_slice(name.as_str().as_bytes()),
+                    value.clone(),
+                ));
+            }
+        }
+        sent
+    }
+}
+
+impl RequestInfo {
+    /// Get the request method, after any redirect that changed it.
+    #[inline]
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Get the request URL, after any redirects.
+    #[inline]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Get the sent headers as `[name, value]` pairs, in the order they were sent.
+    pub fn headers(ruby: &Ruby, rb_self: &Self) -> Result<RArray, Error> {
+        let sent = rb_self.sent_headers();
+        let headers = ruby.ary_new_capa(sent.len());
+        for (name, value) in sent {
+            let pair = ruby.ary_new_capa(2);
+            pair.push(ruby.str_from_slice(&name))?;
+            pair.push(ruby.str_from_slice(value.as_bytes()))?;
+            pair.freeze();
+            headers.push(pair)?;
+        }
+        headers.freeze();
+        Ok(headers)
+    }
+
+    /// Get the HTTP version the request was sent with.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Get the size of the sent body in bytes, or `None` for a stream of unknown length.
+    #[inline]
+    pub fn body_size(&self) -> Option<u64> {
+        self.body_size
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let request_info_class = gem_module.define_class("RequestInfo", ruby.class_object())?;
+    request_info_class.define_method("method", method!(RequestInfo::method, 0))?;
+    request_info_class.define_method("url", method!(RequestInfo::url, 0))?;
+    request_info_class.define_method("headers", method!(RequestInfo::headers, 0))?;
+    request_info_class.define_method("version", method!(RequestInfo::version, 0))?;
+    request_info_class.define_method("body_size", method!(RequestInfo::body_size, 0))?;
+    Ok(())
+}
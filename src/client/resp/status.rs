@@ -0,0 +1,74 @@
+use magnus::{Error, RArray, Ruby, TryConvert, Value, typed_data::Obj, value::ReprValue};
+
+use super::Response;
+use crate::http::StatusCode;
+
+/// The statuses that raise a `Wreq::StatusError`, set with the `raise_on_status:` option.
+#[derive(Clone, Default)]
+pub enum RaiseOnStatus {
+    /// Never raise.
+    #[default]
+    Off,
+    /// Raise on any 4xx or 5xx status.
+    Errors,
+    /// Raise on the given status codes only.
+    Codes(Vec<u16>),
+}
+
+// ===== impl RaiseOnStatus =====
+
+impl RaiseOnStatus {
+    /// Parse the `raise_on_status:` option, given as `true`, `false` or an Array of
+    /// status codes.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        if let Some(codes) = RArray::from_value(value) {
+            let codes = codes
+                .into_iter()
+                .map(|code| {
+                    let code = u16::try_convert(code)?;
+                    if (100..=999).contains(&code) {
+                        Ok(code)
+                    } else {
+                        Err(Error::new(
+                            ruby.exception_arg_error(),
+                            format!("invalid raise_on_status code: {code}"),
+                        ))
+                    }
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            return Ok(Self::Codes(codes));
+        }
+        if value.is_kind_of(ruby.class_true_class()) {
+            Ok(Self::Errors)
+        } else if value.is_kind_of(ruby.class_false_class()) {
+            Ok(Self::Off)
+        } else {
+            Err(Error::new(
+                ruby.exception_type_error(),
+                format!(
+                    "raise_on_status must be true, false or an Array of status codes, got {}",
+                    value.inspect()
+                ),
+            ))
+        }
+    }
+
+    /// Returns `true` if a response with `status` should raise.
+    pub fn matches(&self, status: StatusCode) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Errors => status.is_client_error() || status.is_server_error(),
+            Self::Codes(codes) => codes.contains(&status.0.as_u16()),
+        }
+    }
+
+    /// Pass `response` through, or raise a `Wreq::StatusError` for it if its status
+    /// matches.
+    pub fn check(&self, response: Obj<Response>) -> Result<Obj<Response>, Error> {
+        if self.matches(response.status()) {
+            Err(response.status_error())
+        } else {
+            Ok(response)
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::net::IpAddr;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bytes::Bytes;
+use magnus::{Error, Module, RModule, Ruby, Time, method};
+use wreq::Uri;
+use x509_parser::{
+    extensions::GeneralName,
+    prelude::{FromDer, X509Certificate},
+};
+
+use crate::http::Version;
+
+/// Details of the TLS session a response was received on.
+#[magnus::wrap(class = "Wreq::TlsInfo", free_immediately, size)]
+pub struct TlsInfo {
+    alpn: Option<&'static str>,
+    sni: Option<String>,
+    chain: Vec<Certificate>,
+}
+
+/// A peer certificate, with the fields most useful for debugging parsed out.
+#[derive(Clone)]
+#[magnus::wrap(class = "Wreq::Certificate", free_immediately, size)]
+pub struct Certificate {
+    der: Bytes,
+    subject: Option<String>,
+    issuer: Option<String>,
+    subject_alt_names: Vec<String>,
+    not_before: Option<i64>,
+    not_after: Option<i64>,
+}
+
+// ===== impl TlsInfo =====
+
+impl TlsInfo {
+    /// Collect the TLS details recorded by wreq for a response to `uri`.
+    pub fn new(info: &wreq::tls::TlsInfo, uri: &Uri, version: Version) -> Self {
+        let mut chain = info
+            .peer_certificate_chain()
+            .map(|chain| chain.map(Certificate::from_der).collect::<Vec<_>>())
+            .unwrap_or_default();
+        // The chain usually starts with the leaf certificate, but is not required to.
+        if let Some(leaf) = info.peer_certificate() {
+            if chain.first().is_none_or(|first| first.der != leaf) {
+                chain.insert(0, Certificate::from_der(leaf));
+            }
+        }
+
+        // The protocol is inferred from the HTTP version the connection ended up using.
+        let alpn = match version {
+            Version::HTTP_2 => Some("h2"),
+            Version::HTTP_11 => Some("http/1.1"),
+            _ => None,
+        };
+        // SNI is only sent for host names, never for IP addresses.
+        let sni = uri
+            .host()
+            .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+            .filter(|host| host.parse::<IpAddr>().is_err())
+            .map(ToOwned::to_owned);
+
+        Self { alpn, sni, chain }
+    }
+
+    /// Get the application protocol negotiated with ALPN.
+    #[inline]
+    pub fn alpn(&self) -> Option<&'static str> {
+        self.alpn
+    }
+
+    /// Get the server name sent with SNI.
+    #[inline]
+    pub fn sni(&self) -> Option<String> {
+        self.sni.clone()
+    }
+
+    /// Get the leaf certificate of the peer.
+    #[inline]
+    pub fn peer_certificate(&self) -> Option<Certificate> {
+        self.chain.first().cloned()
+    }
+
+    /// Get the certificate chain of the peer, leaf first.
+    #[inline]
+    pub fn peer_certificates(&self) -> Vec<Certificate> {
+        self.chain.clone()
+    }
+}
+
+// ===== impl Certificate =====
+
+impl Certificate {
+    /// Parse a DER certificate; fields that cannot be parsed are left empty.
+    fn from_der(der: &[u8]) -> Self {
+        let mut certificate = Self {
+            der: Bytes::copy_from_slice(der),
+            subject: None,
+            issuer: None,
+            subject_alt_names: Vec::new(),
+            not_before: None,
+            not_after: None,
+        };
+        let Ok((_, cert)) = X509Certificate::from_der(der) else {
+            return certificate;
+        };
+
+        certificate.subject = Some(cert.subject().to_string());
+        certificate.issuer = Some(cert.issuer().to_string());
+        certificate.not_before = Some(cert.validity().not_before.timestamp());
+        certificate.not_after = Some(cert.validity().not_after.timestamp());
+        if let Ok(Some(san)) = cert.subject_alternative_name() {
+            certificate.subject_alt_names = san
+                .value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(name) => Some(name.to_string()),
+                    GeneralName::IPAddress(bytes) => ip_address(bytes).map(|ip| ip.to_string()),
+                    GeneralName::URI(uri) => Some(uri.to_string()),
+                    GeneralName::RFC822Name(email) => Some(email.to_string()),
+                    _ => None,
+                })
+                .collect();
+        }
+        certificate
+    }
+
+    /// Get the certificate in DER encoding.
+    #[inline]
+    pub fn der(&self) -> Bytes {
+        self.der.clone()
+    }
+
+    /// Get the certificate in PEM encoding.
+    pub fn to_pem(&self) -> String {
+        let encoded = STANDARD.encode(&self.der);
+        let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+        for line in encoded.as_bytes().chunks(64) {
+            pem.push_str(&String::from_utf8_lossy(line));
+            pem.push('\n');
+        }
+        pem.push_str("-----END CERTIFICATE-----\n");
+        pem
+    }
+
+    /// Get the subject distinguished name.
+    #[inline]
+    pub fn subject(&self) -> Option<String> {
+        self.subject.clone()
+    }
+
+    /// Get the issuer distinguished name.
+    #[inline]
+    pub fn issuer(&self) -> Option<String> {
+        self.issuer.clone()
+    }
+
+    /// Get the DNS names, IP addresses, URIs and emails of the subject alternative names.
+    #[inline]
+    pub fn subject_alt_names(&self) -> Vec<String> {
+        self.subject_alt_names.clone()
+    }
+
+    /// Get the start of the validity period.
+    pub fn not_before(ruby: &Ruby, rb_self: &Self) -> Result<Option<Time>, Error> {
+        rb_self
+            .not_before
+            .map(|secs| ruby.time_new(secs, 0))
+            .transpose()
+    }
+
+    /// Get the end of the validity period.
+    pub fn not_after(ruby: &Ruby, rb_self: &Self) -> Result<Option<Time>, Error> {
+        rb_self
+            .not_after
+            .map(|secs| ruby.time_new(secs, 0))
+            .transpose()
+    }
+}
+
+/// Decode an IPv4 or IPv6 address from a subject alternative name.
+fn ip_address(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let tls_info_class = gem_module.define_class("TlsInfo", ruby.class_object())?;
+    tls_info_class.define_method("alpn", method!(TlsInfo::alpn, 0))?;
+    tls_info_class.define_method("sni", method!(TlsInfo::sni, 0))?;
+    tls_info_class.define_method("peer_certificate", method!(TlsInfo::peer_certificate, 0))?;
+    tls_info_class.define_method("peer_certificates", method!(TlsInfo::peer_certificates, 0))?;
+
+    let certificate_class = gem_module.define_class("Certificate", ruby.class_object())?;
+    certificate_class.define_method("der", method!(Certificate::der, 0))?;
+    certificate_class.define_method("to_pem", method!(Certificate::to_pem, 0))?;
+    certificate_class.define_method("subject", method!(Certificate::subject, 0))?;
+    certificate_class.define_method("issuer", method!(Certificate::issuer, 0))?;
+    certificate_class.define_method(
+        "subject_alt_names",
+        method!(Certificate::subject_alt_names, 0),
+    )?;
+    certificate_class.define_method("not_before", method!(Certificate::not_before, 0))?;
+    certificate_class.define_method("not_after", method!(Certificate::not_after, 0))?;
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    time::{Duration, SystemTime},
+};
+
+use magnus::{
+    Error, Integer, RArray, RHash, Ruby, TryConvert, Value, typed_data::Obj, value::ReprValue,
+};
+use wreq::header::{HeaderMap, RETRY_AFTER};
+
+use crate::{client::timeout, error::ErrorKind, http::Method};
+
+/// Methods retried by default, as they are idempotent.
+const IDEMPOTENT_METHODS: &[Method] = &[
+    Method::GET,
+    Method::HEAD,
+    Method::PUT,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::TRACE,
+];
+
+/// Statuses retried by default.
+const RETRY_STATUSES: &[u16] = &[429, 502, 503, 504];
+
+/// Errors retried by default.
+const RETRY_ERRORS: &[ErrorKind] = &[
+    ErrorKind::Connection,
+    ErrorKind::ConnectionReset,
+    ErrorKind::Timeout,
+];
+
+/// How failed requests are retried, set with the `retry:` option.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    attempts: u32,
+    /// The delay before the first retry, doubled for every further retry.
+    backoff: Duration,
+    /// The upper bound for any delay, including `Retry-After`.
+    max_backoff: Duration,
+    /// Whether to randomize delays between zero and the computed backoff.
+    jitter: bool,
+    /// The response statuses that trigger a retry.
+    statuses: Vec<u16>,
+    /// The error kinds that trigger a retry.
+    errors: Vec<ErrorKind>,
+    /// The methods that may be retried.
+    methods: Vec<Method>,
+    /// Whether to wait as long as the `Retry-After` response header asks.
+    retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            statuses: RETRY_STATUSES.to_vec(),
+            errors: RETRY_ERRORS.to_vec(),
+            methods: IDEMPOTENT_METHODS.to_vec(),
+            retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Parse the `retry:` option.
+    ///
+    /// `true` uses the defaults, `false` disables retries, an Integer sets the maximum
+    /// number of attempts, and a Hash overrides any of the settings.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        if value.is_kind_of(ruby.class_true_class()) {
+            return Ok(Self::default());
+        }
+        if value.is_kind_of(ruby.class_false_class()) {
+            return Ok(Self {
+                attempts: 1,
+                ..Self::default()
+            });
+        }
+        if let Some(attempts) = Integer::from_value(value) {
+            return Ok(Self {
+                attempts: extract_attempts(ruby, attempts.to_u32()?)?,
+                ..Self::default()
+            });
+        }
+
+        let hash = RHash::try_convert(value)?;
+        let mut policy = Self::default();
+        if let Some(attempts) = get::<u32>(ruby, hash, "attempts")? {
+            policy.attempts = extract_attempts(ruby, attempts)?;
+        }
+        if let Some(backoff) = timeout::extract_duration(ruby, hash, "backoff")? {
+            policy.backoff = backoff;
+        }
+        if let Some(max_backoff) = timeout::extract_duration(ruby, hash, "max_backoff")? {
+            policy.max_backoff = max_backoff;
+        }
+        if let Some(jitter) = get::<bool>(ruby, hash, "jitter")? {
+            policy.jitter = jitter;
+        }
+        if let Some(statuses) = get::<Vec<u16>>(ruby, hash, "statuses")? {
+            policy.statuses = statuses;
+        }
+        if let Some(errors) = get::<RArray>(ruby, hash, "errors")? {
+            policy.errors = errors
+                .into_iter()
+                .map(|class| {
+                    ErrorKind::from_class(ruby, class).ok_or_else(|| {
+                        Error::new(
+                            ruby.exception_arg_error(),
+                            format!("cannot retry on {}", class.inspect()),
+                        )
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(methods) = get::<Vec<Obj<Method>>>(ruby, hash, "methods")? {
+            policy.methods = methods.into_iter().map(|method| *method).collect();
+        }
+        if let Some(retry_after) = get::<bool>(ruby, hash, "retry_after")? {
+            policy.retry_after = retry_after;
+        }
+        Ok(policy)
+    }
+
+    /// Returns `true` if the default policy retries a failure of `kind`, or a response
+    /// with `status`.
+    pub fn retries_by_default(kind: ErrorKind, status: Option<u16>) -> bool {
+        RETRY_ERRORS.contains(&kind)
+            || status.is_some_and(|status| RETRY_STATUSES.contains(&status))
+    }
+
+    /// The maximum number of attempts for a request with the given method.
+    pub fn max_attempts(&self, method: Method) -> u32 {
+        if self.methods.contains(&method) {
+            self.attempts
+        } else {
+            1
+        }
+    }
+
+    /// The delay before retrying after `response`, or `None` if it should be returned as is.
+    pub fn delay_for_response(&self, attempt: u32, response: &wreq::Response) -> Option<Duration> {
+        if !self.statuses.contains(&response.status().as_u16()) {
+            return None;
+        }
+        if self.retry_after {
+            if let Some(delay) = parse_retry_after(response.headers()) {
+                // Never retry sooner than asked; give up if asked to wait too long.
+                return (delay <= self.max_backoff).then_some(delay);
+            }
+        }
+        Some(self.backoff(attempt))
+    }
+
+    /// The delay before retrying after an error of `kind`, or `None` if it should be raised.
+    pub fn delay_for_error(&self, attempt: u32, kind: ErrorKind) -> Option<Duration> {
+        self.errors.contains(&kind).then(|| self.backoff(attempt))
+    }
+
+    /// Exponential backoff for the given (1-based) attempt, with optional full jitter.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.backoff.saturating_mul(factor).min(self.max_backoff);
+        if self.jitter {
+            let random = RandomState::new().hash_one(attempt) as f64 / u64::MAX as f64;
+            delay.mul_f64(random)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Read an optional setting from the policy hash.
+fn get<T: TryConvert>(ruby: &Ruby, hash: RHash, key: &str) -> Result<Option<T>, Error> {
+    match hash.get(ruby.to_symbol(key)) {
+        Some(value) if !value.is_nil() => T::try_convert(value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn extract_attempts(ruby: &Ruby, attempts: u32) -> Result<u32, Error> {
+    if attempts == 0 {
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            "retry attempts must be at least 1",
+        ));
+    }
+    Ok(attempts)
+}
+
+/// Parse a `Retry-After` header, given either in seconds or as an HTTP date.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
@@ -0,0 +1,377 @@
+use std::{collections::VecDeque, mem, pin::Pin, time::Duration};
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use http::{HeaderValue, header};
+use magnus::{
+    Error, Integer, Module, RHash, RModule, Ruby, Value, method, scan_args, value::ReprValue,
+};
+
+use crate::{
+    client::{
+        Client,
+        req::{Request, execute_request},
+        resp::RaiseOnStatus,
+        timeout,
+    },
+    error::{is_network_error, wreq_error_to_magnus},
+    http::Method,
+    rt,
+};
+
+/// How long to wait before reconnecting, until the server sets it with `retry:`.
+const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// The UTF-8 byte order mark, skipped at the start of a stream.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// An event received from a `text/event-stream` response.
+#[derive(Clone)]
+#[magnus::wrap(class = "Wreq::ServerSentEvent", free_immediately, size)]
+pub struct ServerSentEvent {
+    event: String,
+    data: String,
+    id: Option<String>,
+    retry: Option<u64>,
+}
+
+/// Splits a `text/event-stream` body into events, as the HTML standard parses it.
+#[derive(Default)]
+struct Decoder {
+    /// Bytes received after the last complete line.
+    buffer: Vec<u8>,
+    /// Whether the start of the stream, with its optional BOM, was seen.
+    started: bool,
+    /// Whether the last line ended with `\r`, so a following `\n` belongs to it.
+    skip_lf: bool,
+    event: String,
+    data: String,
+    retry: Option<u64>,
+    /// The last `id:` received, kept across events and reconnects.
+    last_event_id: String,
+    /// The reconnect delay set by the last valid `retry:` field.
+    reconnect_delay: Option<Duration>,
+    events: VecDeque<ServerSentEvent>,
+}
+
+/// Reads the events of a response body.
+pub struct EventReceiver {
+    body: Pin<Box<dyn Stream<Item = wreq::Result<Bytes>> + Send>>,
+    decoder: Decoder,
+}
+
+/// The options of `Client#sse` besides those of the request.
+struct SseOptions {
+    /// Whether to reconnect when the stream ends or the connection fails.
+    reconnect: bool,
+    /// How many reconnects in a row may fail to receive an event, without a limit if `None`.
+    max_reconnects: Option<u32>,
+    /// How long to wait before reconnecting, until the server sets it with `retry:`.
+    delay: Duration,
+}
+
+/// Open a Server-Sent Events stream with `client`, yielding each event to the block.
+///
+/// Without a block, returns an Enumerator over the events.
+pub fn execute_sse(ruby: &Ruby, client: &Client, args: &[Value]) -> Result<Value, Error> {
+    if !ruby.block_given() {
+        let receiver = ruby.current_receiver::<Value>()?;
+        return Ok(receiver.enumeratorize("sse", args).as_value());
+    }
+
+    let args = scan_args::scan_args::<(String,), (), (), (), RHash, ()>(args)?;
+    let (url,) = args.required;
+    let options = SseOptions::new(ruby, args.keywords)?;
+
+    // The options are read once, as their streams and forms can only be taken once, so
+    // each reconnect sends a copy of the request.
+    let request = Request::new(ruby, args.keywords)?;
+    let replay = if options.reconnect {
+        Some(request.try_clone().ok_or_else(|| {
+            Error::new(
+                ruby.exception_arg_error(),
+                "reconnect cannot be used with a streamed body, which can only be sent once",
+            )
+        })?)
+    } else {
+        None
+    };
+    let mut first = Some(request);
+
+    let mut decoder = Decoder::default();
+    let mut reconnects = 0;
+    loop {
+        let mut request = match first.take() {
+            Some(request) => request,
+            None => replay
+                .as_ref()
+                .and_then(Request::try_clone)
+                .expect("a request checked to be replayable is copied again"),
+        };
+        decoder.apply(&mut request);
+
+        let failure = match execute_request(client.clone(), Method::GET, url.clone(), request) {
+            Ok(response) => {
+                // The server asks the client to stop reconnecting with a 204.
+                if response.status().0 == http::StatusCode::NO_CONTENT {
+                    return Ok(ruby.qnil().as_value());
+                }
+                let response = RaiseOnStatus::Errors.check(response)?;
+                let mut receiver = EventReceiver::with_decoder(response.bytes_stream()?, decoder);
+                let failure = loop {
+                    match rt::try_block_on(async { Ok(receiver.next_event().await) })? {
+                        Some(Ok(event)) => {
+                            reconnects = 0;
+                            let _: Value = ruby.yield_value(event)?;
+                        }
+                        Some(Err(err)) => break Some(wreq_error_to_magnus(err)),
+                        None => break None,
+                    }
+                };
+                decoder = receiver.into_decoder();
+                failure
+            }
+            Err(err) if is_network_error(&err) => Some(err),
+            Err(err) => return Err(err),
+        };
+
+        if !options.reconnect || options.max_reconnects.is_some_and(|max| reconnects >= max) {
+            return match failure {
+                Some(err) => Err(err),
+                None => Ok(ruby.qnil().as_value()),
+            };
+        }
+        reconnects += 1;
+        let delay = decoder.reconnect_delay.unwrap_or(options.delay);
+        rt::try_block_on(async move {
+            tokio::time::sleep(delay).await;
+            Ok(())
+        })?;
+    }
+}
+
+// ===== impl ServerSentEvent =====
+
+impl ServerSentEvent {
+    /// Get the event type, `"message"` unless the server set one with `event:`.
+    #[inline]
+    pub fn event(&self) -> String {
+        self.event.clone()
+    }
+
+    /// Get the event data, its `data:` lines joined with newlines.
+    #[inline]
+    pub fn data(&self) -> String {
+        self.data.clone()
+    }
+
+    /// Get the last event ID set by the server, or `None` if it set none.
+    #[inline]
+    pub fn id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    /// Get the reconnect delay in milliseconds set with this event, if any.
+    #[inline]
+    pub fn retry(&self) -> Option<u64> {
+        self.retry
+    }
+}
+
+// ===== impl Decoder =====
+
+impl Decoder {
+    /// Decode the lines completed by `chunk`.
+    fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+        if !self.started {
+            if self.buffer.len() < BOM.len() && BOM.starts_with(&self.buffer) {
+                return;
+            }
+            if self.buffer.starts_with(BOM) {
+                self.buffer.drain(..BOM.len());
+            }
+            self.started = true;
+        }
+
+        let mut start = 0;
+        loop {
+            if self.skip_lf && start < self.buffer.len() {
+                if self.buffer[start] == b'\n' {
+                    start += 1;
+                }
+                self.skip_lf = false;
+            }
+            let Some(len) = self.buffer[start..]
+                .iter()
+                .position(|&byte| byte == b'\r' || byte == b'\n')
+            else {
+                break;
+            };
+            let end = start + len;
+            self.skip_lf = self.buffer[end] == b'\r';
+            let line = String::from_utf8_lossy(&self.buffer[start..end]).into_owned();
+            self.line(&line);
+            start = end + 1;
+        }
+        self.buffer.drain(..start);
+    }
+
+    /// Process one line: a field, a comment, or the blank line ending an event.
+    fn line(&mut self, line: &str) {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_owned(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.to_owned(),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse() {
+                    self.retry = Some(millis);
+                    self.reconnect_delay = Some(Duration::from_millis(millis));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Queue the event collected so far; events without data are dropped.
+    fn dispatch(&mut self) {
+        let retry = self.retry.take();
+        let event = mem::take(&mut self.event);
+        if self.data.is_empty() {
+            return;
+        }
+        let mut data = mem::take(&mut self.data);
+        data.pop();
+        self.events.push_back(ServerSentEvent {
+            event: if event.is_empty() {
+                "message".to_owned()
+            } else {
+                event
+            },
+            data,
+            id: Some(self.last_event_id.clone()).filter(|id| !id.is_empty()),
+            retry,
+        });
+    }
+
+    /// Forget the partial event of a closed stream, keeping the last event ID and
+    /// reconnect delay for the next one.
+    fn reset(self) -> Self {
+        Self {
+            last_event_id: self.last_event_id,
+            reconnect_delay: self.reconnect_delay,
+            ..Self::default()
+        }
+    }
+
+    /// Ask for an event stream, resuming after the last event ID received.
+    fn apply(&self, request: &mut Request) {
+        let mut headers = request.take_headers();
+        if !headers.contains_key(header::ACCEPT) {
+            headers.insert(
+                header::ACCEPT,
+                HeaderValue::from_static("text/event-stream"),
+            );
+        }
+        if !headers.contains_key(header::CACHE_CONTROL) {
+            headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        }
+        if !self.last_event_id.is_empty() {
+            if let Ok(id) = HeaderValue::from_str(&self.last_event_id) {
+                headers.insert("last-event-id", id);
+            }
+        }
+        request.set_headers(headers);
+    }
+}
+
+// ===== impl EventReceiver =====
+
+impl EventReceiver {
+    /// Create a new [`EventReceiver`] for a response body.
+    #[inline]
+    pub fn new(body: impl Stream<Item = wreq::Result<Bytes>> + Send + 'static) -> Self {
+        Self::with_decoder(body, Decoder::default())
+    }
+
+    fn with_decoder(
+        body: impl Stream<Item = wreq::Result<Bytes>> + Send + 'static,
+        decoder: Decoder,
+    ) -> Self {
+        Self {
+            body: Box::pin(body),
+            decoder: decoder.reset(),
+        }
+    }
+
+    /// Read the body until the next event, returning `None` at its end.
+    async fn next_event(&mut self) -> Option<wreq::Result<ServerSentEvent>> {
+        loop {
+            if let Some(event) = self.decoder.events.pop_front() {
+                return Some(Ok(event));
+            }
+            match self.body.next().await? {
+                Ok(chunk) => self.decoder.feed(&chunk),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    /// Wait for the next event, returning `None` at the end of the body.
+    pub fn recv(&mut self) -> Result<Option<ServerSentEvent>, Error> {
+        rt::try_block_on(async { Ok(self.next_event().await) })?
+            .transpose()
+            .map_err(wreq_error_to_magnus)
+    }
+
+    fn into_decoder(self) -> Decoder {
+        self.decoder
+    }
+}
+
+// ===== impl SseOptions =====
+
+impl SseOptions {
+    /// Read the `reconnect:` and `reconnect_delay:` options.
+    fn new(ruby: &Ruby, keywords: RHash) -> Result<Self, Error> {
+        let (reconnect, max_reconnects) = match keywords
+            .get(ruby.to_symbol("reconnect"))
+            .filter(|v| !v.is_nil())
+        {
+            Some(value) => match Integer::from_value(value) {
+                Some(max) => (true, Some(max.to_u32()?)),
+                None => (value.to_bool(), None),
+            },
+            None => (false, None),
+        };
+        let delay = timeout::extract_duration(ruby, keywords, "reconnect_delay")?
+            .unwrap_or(DEFAULT_RECONNECT_DELAY);
+        Ok(Self {
+            reconnect,
+            max_reconnects,
+            delay,
+        })
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let event_class = gem_module.define_class("ServerSentEvent", ruby.class_object())?;
+    event_class.define_method("event", method!(ServerSentEvent::event, 0))?;
+    event_class.define_method("data", method!(ServerSentEvent::data, 0))?;
+    event_class.define_method("id", method!(ServerSentEvent::id, 0))?;
+    event_class.define_method("retry", method!(ServerSentEvent::retry, 0))?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+use magnus::{Error, RHash, Ruby, TryConvert, value::ReprValue};
+
+/// Read an optional duration from the options, given in seconds as an Integer, Float or
+/// Rational.
+pub fn extract_duration(ruby: &Ruby, hash: RHash, key: &str) -> Result<Option<Duration>, Error> {
+    let Some(value) = hash.get(ruby.to_symbol(key)).filter(|v| !v.is_nil()) else {
+        return Ok(None);
+    };
+    let secs = f64::try_convert(value)?;
+    Duration::try_from_secs_f64(secs)
+        .map(Some)
+        .map_err(|err| Error::new(ruby.exception_arg_error(), format!("invalid {key}: {err}")))
+}
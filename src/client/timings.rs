@@ -0,0 +1,313 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures_util::{StreamExt, future, stream};
+use http_body::{Body as _, Frame, SizeHint};
+use magnus::{Error, Module, RHash, RModule, Ruby, method};
+
+tokio::task_local! {
+    /// The recorder of the request attempt being sent.
+    static RECORDER: Arc<Recorder>;
+}
+
+/// Records when each phase of one request attempt ends.
+///
+/// The recorder is reachable from the resolver and the connector while the request is
+/// sent, as both run within the request's task.
+pub struct Recorder {
+    started: Instant,
+    marks: Mutex<Marks>,
+}
+
+/// The moments recorded for a request attempt.
+#[derive(Clone, Copy, Default)]
+struct Marks {
+    /// When the connector was first asked for a connection.
+    connecting: Option<Instant>,
+    /// When a connection first read the request body, or found it empty.
+    sending: Option<Instant>,
+    /// When the DNS lookup started and ended.
+    dns: Option<(Instant, Instant)>,
+    /// When the connector started and returned the connection, including the lookup.
+    connect: Option<(Instant, Instant)>,
+    /// When the request body was written, or found empty.
+    written: Option<Instant>,
+    /// When the response headers were received.
+    first_byte: Option<Instant>,
+    /// When the last byte of the body was read.
+    body: Option<Instant>,
+}
+
+/// The durations of the phases of a request, as returned by `Response#timings`.
+#[derive(Clone, Copy)]
+#[magnus::wrap(class = "Wreq::Timings", free_immediately, size)]
+pub struct Timings {
+    dns: Duration,
+    connect: Duration,
+    request_written: Duration,
+    server: Duration,
+    first_byte: Duration,
+    body: Option<Duration>,
+    total: Option<Duration>,
+    reused: bool,
+}
+
+/// A request body that tells the recorder when a connection starts and finishes
+/// sending it.
+struct RequestBody {
+    inner: wreq::Body,
+    recorder: Arc<Recorder>,
+}
+
+/// Run `future`, which sends one request attempt, recording its phases in `recorder`.
+pub async fn scope<F: Future>(recorder: Arc<Recorder>, future: F) -> F::Output {
+    RECORDER.scope(recorder, future).await
+}
+
+/// Get the recorder of the request attempt being sent, if any.
+pub fn current() -> Option<Arc<Recorder>> {
+    RECORDER.try_with(Arc::clone).ok()
+}
+
+/// Mark the end of the body when `body` has been read to the end.
+pub fn watch_body(recorder: Arc<Recorder>, body: wreq::Body) -> wreq::Body {
+    let end = stream::once(async move { recorder.body() })
+        .filter_map(|()| future::ready(None::<Result<Bytes, wreq::Error>>));
+    wreq::Body::wrap_stream(body.into_data_stream().chain(end))
+}
+
+/// Mark when a connection starts and finishes sending `body`, the body of the request
+/// attempt.
+///
+/// A request without a body is given an empty one, so the connection sending it is
+/// noticed as well.
+pub fn watch_request(recorder: Arc<Recorder>, body: Option<wreq::Body>) -> wreq::Body {
+    wreq::Body::wrap(RequestBody {
+        inner: body.unwrap_or_else(|| wreq::Body::from(Bytes::new())),
+        recorder,
+    })
+}
+
+// ===== impl Recorder =====
+
+impl Recorder {
+    /// Start recording a request attempt sent now.
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            marks: Mutex::default(),
+        })
+    }
+
+    /// Record that the connector was asked for a connection, ending the wait for one
+    /// from the pool.
+    pub fn connecting(&self) {
+        self.update(|marks| {
+            marks.connecting.get_or_insert_with(Instant::now);
+        });
+    }
+
+    /// Record that a connection started sending the request.
+    fn sending(&self) {
+        self.update(|marks| {
+            marks.sending.get_or_insert_with(Instant::now);
+        });
+    }
+
+    /// Record that the connection finished sending the request.
+    fn written(&self) {
+        self.update(|marks| {
+            marks.written.get_or_insert_with(Instant::now);
+        });
+    }
+
+    /// Returns `true` once the request has a connection, opened for it or from the pool,
+    /// or a new one is being opened.
+    pub fn checked_out(&self) -> bool {
+        let marks = *self.marks.lock().unwrap_or_else(|err| err.into_inner());
+        marks.connecting.is_some() || marks.sending.is_some()
+    }
+
+    /// Record a DNS lookup started at `started` and ending now.
+    pub fn dns(&self, started: Instant) {
+        self.update(|marks| marks.dns = Some((started, Instant::now())));
+    }
+
+    /// Record a connection opened from `started` until now.
+    pub fn connect(&self, started: Instant) {
+        self.update(|marks| {
+            // A connection finishing after the response arrived was not used for it, as
+            // the request went out on a pooled connection meanwhile.
+            if marks.first_byte.is_none() {
+                marks.connect = Some((started, Instant::now()));
+            }
+        });
+    }
+
+    /// Record the arrival of the response headers.
+    pub fn first_byte(&self) {
+        self.update(|marks| marks.first_byte = Some(Instant::now()));
+    }
+
+    /// Record the end of the body.
+    fn body(&self) {
+        self.update(|marks| {
+            marks.body.get_or_insert_with(Instant::now);
+        });
+    }
+
+    /// Get the durations recorded so far.
+    pub fn timings(&self) -> Timings {
+        let marks = *self.marks.lock().unwrap_or_else(|err| err.into_inner());
+        let since = |from: Instant, to: Instant| to.saturating_duration_since(from);
+
+        let dns = marks
+            .dns
+            .map(|(start, end)| since(start, end))
+            .unwrap_or_default();
+        let connect = marks
+            .connect
+            .map(|(start, end)| since(start, end).saturating_sub(dns))
+            .unwrap_or_default();
+        let ready = marks.connect.map_or(self.started, |(_, end)| end);
+        let sending = marks.sending.map_or(ready, |sending| sending.max(ready));
+        let written = marks.written.unwrap_or(sending);
+        let first_byte = marks.first_byte.unwrap_or(self.started);
+
+        Timings {
+            dns,
+            connect,
+            request_written: since(sending, written),
+            server: since(written, first_byte),
+            first_byte: since(self.started, first_byte),
+            body: marks.body.map(|body| since(first_byte, body)),
+            total: marks.body.map(|body| since(self.started, body)),
+            reused: marks.connect.is_none(),
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Marks)) {
+        f(&mut self.marks.lock().unwrap_or_else(|err| err.into_inner()));
+    }
+}
+
+// ===== impl RequestBody =====
+
+impl http_body::Body for RequestBody {
+    type Data = Bytes;
+    type Error = wreq::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, wreq::Error>>> {
+        self.recorder.sending();
+        let frame = Pin::new(&mut self.inner).poll_frame(cx);
+        if let Poll::Ready(None) = frame {
+            self.recorder.written();
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // Connections ask before polling, and stop polling once the body says it ended.
+        let end = self.inner.is_end_stream();
+        if end {
+            self.recorder.sending();
+            self.recorder.written();
+        }
+        end
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// ===== impl Timings =====
+
+impl Timings {
+    /// Get the time spent looking up the host name, in seconds.
+    #[inline]
+    pub fn dns(&self) -> f64 {
+        self.dns.as_secs_f64()
+    }
+
+    /// Get the time spent opening the connection after the lookup, in seconds, including
+    /// any proxy tunnel and the TLS handshake.
+    #[inline]
+    pub fn connect(&self) -> f64 {
+        self.connect.as_secs_f64()
+    }
+
+    /// Get the time spent writing the request once the connection was ready, in seconds.
+    #[inline]
+    pub fn request_written(&self) -> f64 {
+        self.request_written.as_secs_f64()
+    }
+
+    /// Get the time from the request being written to the response headers, in seconds.
+    #[inline]
+    pub fn server(&self) -> f64 {
+        self.server.as_secs_f64()
+    }
+
+    /// Get the time from sending the request to the response headers, in seconds.
+    #[inline]
+    pub fn first_byte(&self) -> f64 {
+        self.first_byte.as_secs_f64()
+    }
+
+    /// Get the time spent reading the body, in seconds, or `None` until it is read.
+    #[inline]
+    pub fn body(&self) -> Option<f64> {
+        self.body.map(|body| body.as_secs_f64())
+    }
+
+    /// Get the time from sending the request to the end of the body, in seconds, or
+    /// `None` until the body is read.
+    #[inline]
+    pub fn total(&self) -> Option<f64> {
+        self.total.map(|total| total.as_secs_f64())
+    }
+
+    /// Returns `true` if the request was sent on a pooled connection.
+    #[inline]
+    pub fn reused(&self) -> bool {
+        self.reused
+    }
+
+    /// Get the timings as a Hash.
+    pub fn to_h(ruby: &Ruby, rb_self: &Self) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("dns"), rb_self.dns())?;
+        hash.aset(ruby.to_symbol("connect"), rb_self.connect())?;
+        hash.aset(ruby.to_symbol("request_written"), rb_self.request_written())?;
+        hash.aset(ruby.to_symbol("server"), rb_self.server())?;
+        hash.aset(ruby.to_symbol("first_byte"), rb_self.first_byte())?;
+        hash.aset(ruby.to_symbol("body"), rb_self.body())?;
+        hash.aset(ruby.to_symbol("total"), rb_self.total())?;
+        hash.aset(ruby.to_symbol("reused"), rb_self.reused())?;
+        Ok(hash)
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let timings_class = gem_module.define_class("Timings", ruby.class_object())?;
+    timings_class.define_method("dns", method!(Timings::dns, 0))?;
+    timings_class.define_method("connect", method!(Timings::connect, 0))?;
+    timings_class.define_method("request_written", method!(Timings::request_written, 0))?;
+    timings_class.define_method("server", method!(Timings::server, 0))?;
+    timings_class.define_method("first_byte", method!(Timings::first_byte, 0))?;
+    timings_class.define_method("body", method!(Timings::body, 0))?;
+    timings_class.define_method("total", method!(Timings::total, 0))?;
+    timings_class.define_method("reused?", method!(Timings::reused, 0))?;
+    timings_class.define_method("to_h", method!(Timings::to_h, 0))?;
+    Ok(())
+}
@@ -0,0 +1,341 @@
+use std::{
+    fmt,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use http::uri::Scheme;
+use magnus::{Error, RArray, RHash, RString, Ruby, Symbol, TryConvert, Value, value::ReprValue};
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+use wreq::tls::{CertStore, Identity, TlsInfo, TlsVersion};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::error::tls_error;
+
+/// The environment variable read for `ca_cert: :env`.
+const SSL_CERT_FILE: &str = "SSL_CERT_FILE";
+
+/// The SHA-256 hashes of trusted public keys, set with the `pin_sha256:` option.
+#[derive(Clone)]
+pub struct CertPins(Arc<Vec<[u8; 32]>>);
+
+impl CertPins {
+    /// Parse the `pin_sha256:` option, one pin or an Array of them.
+    ///
+    /// Each pin is the SHA-256 hash of a certificate's SubjectPublicKeyInfo, in base64
+    /// (optionally prefixed with `sha256/`, as in HPKP) or hex.
+    pub fn new(ruby: &Ruby, value: Value) -> Result<Self, Error> {
+        let pins = match RArray::from_value(value) {
+            Some(pins) => pins.to_vec::<String>()?,
+            None => vec![String::try_convert(value)?],
+        };
+        if pins.is_empty() {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "pin_sha256 must not be empty",
+            ));
+        }
+        pins.iter()
+            .map(|pin| {
+                decode_pin(pin).ok_or_else(|| {
+                    Error::new(
+                        ruby.exception_arg_error(),
+                        format!("invalid pin_sha256: {pin}"),
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(|pins| Self(Arc::new(pins)))
+    }
+
+    /// Check that the leaf certificate or one of the chain certificates the server sent,
+    /// given in DER, has a pinned public key. `host` is the name of the server, if any.
+    pub fn check<'a>(
+        &self,
+        host: Option<&str>,
+        chain: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<(), PinMismatch> {
+        let actual = chain
+            .into_iter()
+            .filter_map(spki_sha256)
+            .collect::<Vec<_>>();
+        if actual.iter().any(|hash| self.0.contains(hash)) {
+            return Ok(());
+        }
+
+        let format = |hashes: &[[u8; 32]]| {
+            hashes
+                .iter()
+                .map(|hash| format!("sha256/{}", STANDARD.encode(hash)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let host = host.map(|host| format!(" for {host}")).unwrap_or_default();
+        Err(PinMismatch(format!(
+            "certificate pin mismatch{host}: expected one of [{}], got [{}]",
+            format(&self.0),
+            format(&actual)
+        )))
+    }
+}
+
+/// A TLS connection whose certificates match none of the pins, failing the request
+/// before its response is handed back.
+#[derive(Debug)]
+pub struct PinMismatch(String);
+
+/// Checks the certificates each HTTPS response was received with against the pins,
+/// see [`CertPins`].
+///
+/// The client attaches the certificates of a connection to its responses only when
+/// `tls_info: true` is set, so it is set along with this layer, and the certificates
+/// are removed again unless `keep_info` is true.
+#[derive(Clone)]
+pub struct PinLayer {
+    pins: CertPins,
+    keep_info: bool,
+}
+
+/// The service wrapped by [`PinLayer`].
+#[derive(Clone)]
+pub struct CheckPins<S> {
+    inner: S,
+    pins: CertPins,
+    keep_info: bool,
+}
+
+impl fmt::Display for PinMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PinMismatch {}
+
+// ===== impl PinLayer =====
+
+impl PinLayer {
+    /// Create a layer checking responses against `pins`, keeping their certificates
+    /// for `Response#tls_info` if `keep_info` is true.
+    pub fn new(pins: CertPins, keep_info: bool) -> Self {
+        Self { pins, keep_info }
+    }
+}
+
+impl<S> Layer<S> for PinLayer {
+    type Service = CheckPins<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CheckPins {
+            inner,
+            pins: self.pins.clone(),
+            keep_info: self.keep_info,
+        }
+    }
+}
+
+impl<S, B, R> Service<http::Request<B>> for CheckPins<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<R>>,
+    S::Future: Send + 'static,
+    S::Error: From<PinMismatch> + Send + 'static,
+    R: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let secure = req.uri().scheme() == Some(&Scheme::HTTPS);
+        let host = req.uri().host().map(ToOwned::to_owned);
+        let pins = self.pins.clone();
+        let keep_info = self.keep_info;
+        let response = self.inner.call(req);
+        Box::pin(async move {
+            let mut response = response.await?;
+            // Fails closed: a response without certificates matches no pin.
+            if secure {
+                let info = response.extensions().get::<TlsInfo>();
+                let leaf = info.and_then(|info| info.peer_certificate());
+                let chain = info.and_then(|info| info.peer_certificate_chain());
+                pins.check(
+                    host.as_deref(),
+                    leaf.into_iter().chain(chain.into_iter().flatten()),
+                )?;
+            }
+            if !keep_info {
+                response.extensions_mut().remove::<TlsInfo>();
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Decode a pin given in base64 (optionally prefixed with `sha256/`) or hex.
+fn decode_pin(pin: &str) -> Option<[u8; 32]> {
+    let pin = pin.trim();
+    let pin = pin.strip_prefix("sha256/").unwrap_or(pin);
+    let bytes = if pin.len() == 64 && pin.bytes().all(|b| b.is_ascii_hexdigit()) {
+        (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&pin[i..i + 2], 16).ok())
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        STANDARD.decode(pin).ok()?
+    };
+    bytes.try_into().ok()
+}
+
+/// Hash the SubjectPublicKeyInfo of a DER certificate.
+fn spki_sha256(der: &[u8]) -> Option<[u8; 32]> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+    Some(Sha256::digest(cert.public_key().raw).into())
+}
+
+/// Parse the `ca_cert:` option into a store of trusted root certificates.
+///
+/// Accepts a PEM String, a path, `:env` for the file named by `SSL_CERT_FILE`,
+/// or an Array of those. Returns `None` if nothing was given, or if only `:env`
+/// was given and `SSL_CERT_FILE` is not set, so the default roots are kept.
+pub fn extract_cert_store(ruby: &Ruby, hash: RHash) -> Result<Option<CertStore>, Error> {
+    let Some(value) = get(ruby, hash, "ca_cert") else {
+        return Ok(None);
+    };
+    let sources = match RArray::from_value(value) {
+        Some(sources) => sources.into_iter().collect(),
+        None => vec![value],
+    };
+
+    let mut pem = Vec::new();
+    for source in sources {
+        let certs = match Symbol::from_value(source) {
+            Some(symbol) if symbol.name()? == "env" => match std::env::var_os(SSL_CERT_FILE) {
+                Some(path) => read_file(Path::new(&path), "CA certificate")?,
+                None => continue,
+            },
+            Some(symbol) => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("unknown ca_cert source :{}", symbol.name()?),
+                ));
+            }
+            None => read_source(source, "CA certificate", true)?,
+        };
+        pem.extend_from_slice(&certs);
+        pem.push(b'\n');
+    }
+    if pem.is_empty() {
+        return Ok(None);
+    }
+
+    CertStore::from_pem_stack(pem)
+        .map(Some)
+        .map_err(|err| tls_error(format!("invalid CA certificate: {err}")))
+}
+
+/// Parse the client certificate for mutual TLS, given either as a PKCS#12 archive with
+/// `identity:` (and `identity_password:`), or as PEM with `client_cert:` and `client_key:`.
+pub fn extract_identity(ruby: &Ruby, hash: RHash) -> Result<Option<Identity>, Error> {
+    let identity = get(ruby, hash, "identity");
+    let cert = get(ruby, hash, "client_cert");
+    let key = get(ruby, hash, "client_key");
+
+    match (identity, cert, key) {
+        (None, None, None) => Ok(None),
+        (Some(identity), None, None) => {
+            let der = read_source(identity, "identity", false)?;
+            let password = match get(ruby, hash, "identity_password") {
+                Some(password) => String::try_convert(password)?,
+                None => String::new(),
+            };
+            Identity::from_pkcs12_der(&der, &password)
+                .map(Some)
+                .map_err(|err| tls_error(format!("invalid identity: {err}")))
+        }
+        (None, Some(cert), Some(key)) => {
+            let cert = read_source(cert, "client certificate", true)?;
+            let key = read_source(key, "client key", true)?;
+            Identity::from_pkcs8_pem(&cert, &key)
+                .map(Some)
+                .map_err(|err| tls_error(format!("invalid client certificate: {err}")))
+        }
+        (Some(_), _, _) => Err(Error::new(
+            ruby.exception_arg_error(),
+            "identity cannot be combined with client_cert or client_key",
+        )),
+        _ => Err(Error::new(
+            ruby.exception_arg_error(),
+            "client_cert and client_key must be given together",
+        )),
+    }
+}
+
+/// Parse a TLS version option, given as `"1.2"`, `:tls1_2` or `"TLSv1.2"`.
+pub fn extract_tls_version(
+    ruby: &Ruby,
+    hash: RHash,
+    key: &str,
+) -> Result<Option<TlsVersion>, Error> {
+    let Some(value) = get(ruby, hash, key) else {
+        return Ok(None);
+    };
+    let name = match Symbol::from_value(value) {
+        Some(symbol) => symbol.name()?.into_owned(),
+        None => String::try_convert(value)?,
+    };
+    let version = name.to_ascii_lowercase().replace('_', ".");
+    let version = version
+        .trim_start_matches("tls")
+        .trim_start_matches('v')
+        .trim_start_matches('.');
+    match version {
+        "1" | "1.0" => Ok(Some(TlsVersion::TLS_1_0)),
+        "1.1" => Ok(Some(TlsVersion::TLS_1_1)),
+        "1.2" => Ok(Some(TlsVersion::TLS_1_2)),
+        "1.3" => Ok(Some(TlsVersion::TLS_1_3)),
+        _ => Err(Error::new(
+            ruby.exception_arg_error(),
+            format!("invalid {key}: {name}, expected one of 1.0, 1.1, 1.2, 1.3"),
+        )),
+    }
+}
+
+/// Read certificate material given as a path (String or Pathname) or inline.
+///
+/// Strings holding PEM are used as is. Other Strings are read as paths, unless
+/// `pem` is false and no such file exists, in which case they are used as DER.
+fn read_source(value: Value, what: &str, pem: bool) -> Result<Vec<u8>, Error> {
+    // File and Pathname both respond to `to_path`.
+    if value.respond_to("to_path", false)? {
+        let path: String = value.funcall("to_path", ())?;
+        return read_file(Path::new(&path), what);
+    }
+
+    let content = RString::try_convert(value)?.to_bytes();
+    if content.windows(10).any(|window| window == b"-----BEGIN") {
+        return Ok(content.to_vec());
+    }
+    match std::str::from_utf8(&content).map(Path::new) {
+        Ok(path) if pem || path.is_file() => read_file(path, what),
+        _ => Ok(content.to_vec()),
+    }
+}
+
+fn read_file(path: &Path, what: &str) -> Result<Vec<u8>, Error> {
+    std::fs::read(path)
+        .map_err(|err| tls_error(format!("cannot read {what} {}: {err}", path.display())))
+}
+
+/// Read an optional setting from the keyword arguments.
+fn get(ruby: &Ruby, hash: RHash, key: &str) -> Option<Value> {
+    hash.get(ruby.to_symbol(key))
+        .filter(|value| !value.is_nil())
+}
@@ -0,0 +1,467 @@
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use futures_util::{
+    SinkExt, StreamExt,
+    stream::{SplitSink, SplitStream},
+};
+use http::{HeaderValue, header};
+use magnus::{
+    Error, Module, RHash, RModule, RString, Ruby, Symbol, TryConvert, Value, method,
+    typed_data::Obj, value::ReprValue,
+};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use wreq::{
+    Client, Uri,
+    header::{HeaderMap, OrigHeaderMap},
+    ws::message::{CloseCode, CloseFrame, Message as RawMessage},
+};
+
+use crate::{
+    client::{
+        dns::{self, IpFamily, Overrides},
+        proxy::{ProxyPool, ProxySetting},
+        query::Query,
+    },
+    emulate::Emulation,
+    error::{RequestError, timeout_error, websocket_closed_error, wreq_error_to_magnus},
+    extractor::Extractor,
+    header::Headers,
+    http::StatusCode,
+    rt,
+};
+
+type Sender = SplitSink<wreq::ws::WebSocket, RawMessage>;
+type Receiver = SplitStream<wreq::ws::WebSocket>;
+
+/// The parameters for a WebSocket handshake request.
+#[derive(Default, Deserialize)]
+#[non_exhaustive]
+pub struct WebSocketRequest {
+    /// The emulation option for the request.
+    #[serde(skip)]
+    emulation: Option<Emulation>,
+
+    /// The proxies, or proxy pool, to use for the request.
+    #[serde(skip)]
+    proxy: Option<ProxySetting>,
+
+    /// Bind to a local IP Address.
+    local_address: Option<IpAddr>,
+
+    /// Bind to an interface by `SO_BINDTODEVICE`.
+    interface: Option<String>,
+
+    /// The static addresses to use for host names.
+    #[serde(skip)]
+    resolve: Option<Overrides>,
+
+    /// The address family to connect with.
+    #[serde(skip)]
+    ip_family: Option<IpFamily>,
+
+    /// The headers to use for the request.
+    #[serde(skip)]
+    headers: Option<HeaderMap>,
+
+    /// The original headers to use for the request.
+    #[serde(skip)]
+    orig_headers: Option<OrigHeaderMap>,
+
+    /// The option enables default headers.
+    default_headers: Option<bool>,
+
+    /// The cookies to use for the request.
+    #[serde(skip)]
+    cookies: Option<Vec<HeaderValue>>,
+
+    /// The WebSocket subprotocols to request.
+    protocols: Option<Vec<String>>,
+
+    /// The authentication to use for the request.
+    auth: Option<String>,
+
+    /// The bearer authentication to use for the request.
+    bearer_auth: Option<String>,
+
+    /// The basic authentication to use for the request.
+    basic_auth: Option<(String, Option<String>)>,
+
+    /// The query parameters to use for the request.
+    query: Option<Query>,
+}
+
+/// A WebSocket connection.
+#[magnus::wrap(class = "Wreq::WebSocket", free_immediately, size)]
+pub struct WebSocket {
+    status: StatusCode,
+    headers: HeaderMap,
+    protocol: Option<String>,
+    sender: Mutex<Option<Sender>>,
+    receiver: Arc<Mutex<Receiver>>,
+}
+
+/// A message received from a WebSocket.
+#[magnus::wrap(class = "Wreq::Message", free_immediately, size)]
+pub struct Message(RawMessage);
+
+// ===== impl WebSocketRequest =====
+
+impl WebSocketRequest {
+    /// Create a new [`WebSocketRequest`] from Ruby keyword arguments.
+    pub fn new(ruby: &Ruby, hash: RHash) -> Result<Self, Error> {
+        let kwargs = hash.as_value();
+        let mut builder: Self = serde_magnus::deserialize(ruby, kwargs)?;
+
+        // extra emulation handling
+        if let Some(v) = hash.get(ruby.to_symbol("emulation")) {
+            let emulation_obj = Obj::<Emulation>::try_convert(v)?;
+            builder.emulation = Some((*emulation_obj).clone());
+        }
+
+        // extra headers handling
+        builder.headers = Extractor::<HeaderMap>::try_convert(kwargs)?.into_inner();
+
+        // extra original headers handling
+        builder.orig_headers = Extractor::<OrigHeaderMap>::try_convert(kwargs)?.into_inner();
+
+        // extra cookies handling
+        builder.cookies = Extractor::<Vec<HeaderValue>>::try_convert(kwargs)?.into_inner();
+
+        // extra proxy handling
+        builder.proxy = Extractor::<ProxySetting>::try_convert(kwargs)?.into_inner();
+
+        // extra resolve handling
+        if let Some(resolve) = hash.get(ruby.to_symbol("resolve")).filter(|v| !v.is_nil()) {
+            builder.resolve = Some(Overrides::new(ruby, resolve)?);
+        }
+
+        // extra IP family handling
+        if let Some(ip_family) = hash
+            .get(ruby.to_symbol("ip_family"))
+            .filter(|v| !v.is_nil())
+        {
+            builder.ip_family = Some(IpFamily::new(ruby, ip_family)?);
+        }
+
+        Ok(builder)
+    }
+
+    /// Take the `resolve:` and `ip_family:` settings of the handshake.
+    pub fn take_dns(&mut self) -> dns::Settings {
+        dns::Settings {
+            overrides: self.resolve.take(),
+            ip_family: self.ip_family.take(),
+            timeout: None,
+        }
+    }
+}
+
+pub fn execute_websocket_request<U: AsRef<str>>(
+    client: Client,
+    proxy_pool: Option<ProxyPool>,
+    url: U,
+    mut request: WebSocketRequest,
+) -> Result<WebSocket, Error> {
+    rt::try_block_on_request(async move {
+        let mut builder = client.websocket(url.as_ref());
+        let uri = url.as_ref().parse::<Uri>().unwrap_or_default();
+
+        // Emulation options.
+        apply_option!(set_if_some_inner, builder, request.emulation, emulation);
+
+        // Network options.
+        let mut checkout = None;
+        if let Some((proxy, pool_checkout)) = request
+            .proxy
+            .take()
+            .or_else(|| proxy_pool.map(ProxySetting::Pool))
+            .and_then(|proxy| proxy.for_uri(&uri))
+        {
+            builder = builder.proxy(proxy);
+            checkout = pool_checkout;
+        }
+        apply_option!(set_if_some, builder, request.local_address, local_address);
+        apply_option!(set_if_some, builder, request.interface, interface);
+
+        // Headers options.
+        apply_option!(set_if_some, builder, request.headers, headers);
+        apply_option!(set_if_some, builder, request.orig_headers, orig_headers);
+        apply_option!(
+            set_if_some,
+            builder,
+            request.default_headers,
+            default_headers
+        );
+
+        // Protocols options.
+        apply_option!(set_if_some, builder, request.protocols, protocols);
+
+        // Authentication options.
+        apply_option!(
+            set_if_some_map_ref,
+            builder,
+            request.auth,
+            auth,
+            AsRef::<str>::as_ref
+        );
+        apply_option!(set_if_some, builder, request.bearer_auth, bearer_auth);
+        if let Some(basic_auth) = request.basic_auth.take() {
+            builder = builder.basic_auth(basic_auth.0, basic_auth.1);
+        }
+
+        // Cookies options.
+        if let Some(cookies) = request.cookies.take() {
+            for cookie in cookies {
+                builder = builder.header(header::COOKIE, cookie);
+            }
+        }
+
+        // Query options.
+        apply_option!(set_if_some_ref, builder, request.query, query);
+
+        // Perform the handshake.
+        let result = dns::scope(None, &uri, builder.send())
+            .await
+            .map_err(RequestError::from);
+        if let Some(checkout) = checkout {
+            checkout.finish(result.as_ref().err());
+        }
+        let response = result?;
+        let status = StatusCode::from(response.status());
+        let headers = response.headers().clone();
+        let websocket = response.into_websocket().await?;
+        let protocol = websocket
+            .protocol()
+            .and_then(|protocol| protocol.to_str().ok())
+            .map(ToOwned::to_owned);
+        let (sender, receiver) = websocket.split();
+
+        Ok(WebSocket {
+            status,
+            headers,
+            protocol,
+            sender: Mutex::new(Some(sender)),
+            receiver: Arc::new(Mutex::new(receiver)),
+        })
+    })
+}
+
+// ===== impl WebSocket =====
+
+impl WebSocket {
+    /// Get the handshake response status code.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the handshake response headers.
+    #[inline]
+    pub fn headers(&self) -> Headers {
+        Headers::from(self.headers.clone())
+    }
+
+    /// Get the subprotocol selected by the server, if any.
+    #[inline]
+    pub fn protocol(&self) -> Option<String> {
+        self.protocol.clone()
+    }
+
+    /// Send a text message.
+    pub fn send_text(&self, text: String) -> Result<(), Error> {
+        self.send(RawMessage::text(text))
+    }
+
+    /// Send a binary message.
+    pub fn send_binary(&self, data: RString) -> Result<(), Error> {
+        self.send(RawMessage::binary(data.to_bytes()))
+    }
+
+    /// Send a ping with an optional payload.
+    pub fn ping(&self, args: &[Value]) -> Result<(), Error> {
+        let payload = match args.first() {
+            Some(v) => RString::try_convert(*v)?.to_bytes(),
+            None => Bytes::new(),
+        };
+        self.send(RawMessage::Ping(payload))
+    }
+
+    /// Receive the next message, blocking without the GVL.
+    ///
+    /// Returns `nil` once the connection is closed.
+    pub fn recv(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<Option<Message>, Error> {
+        let args = magnus::scan_args::scan_args::<(), (), (), (), RHash, ()>(args)?;
+        let keywords: magnus::scan_args::KwArgs<(), (Option<f64>,), ()> =
+            magnus::scan_args::get_kwargs(args.keywords, &[], &["timeout"])?;
+        let timeout = keywords
+            .optional
+            .0
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|err| {
+                Error::new(
+                    ruby.exception_arg_error(),
+                    format!("invalid timeout: {err}"),
+                )
+            })?;
+        rb_self.next_message(timeout)
+    }
+
+    /// Yield each received message to the block until the connection is closed.
+    ///
+    /// Without a block, returns an Enumerator over the messages.
+    pub fn each(ruby: &Ruby, rb_self: &Self) -> Result<Value, Error> {
+        if !ruby.block_given() {
+            let receiver = ruby.current_receiver::<Value>()?;
+            return Ok(receiver.enumeratorize("each", ()).as_value());
+        }
+        while let Some(message) = rb_self.next_message(None)? {
+            let _: Value = ruby.yield_value(message)?;
+        }
+        Ok(ruby.qnil().as_value())
+    }
+
+    /// Send a close frame and shut down the sending half of the connection.
+    pub fn close(&self, args: &[Value]) -> Result<(), Error> {
+        let args = magnus::scan_args::scan_args::<(), (Option<u16>, Option<String>), (), (), (), ()>(
+            args,
+        )?;
+        let (code, reason) = args.optional;
+        let frame = CloseFrame {
+            code: CloseCode::from(code.unwrap_or(1000)),
+            reason: reason.unwrap_or_default().into(),
+        };
+
+        rt::try_block_on_request(async {
+            if let Some(mut sender) = self.sender.lock().await.take() {
+                sender.send(RawMessage::Close(Some(frame))).await?;
+                sender.close().await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn send(&self, message: RawMessage) -> Result<(), Error> {
+        rt::try_block_on(async {
+            match self.sender.lock().await.as_mut() {
+                Some(sender) => Ok(sender.send(message).await),
+                None => Err(websocket_closed_error()),
+            }
+        })?
+        .map_err(wreq_error_to_magnus)
+    }
+
+    /// Receive the next message, waiting at most `timeout`, or `None` once closed.
+    fn next_message(&self, timeout: Option<Duration>) -> Result<Option<Message>, Error> {
+        let receiver = self.receiver.clone();
+        rt::try_block_on(async move {
+            let mut receiver = receiver.lock().await;
+            let next = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, receiver.next())
+                    .await
+                    .map_err(|_| timeout_error("websocket receive timed out"))?,
+                None => receiver.next().await,
+            };
+            Ok(next.transpose())
+        })?
+        .map(|message| message.map(Message))
+        .map_err(wreq_error_to_magnus)
+    }
+}
+
+// ===== impl Message =====
+
+impl Message {
+    /// Get the message type as a Symbol (`:text`, `:binary`, `:ping`, `:pong` or `:close`).
+    pub fn kind(ruby: &Ruby, rb_self: &Self) -> Symbol {
+        ruby.to_symbol(match rb_self.0 {
+            RawMessage::Text(_) => "text",
+            RawMessage::Binary(_) => "binary",
+            RawMessage::Ping(_) => "ping",
+            RawMessage::Pong(_) => "pong",
+            RawMessage::Close(_) => "close",
+        })
+    }
+
+    /// Get the message text, for text messages.
+    pub fn text(&self) -> Option<String> {
+        match &self.0 {
+            RawMessage::Text(text) => Some(text.as_str().to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Get the raw message payload as a binary String.
+    pub fn data(&self) -> Bytes {
+        match &self.0 {
+            RawMessage::Text(text) => Bytes::copy_from_slice(text.as_str().as_bytes()),
+            RawMessage::Binary(data) | RawMessage::Ping(data) | RawMessage::Pong(data) => {
+                data.clone()
+            }
+            RawMessage::Close(frame) => frame
+                .as_ref()
+                .map(|frame| Bytes::copy_from_slice(frame.reason.as_str().as_bytes()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Get the close code, for close messages.
+    pub fn close_code(&self) -> Option<u16> {
+        match &self.0 {
+            RawMessage::Close(Some(frame)) => Some(u16::from(frame.code)),
+            _ => None,
+        }
+    }
+
+    /// Get the close reason, for close messages.
+    pub fn close_reason(&self) -> Option<String> {
+        match &self.0 {
+            RawMessage::Close(Some(frame)) => Some(frame.reason.as_str().to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Check if this is a text message.
+    #[inline]
+    pub fn is_text(&self) -> bool {
+        matches!(self.0, RawMessage::Text(_))
+    }
+
+    /// Check if this is a binary message.
+    #[inline]
+    pub fn is_binary(&self) -> bool {
+        matches!(self.0, RawMessage::Binary(_))
+    }
+
+    /// Check if this is a close message.
+    #[inline]
+    pub fn is_close(&self) -> bool {
+        matches!(self.0, RawMessage::Close(_))
+    }
+}
+
+pub fn include(ruby: &Ruby, gem_module: &RModule) -> Result<(), Error> {
+    let websocket_class = gem_module.define_class("WebSocket", ruby.class_object())?;
+    websocket_class.define_method("status", method!(WebSocket::status, 0))?;
+    websocket_class.define_method("headers", method!(WebSocket::headers, 0))?;
+    websocket_class.define_method("protocol", method!(WebSocket::protocol, 0))?;
+    websocket_class.define_method("send_text", method!(WebSocket::send_text, 1))?;
+    websocket_class.define_method("send_binary", method!(WebSocket::send_binary, 1))?;
+    websocket_class.define_method("ping", method!(WebSocket::ping, -1))?;
+    websocket_class.define_method("recv", method!(WebSocket::recv, -1))?;
+    websocket_class.define_method("each", method!(WebSocket::each, 0))?;
+    websocket_class.define_method("close", method!(WebSocket::close, -1))?;
+
+    let message_class = gem_module.define_class("Message", ruby.class_object())?;
+    message_class.define_method("kind", method!(Message::kind, 0))?;
+    message_class.define_method("text", method!(Message::text, 0))?;
+    message_class.define_method("data", method!(Message::data, 0))?;
+    message_class.define_method("close_code", method!(Message::close_code, 0))?;
+    message_class.define_method("close_reason", method!(Message::close_reason, 0))?;
+    message_class.define_method("text?", method!(Message::is_text, 0))?;
+    message_class.define_method("binary?", method!(Message::is_binary, 0))?;
+    message_class.define_method("close?", method!(Message::is_close, 0))?;
+    Ok(())
+}
@@ -1,8 +1,22 @@
+use std::{fmt, sync::Mutex};
+
 use magnus::{
-    Error as MagnusError, RModule, Ruby, exception::ExceptionClass, prelude::*, value::Lazy,
+    Error as MagnusError, RArray, RHash, RModule, Ruby, Symbol, Value,
+    error::{ErrorType, OpaqueError},
+    exception::{Exception, ExceptionClass},
+    method,
+    prelude::*,
+    value::Lazy,
 };
 use tokio::sync::mpsc::error::SendError;
 
+use crate::{
+    client::{retry::RetryPolicy, tls::PinMismatch},
+    gvl,
+    header::Headers,
+    http::StatusCode,
+};
+
 const RACE_CONDITION_ERROR_MSG: &str = r#"Due to Rust's memory management with borrowing,
 you cannot use certain instances multiple times as they may be consumed.
 
@@ -19,6 +33,13 @@ Potential solutions:
 
 static WREQ: Lazy<RModule> = Lazy::new(|ruby| ruby.define_module(crate::RUBY_MODULE_NAME).unwrap());
 
+/// The exceptions held by [`CallbackError`]s, kept alive until they are raised again.
+static HELD_EXCEPTIONS: Lazy<RHash> = Lazy::new(|ruby| {
+    let hash = ruby.hash_new();
+    let _: Value = hash.funcall("compare_by_identity", ()).unwrap();
+    hash
+});
+
 macro_rules! define_exception {
     ($name:ident, $ruby_name:literal, $parent_method:ident) => {
         static $name: Lazy<ExceptionClass> = Lazy::new(|ruby| {
@@ -27,50 +48,78 @@ macro_rules! define_exception {
                 .unwrap()
         });
     };
+    ($name:ident, $ruby_name:literal, < $parent:ident) => {
+        static $name: Lazy<ExceptionClass> = Lazy::new(|ruby| {
+            ruby.get_inner(&WREQ)
+                .define_error($ruby_name, ruby.get_inner(&$parent))
+                .unwrap()
+        });
+    };
 }
 
-macro_rules! map_wreq_error {
-    ($ruby:expr, $err:expr, $msg:expr, $($check_method:ident => $exception:ident),* $(,)?) => {
-        {
-            $(
-                if $err.$check_method() {
-                    return MagnusError::new($ruby.get_inner(&$exception), $msg);
+macro_rules! define_error_kind {
+    ($($kind:ident => $check_method:ident => $exception:ident),* $(,)?) => {
+        /// The Ruby exception class a [`wreq::Error`] maps to.
+        ///
+        /// Resolving the kind does not touch the Ruby VM, so it can be done on runtime threads.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum ErrorKind {
+            $($kind,)*
+            Runtime,
+        }
+
+        impl ErrorKind {
+            const ALL: &[ErrorKind] = &[$(ErrorKind::$kind,)*];
+
+            /// Classify a [`wreq::Error`].
+            pub fn of(err: &wreq::Error) -> Self {
+                // Timeouts of any phase, including connecting, are timeouts first.
+                if TimeoutPhase::of(err).is_some() {
+                    return ErrorKind::Timeout;
+                }
+                $(
+                    if err.$check_method() {
+                        return ErrorKind::$kind;
+                    }
+                )*
+                ErrorKind::Runtime
+            }
+
+            /// The Ruby exception class for this kind.
+            pub fn exception_class(self, ruby: &Ruby) -> ExceptionClass {
+                match self {
+                    $(ErrorKind::$kind => ruby.get_inner(&$exception),)*
+                    ErrorKind::Runtime => ruby.get_inner(&ERROR),
                 }
-            )*
-            MagnusError::new($ruby.exception_runtime_error(), $msg)
+            }
         }
     };
 }
 
+// The base of the errors raised by the bindings
+define_exception!(ERROR, "Error", exception_runtime_error);
+
 // System-level and runtime errors
-define_exception!(MEMORY, "MemoryError", exception_runtime_error);
+define_exception!(MEMORY, "MemoryError", <ERROR);
 
 // Network connection errors
-define_exception!(CONNECTION_ERROR, "ConnectionError", exception_runtime_error);
-define_exception!(
-    PROXY_CONNECTION_ERROR,
-    "ProxyConnectionError",
-    exception_runtime_error
-);
-define_exception!(
-    CONNECTION_RESET_ERROR,
-    "ConnectionResetError",
-    exception_runtime_error
-);
-define_exception!(TLS_ERROR, "TlsError", exception_runtime_error);
+define_exception!(CONNECTION_ERROR, "ConnectionError", <ERROR);
+define_exception!(PROXY_CONNECTION_ERROR, "ProxyConnectionError", <ERROR);
+define_exception!(CONNECTION_RESET_ERROR, "ConnectionResetError", <ERROR);
+define_exception!(TLS_ERROR, "TlsError", <ERROR);
 
 // HTTP protocol and request/response errors
-define_exception!(REQUEST_ERROR, "RequestError", exception_runtime_error);
-define_exception!(STATUS_ERROR, "StatusError", exception_runtime_error);
-define_exception!(REDIRECT_ERROR, "RedirectError", exception_runtime_error);
-define_exception!(TIMEOUT_ERROR, "TimeoutError", exception_runtime_error);
+define_exception!(REQUEST_ERROR, "RequestError", <ERROR);
+define_exception!(STATUS_ERROR, "StatusError", <ERROR);
+define_exception!(REDIRECT_ERROR, "RedirectError", <ERROR);
+define_exception!(TIMEOUT_ERROR, "TimeoutError", <ERROR);
 
 // Data processing and encoding errors
-define_exception!(BODY_ERROR, "BodyError", exception_runtime_error);
-define_exception!(DECODING_ERROR, "DecodingError", exception_runtime_error);
+define_exception!(BODY_ERROR, "BodyError", <ERROR);
+define_exception!(DECODING_ERROR, "DecodingError", <ERROR);
 
 // Configuration and builder errors
-define_exception!(BUILDER_ERROR, "BuilderError", exception_runtime_error);
+define_exception!(BUILDER_ERROR, "BuilderError", <ERROR);
 
 // Thread interruption error
 define_exception!(INTERRUPT_ERROR, "InterruptError", exception_interrupt);
@@ -85,6 +134,237 @@ pub fn interrupt_error() -> MagnusError {
     MagnusError::new(ruby!().get_inner(&INTERRUPT_ERROR), "request interrupted")
 }
 
+/// Cancelled request error (raised when the value of a cancelled request is read)
+pub fn cancelled_error() -> MagnusError {
+    MagnusError::new(ruby!().get_inner(&REQUEST_ERROR), "request was cancelled")
+}
+
+/// Get the exception raised by `err`, creating it if it was not raised yet.
+///
+/// Errors created with [`MagnusError::new`] only hold the class and message of their
+/// exception until they are raised, so [`MagnusError::value`] returns the class.
+pub fn exception_of(err: MagnusError) -> Result<Exception, MagnusError> {
+    match err.error_type() {
+        ErrorType::Error(class, msg) => class.new_instance((msg.as_ref(),)),
+        ErrorType::Exception(exception) => Ok(*exception),
+        ErrorType::Jump(_) => Err(err),
+    }
+}
+
+/// Returns `true` for errors of the network, after which a request may be sent again.
+///
+/// Body errors are left out, as they are also raised for request bodies that cannot be
+/// read, which sending again would not fix.
+pub fn is_network_error(err: &MagnusError) -> bool {
+    let ruby = ruby!();
+    [
+        &CONNECTION_ERROR,
+        &PROXY_CONNECTION_ERROR,
+        &CONNECTION_RESET_ERROR,
+        &TIMEOUT_ERROR,
+    ]
+    .into_iter()
+    .any(|class| err.is_kind_of(ruby.get_inner(class)))
+}
+
+/// Timeout error (raised when a wait started by the bindings expires)
+pub fn timeout_error<T>(msg: T) -> MagnusError
+where
+    T: Into<std::borrow::Cow<'static, str>>,
+{
+    MagnusError::new(ruby!().get_inner(&TIMEOUT_ERROR), msg)
+}
+
+/// TLS error (raised when certificates or keys given to a client cannot be used)
+pub fn tls_error<T>(msg: T) -> MagnusError
+where
+    T: Into<std::borrow::Cow<'static, str>>,
+{
+    MagnusError::new(ruby!().get_inner(&TLS_ERROR), msg)
+}
+
+/// WebSocket closed error (raised when sending on a closed connection)
+pub fn websocket_closed_error() -> MagnusError {
+    MagnusError::new(
+        ruby!().get_inner(&CONNECTION_ERROR),
+        "websocket connection is closed",
+    )
+}
+
+/// An error raised while a request runs on the runtime.
+///
+/// It holds plain Rust errors so it can be produced on any thread,
+/// and is only mapped to a Ruby exception once back on a Ruby thread.
+pub enum RequestError {
+    /// An error from the HTTP client.
+    Wreq(wreq::Error),
+    /// An I/O error while preparing the request body.
+    Io(std::io::Error),
+    /// A redirect refused by the redirect policy.
+    Redirect(String),
+    /// A TLS session rejected by the bindings, such as a certificate pin mismatch.
+    Tls(String),
+    /// A timeout enforced by the bindings, such as the time to the first byte.
+    Timeout(TimeoutPhase),
+    /// An exception raised by a Ruby callback, such as a redirect policy.
+    Callback(CallbackError),
+}
+
+/// An exception raised by a Ruby callback while a request was sent, such as a redirect
+/// policy or a `dns_resolver:`, raised again as is once the request is back in Ruby.
+///
+/// The exception is kept reachable for the garbage collector until then.
+pub struct CallbackError {
+    message: String,
+    error: Mutex<Option<OpaqueError>>,
+}
+
+impl RequestError {
+    /// The kind of error, as matched by retry policies.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RequestError::Wreq(err) => ErrorKind::of(err),
+            RequestError::Io(_) => ErrorKind::Body,
+            RequestError::Redirect(_) => ErrorKind::Redirect,
+            RequestError::Tls(_) => ErrorKind::Tls,
+            RequestError::Timeout(_) => ErrorKind::Timeout,
+            RequestError::Callback(_) => ErrorKind::Runtime,
+        }
+    }
+
+    /// The phase of the request that timed out, or `None` if this is not a timeout.
+    pub fn timeout_phase(&self) -> Option<TimeoutPhase> {
+        match self {
+            RequestError::Wreq(err) => TimeoutPhase::of(err),
+            RequestError::Timeout(phase) => Some(*phase),
+            _ => None,
+        }
+    }
+}
+
+impl From<wreq::Error> for RequestError {
+    fn from(err: wreq::Error) -> Self {
+        // A pin mismatch or a failing resolver fails the request, but is reported as
+        // the TLS error or the exception it is.
+        let mut source = std::error::Error::source(&err);
+        while let Some(cause) = source {
+            if let Some(mismatch) = cause.downcast_ref::<PinMismatch>() {
+                return RequestError::Tls(mismatch.to_string());
+            }
+            // Resolver errors reach the connector wrapped in an I/O error.
+            let inner = cause
+                .downcast_ref::<std::io::Error>()
+                .and_then(std::io::Error::get_ref)
+                .map_or(cause, |inner| inner as &(dyn std::error::Error + 'static));
+            if let Some(callback) = inner.downcast_ref::<CallbackError>() {
+                return RequestError::Callback(callback.take());
+            }
+            source = cause.source();
+        }
+        RequestError::Wreq(err)
+    }
+}
+
+impl From<std::io::Error> for RequestError {
+    fn from(err: std::io::Error) -> Self {
+        RequestError::Io(err)
+    }
+}
+
+impl From<RequestError> for MagnusError {
+    fn from(err: RequestError) -> Self {
+        match err {
+            RequestError::Wreq(err) => wreq_error_to_magnus(err),
+            RequestError::Io(err) => io_error_to_magnus(err),
+            RequestError::Redirect(msg) => {
+                MagnusError::new(ruby!().get_inner(&REDIRECT_ERROR), msg)
+            }
+            RequestError::Tls(msg) => tls_error(msg),
+            RequestError::Callback(err) => err.into_error(),
+            RequestError::Timeout(phase) => Details {
+                phase: Some(phase),
+                retryable: RetryPolicy::retries_by_default(ErrorKind::Timeout, None),
+                ..Details::default()
+            }
+            .into_error(
+                ruby!().get_inner(&TIMEOUT_ERROR),
+                PhaseElapsed(phase).to_string(),
+            ),
+        }
+    }
+}
+
+// ===== impl CallbackError =====
+
+impl CallbackError {
+    /// Hold `err`, raised by a callback on the Ruby thread sending the request.
+    pub fn new(err: MagnusError) -> Self {
+        if let ErrorType::Exception(exception) = err.error_type() {
+            let _ = ruby!().get_inner(&HELD_EXCEPTIONS).aset(*exception, true);
+        }
+        Self {
+            message: err.to_string(),
+            error: Mutex::new(Some(OpaqueError::from(err))),
+        }
+    }
+
+    /// Move the exception into a new [`CallbackError`], leaving this one empty.
+    fn take(&self) -> Self {
+        Self {
+            message: self.message.clone(),
+            error: Mutex::new(self.lock().take()),
+        }
+    }
+
+    /// Get the exception back to raise it, on a Ruby thread.
+    fn into_error(self) -> MagnusError {
+        let ruby = ruby!();
+        let Some(err) = self.lock().take() else {
+            return MagnusError::new(ruby.get_inner(&ERROR), self.message.clone());
+        };
+        let err = OpaqueError::into_error_with(err, &ruby);
+        release(&ruby, &err);
+        err
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<OpaqueError>> {
+        self.error.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for CallbackError {
+    fn drop(&mut self) {
+        // Dropped without being raised, as when the request was cancelled.
+        if let Some(err) = self.lock().take() {
+            gvl::with_gvl(|| {
+                let ruby = ruby!();
+                release(&ruby, &OpaqueError::into_error_with(err, &ruby));
+            });
+        }
+    }
+}
+
+impl fmt::Debug for CallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CallbackError").field(&self.message).finish()
+    }
+}
+
+impl fmt::Display for CallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CallbackError {}
+
+/// Stop keeping the exception of `err` alive.
+fn release(ruby: &Ruby, err: &MagnusError) {
+    if let ErrorType::Exception(exception) = err.error_type() {
+        let _: Result<Value, _> = ruby.get_inner(&HELD_EXCEPTIONS).delete(*exception);
+    }
+}
+
 /// Map [`tokio::sync::mpsc::error::SendError`] to corresponding [`magnus::Error`]
 pub fn mpsc_send_error_to_magnus<T>(err: SendError<T>) -> MagnusError {
     MagnusError::new(
@@ -93,6 +373,19 @@ pub fn mpsc_send_error_to_magnus<T>(err: SendError<T>) -> MagnusError {
     )
 }
 
+/// Map [`std::io::Error`] to corresponding [`magnus::Error`]
+pub fn io_error_to_magnus(err: std::io::Error) -> MagnusError {
+    let details = Details {
+        cause_chain: cause_chain(&err),
+        io_errno: io_errno(&err),
+        ..Details::default()
+    };
+    details.into_error(
+        ruby!().get_inner(&BODY_ERROR),
+        format!("failed to read body: {err}"),
+    )
+}
+
 /// Map [`wreq::header::InvalidHeaderName`] to corresponding [`magnus::Error`]
 pub fn header_name_error_to_magnus(err: wreq::header::InvalidHeaderName) -> MagnusError {
     MagnusError::new(
@@ -109,28 +402,286 @@ pub fn header_value_error_to_magnus(err: wreq::header::InvalidHeaderValue) -> Ma
     )
 }
 
+define_error_kind!(
+    Builder => is_builder => BUILDER_ERROR,
+    Body => is_body => BODY_ERROR,
+    Tls => is_tls => TLS_ERROR,
+    ConnectionReset => is_connection_reset => CONNECTION_RESET_ERROR,
+    Connection => is_connect => CONNECTION_ERROR,
+    ProxyConnection => is_proxy_connect => PROXY_CONNECTION_ERROR,
+    Decoding => is_decode => DECODING_ERROR,
+    Redirect => is_redirect => REDIRECT_ERROR,
+    Timeout => is_timeout => TIMEOUT_ERROR,
+    Status => is_status => STATUS_ERROR,
+    Request => is_request => REQUEST_ERROR,
+);
+
+impl ErrorKind {
+    /// Find the kind whose Ruby exception class is exactly `class`.
+    pub fn from_class(ruby: &Ruby, class: magnus::Value) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| {
+            kind.exception_class(ruby)
+                .as_value()
+                .equal(class)
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// The phase of a request whose timeout expired, reported by `TimeoutError#phase`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutPhase {
+    /// Looking up the host name (`dns_timeout:`).
+    Dns,
+    /// Opening the connection, including the TLS handshake (`connect_timeout:`).
+    Connect,
+    /// Waiting for a connection from the pool (`pool_timeout:`).
+    Pool,
+    /// Waiting for the response headers (`first_byte_timeout:`).
+    FirstByte,
+    /// Waiting for data from the connection (`read_timeout:`).
+    Read,
+    /// The whole request, including reading the body (`timeout:`).
+    Total,
+}
+
+/// A timeout enforced by the bindings, carried as the source of the client error it
+/// causes when it expires within the HTTP client, such as while resolving.
+#[derive(Debug)]
+pub struct PhaseElapsed(pub TimeoutPhase);
+
+impl TimeoutPhase {
+    /// Find the phase of a timeout reported by the HTTP client, or `None` if `err` is
+    /// not a timeout.
+    pub fn of(err: &wreq::Error) -> Option<Self> {
+        let mut read = false;
+        let mut source = std::error::Error::source(err);
+        while let Some(cause) = source {
+            if let Some(elapsed) = cause.downcast_ref::<PhaseElapsed>() {
+                return Some(elapsed.0);
+            }
+            // The read timeout fails reads from the connection; the total timeout
+            // is an error of the client's own.
+            read |= cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|err| err.kind() == std::io::ErrorKind::TimedOut);
+            source = cause.source();
+        }
+        if !err.is_timeout() {
+            return None;
+        }
+        Some(if err.is_connect() {
+            TimeoutPhase::Connect
+        } else if read {
+            TimeoutPhase::Read
+        } else {
+            TimeoutPhase::Total
+        })
+    }
+
+    /// The name of the phase, as returned by `TimeoutError#phase`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TimeoutPhase::Dns => "dns",
+            TimeoutPhase::Connect => "connect",
+            TimeoutPhase::Pool => "pool",
+            TimeoutPhase::FirstByte => "first_byte",
+            TimeoutPhase::Read => "read",
+            TimeoutPhase::Total => "total",
+        }
+    }
+}
+
+impl fmt::Display for PhaseElapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} timeout expired", self.0.name())
+    }
+}
+
+impl std::error::Error for PhaseElapsed {}
+
+/// The attributes of a `Wreq::Error`, set as instance variables for its readers.
+#[derive(Default)]
+struct Details {
+    url: Option<String>,
+    status: Option<StatusCode>,
+    cause_chain: Vec<String>,
+    io_errno: Option<i32>,
+    retryable: bool,
+    phase: Option<TimeoutPhase>,
+}
+
+impl Details {
+    /// Create the error raising an exception of `class` with these attributes.
+    fn into_error(self, class: ExceptionClass, msg: String) -> MagnusError {
+        self.exception(class, msg)
+            .map_or_else(|err| err, MagnusError::from)
+    }
+
+    /// Create an exception of `class` with these attributes.
+    fn exception(self, class: ExceptionClass, msg: String) -> Result<Exception, MagnusError> {
+        let ruby = ruby!();
+        let exception: Exception = class.new_instance((msg,))?;
+        exception.ivar_set("@url", self.url)?;
+        exception.ivar_set("@status", self.status)?;
+        exception.ivar_set("@cause_chain", ruby.ary_from_vec(self.cause_chain))?;
+        let io_error_kind = match self.io_errno {
+            Some(errno) => errno_name(&ruby, errno)?,
+            None => None,
+        };
+        exception.ivar_set("@io_error_kind", io_error_kind)?;
+        exception.ivar_set("@retryable", self.retryable)?;
+        exception.ivar_set(
+            "@phase",
+            self.phase.map(|phase| ruby.to_symbol(phase.name())),
+        )?;
+        Ok(exception)
+    }
+}
+
+/// Collect the messages of the errors that caused `err`, outermost first.
+fn cause_chain(err: &dyn std::error::Error) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push(cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
+
+/// Find the OS error code of the first I/O error in the chain of `err`.
+fn io_errno(err: &(dyn std::error::Error + 'static)) -> Option<i32> {
+    let mut source = Some(err);
+    while let Some(cause) = source {
+        if let Some(errno) = cause
+            .downcast_ref::<std::io::Error>()
+            .and_then(std::io::Error::raw_os_error)
+        {
+            return Some(errno);
+        }
+        source = cause.source();
+    }
+    None
+}
+
+/// Get the name of an OS error code, such as `:ECONNREFUSED`, as Ruby's `Errno` names it.
+fn errno_name(ruby: &Ruby, errno: i32) -> Result<Option<Symbol>, MagnusError> {
+    let system_call_error: ExceptionClass = ruby.class_object().const_get("SystemCallError")?;
+    let exception: Exception = system_call_error.new_instance((ruby.qnil(), errno))?;
+    let class: Value = exception.funcall("class", ())?;
+    let name: String = class.funcall("name", ())?;
+    Ok(name
+        .strip_prefix("Errno::")
+        .map(|name| ruby.to_symbol(name)))
+}
+
+/// Status error for a response, with its status, headers, URL and a preview of its
+/// body set for the `StatusError` readers.
+pub fn status_error(
+    msg: String,
+    status: StatusCode,
+    headers: Headers,
+    url: String,
+    body: Option<String>,
+) -> MagnusError {
+    let details = Details {
+        url: Some(url),
+        status: Some(status),
+        retryable: RetryPolicy::retries_by_default(ErrorKind::Status, Some(status.0.as_u16())),
+        ..Details::default()
+    };
+    let exception = details
+        .exception(ruby!().get_inner(&STATUS_ERROR), msg)
+        .and_then(|exception| {
+            exception.ivar_set("@headers", headers)?;
+            exception.ivar_set("@body", body)?;
+            Ok(exception)
+        });
+    match exception {
+        Ok(exception) => exception.into(),
+        Err(err) => err,
+    }
+}
+
+/// Get the URL of the request that failed, if known.
+fn error_url(rb_self: Exception) -> Result<Option<String>, MagnusError> {
+    rb_self.ivar_get("@url")
+}
+
+/// Get the status of the response that caused the error, if any.
+fn error_status(rb_self: Exception) -> Result<Option<Value>, MagnusError> {
+    rb_self.ivar_get("@status")
+}
+
+/// Get the messages of the underlying errors, outermost first.
+fn error_cause_chain(ruby: &Ruby, rb_self: Exception) -> Result<RArray, MagnusError> {
+    let chain: Option<RArray> = rb_self.ivar_get("@cause_chain")?;
+    Ok(chain.unwrap_or_else(|| ruby.ary_new()))
+}
+
+/// Get the name of the OS error behind the error, such as `:ECONNREFUSED`, if any.
+fn error_io_error_kind(rb_self: Exception) -> Result<Option<Symbol>, MagnusError> {
+    rb_self.ivar_get("@io_error_kind")
+}
+
+/// Returns `true` if the default retry policy would retry the request.
+fn error_is_retryable(rb_self: Exception) -> Result<bool, MagnusError> {
+    let retryable: Value = rb_self.ivar_get("@retryable")?;
+    Ok(retryable.to_bool())
+}
+
+/// Get the phase of the request that timed out, or nil for other errors.
+fn error_phase(rb_self: Exception) -> Result<Option<Symbol>, MagnusError> {
+    rb_self.ivar_get("@phase")
+}
+
+/// Get the headers of the response, or nil if the error was not raised for one.
+fn status_error_headers(rb_self: Exception) -> Result<Option<Value>, MagnusError> {
+    rb_self.ivar_get("@headers")
+}
+
+/// Get the start of the response body, or nil if it could not be read.
+fn status_error_body(rb_self: Exception) -> Result<Option<String>, MagnusError> {
+    rb_self.ivar_get("@body")
+}
+
 /// Map [`wreq::Error`] to corresponding [`magnus::Error`]
 pub fn wreq_error_to_magnus(err: wreq::Error) -> MagnusError {
-    let error_msg = err.to_string();
-    map_wreq_error!(
-        ruby!(),
-        err,
-        error_msg,
-        is_builder => BUILDER_ERROR,
-        is_body => BODY_ERROR,
-        is_tls => TLS_ERROR,
-        is_connection_reset => CONNECTION_RESET_ERROR,
-        is_connect => CONNECTION_ERROR,
-        is_proxy_connect => PROXY_CONNECTION_ERROR,
-        is_decode => DECODING_ERROR,
-        is_redirect => REDIRECT_ERROR,
-        is_timeout => TIMEOUT_ERROR,
-        is_status => STATUS_ERROR,
-        is_request => REQUEST_ERROR,
-    )
+    let kind = ErrorKind::of(&err);
+    let status = err.status().map(StatusCode::from);
+    let details = Details {
+        url: err.url().map(ToString::to_string),
+        status,
+        cause_chain: cause_chain(&err),
+        io_errno: io_errno(&err),
+        retryable: RetryPolicy::retries_by_default(kind, status.map(|status| status.0.as_u16())),
+        phase: TimeoutPhase::of(&err),
+    };
+    details.into_error(kind.exception_class(&ruby!()), err.to_string())
 }
 
 pub fn include(ruby: &Ruby) {
+    Lazy::force(&ERROR, ruby);
+    let error = ruby.get_inner(&ERROR);
+    error
+        .define_method("url", method!(error_url, 0))
+        .expect("failed to define Error#url");
+    error
+        .define_method("status", method!(error_status, 0))
+        .expect("failed to define Error#status");
+    error
+        .define_method("cause_chain", method!(error_cause_chain, 0))
+        .expect("failed to define Error#cause_chain");
+    error
+        .define_method("io_error_kind", method!(error_io_error_kind, 0))
+        .expect("failed to define Error#io_error_kind");
+    error
+        .define_method("retryable?", method!(error_is_retryable, 0))
+        .expect("failed to define Error#retryable?");
+    error
+        .define_method("phase", method!(error_phase, 0))
+        .expect("failed to define Error#phase");
     Lazy::force(&MEMORY, ruby);
     Lazy::force(&CONNECTION_ERROR, ruby);
     Lazy::force(&PROXY_CONNECTION_ERROR, ruby);
@@ -138,6 +689,13 @@ pub fn include(ruby: &Ruby) {
     Lazy::force(&TLS_ERROR, ruby);
     Lazy::force(&REQUEST_ERROR, ruby);
     Lazy::force(&STATUS_ERROR, ruby);
+    let status_error = ruby.get_inner(&STATUS_ERROR);
+    status_error
+        .define_method("headers", method!(status_error_headers, 0))
+        .expect("failed to define StatusError#headers");
+    status_error
+        .define_method("body", method!(status_error_body, 0))
+        .expect("failed to define StatusError#body");
     Lazy::force(&REDIRECT_ERROR, ruby);
     Lazy::force(&TIMEOUT_ERROR, ruby);
     Lazy::force(&BODY_ERROR, ruby);
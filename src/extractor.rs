@@ -1,12 +1,13 @@
 use bytes::Bytes;
-use magnus::{RArray, RHash, RString, Ruby, TryConvert, r_hash::ForEach};
+use magnus::{RArray, RHash, RString, Ruby, TryConvert, r_hash::ForEach, value::ReprValue};
 use wreq::{
-    Proxy, Version,
+    Version,
     header::{HeaderMap, HeaderName, HeaderValue, OrigHeaderMap},
 };
 
-use crate::error::{
-    header_name_error_to_magnus, header_value_error_to_magnus, wreq_error_to_magnus,
+use crate::{
+    client::proxy::ProxySetting,
+    error::{header_name_error_to_magnus, header_value_error_to_magnus},
 };
 
 /// A trait that defines the parameter name for extraction.
@@ -173,25 +174,22 @@ impl TryConvert for Extractor<Vec<HeaderValue>> {
     }
 }
 
-// ===== impl Extractor<Proxy> =====
+// ===== impl Extractor<ProxySetting> =====
 
-impl ExtractorName for Proxy {
+impl ExtractorName for ProxySetting {
     const NAME: &str = "proxy";
 }
 
-impl TryConvert for Extractor<Proxy> {
+impl TryConvert for Extractor<ProxySetting> {
     fn try_convert(value: magnus::Value) -> Result<Self, magnus::Error> {
         let ruby = Ruby::get_with(value);
         let keyword = RHash::try_convert(value)?;
 
         if let Some(proxy) = keyword
-            .get(ruby.to_symbol(Proxy::NAME))
-            .and_then(RString::from_value)
+            .get(ruby.to_symbol(ProxySetting::NAME))
+            .filter(|proxy| !proxy.is_nil())
         {
-            return Proxy::all(proxy.to_bytes().as_ref())
-                .map(Some)
-                .map(Extractor)
-                .map_err(wreq_error_to_magnus);
+            return ProxySetting::new(&ruby, proxy).map(Some).map(Extractor);
         }
 
         Ok(Extractor(None))
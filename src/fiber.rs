@@ -0,0 +1,85 @@
+//! Allow usage of unsafe code for FFI with Ruby's fiber scheduler functions.
+#![allow(unsafe_code)]
+
+use std::{
+    io::Write,
+    os::unix::{io::IntoRawFd, net::UnixStream},
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+use magnus::{Error, Ruby, Value, kwargs, value::ReprValue};
+use tokio::runtime::Handle;
+
+/// Wakes a fiber parked on the read end of a socket pair by writing to the other end.
+struct SocketWaker(UnixStream);
+
+impl Wake for SocketWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // A full buffer already means a wakeup is pending, so errors can be ignored.
+        let _ = (&self.0).write(&[1]);
+    }
+}
+
+/// Returns `true` if the current fiber is non-blocking and a `Fiber.scheduler` is set.
+pub fn scheduler_active() -> bool {
+    unsafe { !rb_sys::NIL_P(rb_sys::rb_fiber_scheduler_current()) }
+}
+
+/// Drive a future to completion on the current thread while yielding to the fiber scheduler.
+///
+/// The future is polled with the GVL held, inside the runtime context so timers and
+/// I/O register with the runtime's drivers (which keep running on its worker threads).
+/// Whenever the future is pending, the current fiber waits on a socket that the
+/// future's waker writes to, so the scheduler's `io_wait` can run other fibers
+/// in the meantime. Interrupts raised while waiting drop the future, cancelling it.
+pub fn block_on<F: Future>(ruby: &Ruby, handle: &Handle, future: F) -> Result<F::Output, Error> {
+    let io_error = |err: std::io::Error| Error::new(ruby.exception_io_error(), err.to_string());
+
+    let (reader, writer) = UnixStream::pair().map_err(io_error)?;
+    reader.set_nonblocking(true).map_err(io_error)?;
+    writer.set_nonblocking(true).map_err(io_error)?;
+
+    // Ruby owns the read end from here on, and closes it once we are done.
+    let io: Value = ruby.class_io().funcall(
+        "for_fd",
+        (reader.into_raw_fd(), kwargs!("autoclose" => true)),
+    )?;
+
+    let waker = Waker::from(Arc::new(SocketWaker(writer)));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    let result = loop {
+        let poll = {
+            let _guard = handle.enter();
+            future.as_mut().poll(&mut cx)
+        };
+        if let Poll::Ready(output) = poll {
+            break Ok(output);
+        }
+        if let Err(err) = wait_readable(ruby, io) {
+            break Err(err);
+        }
+    };
+
+    let _: Value = io.funcall("close", ())?;
+    result
+}
+
+/// Park the current fiber until `io` is readable, then drain it.
+fn wait_readable(ruby: &Ruby, io: Value) -> Result<(), Error> {
+    let _: Value = io.funcall("wait_readable", ())?;
+    loop {
+        let chunk: Value = io.funcall("read_nonblock", (64, kwargs!("exception" => false)))?;
+        // `:wait_readable` means drained, `nil` means EOF.
+        if chunk.is_nil() || chunk.is_kind_of(ruby.class_symbol()) {
+            return Ok(());
+        }
+    }
+}
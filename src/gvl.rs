@@ -3,9 +3,14 @@
 
 use std::{ffi::c_void, mem::MaybeUninit, ptr::null_mut};
 
-use rb_sys::rb_thread_call_without_gvl;
+use rb_sys::{rb_thread_call_with_gvl, rb_thread_call_without_gvl, ruby_native_thread_p};
 use tokio::sync::watch;
 
+unsafe extern "C" {
+    // Exported by libruby but not declared in its public headers.
+    fn ruby_thread_has_gvl_p() -> std::ffi::c_int;
+}
+
 /// Container for safely passing closure and result through C callback.
 struct Args<F, R> {
     func: Option<F>,
@@ -55,7 +60,7 @@ struct UnblockData {
     sender: CancelSender,
 }
 
-unsafe extern "C" fn call_without_gvl<F, R>(arg: *mut c_void) -> *mut c_void
+unsafe extern "C" fn call_closure<F, R>(arg: *mut c_void) -> *mut c_void
 where
     F: FnOnce() -> R,
     R: Sized,
@@ -97,7 +102,7 @@ where
     let arg_ptr = &mut args as *mut _ as *mut c_void;
 
     unsafe {
-        rb_thread_call_without_gvl(Some(call_without_gvl::<F, R>), arg_ptr, None, null_mut());
+        rb_thread_call_without_gvl(Some(call_closure::<F, R>), arg_ptr, None, null_mut());
         args.result.assume_init()
     }
 }
@@ -152,3 +157,35 @@ where
         wrapper.result.assume_init()
     }
 }
+
+/// Executes the given closure with the GVL held, reacquiring it if the current thread
+/// released it through [`nogvl`] or [`nogvl_cancellable`].
+///
+/// Returns `None` when called from a thread that is not a Ruby thread (e.g. a Tokio worker),
+/// where Ruby code cannot run at all.
+pub fn with_gvl<F, R>(func: F) -> Option<R>
+where
+    F: FnOnce() -> R,
+    R: Sized,
+{
+    unsafe {
+        if ruby_native_thread_p() == 0 {
+            return None;
+        }
+        if ruby_thread_has_gvl_p() != 0 {
+            return Some(func());
+        }
+    }
+
+    let mut args = Args {
+        func: Some(func),
+        result: MaybeUninit::uninit(),
+    };
+
+    let arg_ptr = &mut args as *mut _ as *mut c_void;
+
+    unsafe {
+        rb_thread_call_with_gvl(Some(call_closure::<F, R>), arg_ptr);
+        Some(args.result.assume_init())
+    }
+}
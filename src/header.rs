@@ -124,6 +124,12 @@ impl Headers {
     pub fn to_s(&self) -> String {
         self.0.borrow().inspect()
     }
+
+    /// Copy the headers into a [`HeaderMap`].
+    #[inline]
+    pub fn to_header_map(&self) -> HeaderMap {
+        self.0.borrow().clone()
+    }
 }
 
 impl From<HeaderMap> for Headers {
@@ -7,72 +7,156 @@ mod cookie;
 mod emulate;
 mod error;
 mod extractor;
+mod fiber;
 mod gvl;
 mod header;
 mod http;
 mod rt;
 
-use magnus::{Error, Module, Ruby, Value};
+use magnus::{Error, Module, RArray, Ruby, Value, typed_data::Obj};
 
-use crate::client::{Client, resp::Response};
+use crate::client::{
+    Client,
+    pending::Pending,
+    resp::{DownloadResult, Response},
+    ws::WebSocket,
+};
 
 const RUBY_MODULE_NAME: &str = "Wreq";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Send a HTTP request.
 #[inline]
-pub fn request(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn request(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::request(&Client::default(), args)
 }
 
 /// Send a GET request.
 #[inline]
-pub fn get(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn get(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::get(&Client::default(), args)
 }
 
 /// Send a POST request.
 #[inline]
-pub fn post(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn post(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::post(&Client::default(), args)
 }
 
 /// Send a PUT request.
 #[inline]
-pub fn put(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn put(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::put(&Client::default(), args)
 }
 
 /// Send a DELETE request.
 #[inline]
-pub fn delete(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn delete(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::delete(&Client::default(), args)
 }
 
 /// Send a HEAD request.
 #[inline]
-pub fn head(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn head(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::head(&Client::default(), args)
 }
 
 /// Send an OPTIONS request.
 #[inline]
-pub fn options(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn options(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::options(&Client::default(), args)
 }
 
 /// Send a TRACE request.
 #[inline]
-pub fn trace(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn trace(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::trace(&Client::default(), args)
 }
 
 /// Send a PATCH request.
 #[inline]
-pub fn patch(args: &[Value]) -> Result<Response, magnus::Error> {
+pub fn patch(args: &[Value]) -> Result<Obj<Response>, magnus::Error> {
     Client::patch(&Client::default(), args)
 }
 
+/// Send a HTTP request in the background.
+#[inline]
+pub fn request_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::request_async(&Client::default(), args)
+}
+
+/// Send a GET request in the background.
+#[inline]
+pub fn get_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::get_async(&Client::default(), args)
+}
+
+/// Send a POST request in the background.
+#[inline]
+pub fn post_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::post_async(&Client::default(), args)
+}
+
+/// Send a PUT request in the background.
+#[inline]
+pub fn put_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::put_async(&Client::default(), args)
+}
+
+/// Send a DELETE request in the background.
+#[inline]
+pub fn delete_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::delete_async(&Client::default(), args)
+}
+
+/// Send a HEAD request in the background.
+#[inline]
+pub fn head_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::head_async(&Client::default(), args)
+}
+
+/// Send an OPTIONS request in the background.
+#[inline]
+pub fn options_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::options_async(&Client::default(), args)
+}
+
+/// Send a TRACE request in the background.
+#[inline]
+pub fn trace_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::trace_async(&Client::default(), args)
+}
+
+/// Send a PATCH request in the background.
+#[inline]
+pub fn patch_async(args: &[Value]) -> Result<Pending, magnus::Error> {
+    Client::patch_async(&Client::default(), args)
+}
+
+/// Send a GET request and save the response body to a file.
+#[inline]
+pub fn download(ruby: &Ruby, args: &[Value]) -> Result<DownloadResult, magnus::Error> {
+    Client::download(ruby, &Client::default(), args)
+}
+
+/// Send many requests concurrently.
+#[inline]
+pub fn parallel(ruby: &Ruby, args: &[Value]) -> Result<RArray, magnus::Error> {
+    Client::parallel(ruby, &Client::default(), args)
+}
+
+/// Open a WebSocket connection.
+#[inline]
+pub fn websocket(ruby: &Ruby, args: &[Value]) -> Result<WebSocket, magnus::Error> {
+    Client::websocket(ruby, &Client::default(), args)
+}
+
+/// Open a Server-Sent Events stream.
+#[inline]
+pub fn sse(ruby: &Ruby, args: &[Value]) -> Result<Value, magnus::Error> {
+    Client::sse(ruby, &Client::default(), args)
+}
+
 /// wreq ruby binding
 #[magnus::init]
 fn init(ruby: &Ruby) -> Result<(), Error> {
@@ -87,6 +171,19 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     gem_module.define_module_function("options", magnus::function!(options, -1))?;
     gem_module.define_module_function("trace", magnus::function!(trace, -1))?;
     gem_module.define_module_function("patch", magnus::function!(patch, -1))?;
+    gem_module.define_module_function("request_async", magnus::function!(request_async, -1))?;
+    gem_module.define_module_function("get_async", magnus::function!(get_async, -1))?;
+    gem_module.define_module_function("post_async", magnus::function!(post_async, -1))?;
+    gem_module.define_module_function("put_async", magnus::function!(put_async, -1))?;
+    gem_module.define_module_function("delete_async", magnus::function!(delete_async, -1))?;
+    gem_module.define_module_function("head_async", magnus::function!(head_async, -1))?;
+    gem_module.define_module_function("options_async", magnus::function!(options_async, -1))?;
+    gem_module.define_module_function("trace_async", magnus::function!(trace_async, -1))?;
+    gem_module.define_module_function("patch_async", magnus::function!(patch_async, -1))?;
+    gem_module.define_module_function("download", magnus::function!(download, -1))?;
+    gem_module.define_module_function("parallel", magnus::function!(parallel, -1))?;
+    gem_module.define_module_function("websocket", magnus::function!(websocket, -1))?;
+    gem_module.define_module_function("sse", magnus::function!(sse, -1))?;
     http::include(ruby, &gem_module)?;
     header::include(ruby, &gem_module)?;
     cookie::include(ruby, &gem_module)?;
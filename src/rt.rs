@@ -1,8 +1,14 @@
 use std::sync::LazyLock;
 
-use tokio::runtime::{Builder, Runtime};
+use tokio::{
+    runtime::{Builder, Runtime},
+    task::JoinHandle,
+};
 
-use crate::{error::interrupt_error, gvl};
+use crate::{
+    error::{RequestError, interrupt_error},
+    fiber, gvl,
+};
 
 static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
     Builder::new_multi_thread()
@@ -13,10 +19,17 @@ static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
 
 /// Block on a future to completion on the global Tokio runtime,
 /// with support for cancellation via the provided `CancelFlag`.
+///
+/// When called from a non-blocking fiber with an active `Fiber.scheduler`, the
+/// current fiber yields to the scheduler instead of blocking the whole thread.
 pub fn try_block_on<F, T>(future: F) -> F::Output
 where
     F: Future<Output = Result<T, magnus::Error>>,
 {
+    if fiber::scheduler_active() {
+        return fiber::block_on(&ruby!(), RUNTIME.handle(), future).and_then(|result| result);
+    }
+
     gvl::nogvl_cancellable(|flag| {
         RUNTIME.block_on(async move {
             tokio::select! {
@@ -28,20 +41,23 @@ where
     })
 }
 
-/// Block on a future to completion on the global Tokio runtime,
-/// returning `None` if cancelled via the provided `CancelFlag`.
+/// Block on a future failing with a [`RequestError`], like [`try_block_on`].
+///
+/// The error is only converted to a Ruby exception once the GVL is held again, as
+/// building the exception calls into Ruby.
+pub fn try_block_on_request<F, T>(future: F) -> Result<T, magnus::Error>
+where
+    F: Future<Output = Result<T, RequestError>>,
+{
+    try_block_on(async { Ok(future.await) })?.map_err(Into::into)
+}
+
+/// Spawn a future onto the global Tokio runtime, running it in the background.
 #[inline]
-pub fn maybe_block_on<F, T>(future: F) -> F::Output
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
 where
-    F: Future<Output = Option<T>>,
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
 {
-    gvl::nogvl_cancellable(|flag| {
-        RUNTIME.block_on(async move {
-            tokio::select! {
-                biased;
-                _ = flag.cancelled() => None,
-                result = future => result,
-            }
-        })
-    })
+    RUNTIME.spawn(future)
 }
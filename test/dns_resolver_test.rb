@@ -0,0 +1,122 @@
+require "test_helper"
+require "json"
+require "socket"
+require "uri"
+
+class DnsResolverTest < Minitest::Test
+  # Minimal DNS-over-HTTPS JSON API stand-in answering A queries with 127.0.0.1.
+  class DohServer
+    attr_reader :queries
+
+    def initialize
+      @queries = Queue.new
+      @tcp = TCPServer.new("127.0.0.1", 0)
+      @thread = Thread.new { loop { serve(@tcp.accept) } }
+    end
+
+    def url
+      "http://127.0.0.1:#{@tcp.addr[1]}/dns-query"
+    end
+
+    def stop
+      @thread.kill
+      @tcp.close
+    end
+
+    private
+
+    def serve(socket)
+      path = socket.gets("\r\n").split[1]
+      while (line = socket.gets("\r\n")) && line != "\r\n"; end
+      params = URI.decode_www_form(URI(path).query).to_h
+      @queries << params
+      answer = (params["type"] == "A") ? [{name: params["name"], type: 1, TTL: 30, data: "127.0.0.1"}] : []
+      body = JSON.generate({Status: 0, Answer: answer})
+      socket.write("HTTP/1.1 200 OK\r\nContent-Type: application/dns-json\r\nContent-Length: #{body.bytesize}\r\nConnection: close\r\n\r\n#{body}")
+      socket.close
+    end
+  end
+
+  def test_callable_resolver
+    lookups = []
+    client = Wreq::Client.new(dns_resolver: ->(host) {
+      lookups << host
+      ["127.0.0.1"]
+    })
+    response = client.get("http://httpbin.test:8080/get")
+    assert_equal 200, response.code
+    assert_equal "httpbin.test:8080", response.json["headers"]["Host"]
+    assert_equal ["httpbin.test"], lookups
+  end
+
+  def test_callable_returning_nil_uses_system_resolver
+    client = Wreq::Client.new(dns_resolver: {call: ->(_host) {}})
+    assert_equal 200, client.get("http://localhost:8080/get").code
+  end
+
+  def test_callable_errors_are_raised_as_is
+    error = Class.new(StandardError)
+    client = Wreq::Client.new(dns_resolver: ->(_host) { raise error, "lookup refused" })
+    raised = assert_raises(error) { client.get("http://httpbin.test:8080/get") }
+    assert_equal "lookup refused", raised.message
+    assert raised.backtrace.any? { |line| line.include?(__FILE__) }
+  end
+
+  def test_invalid_callable_results_fail_the_request
+    client = Wreq::Client.new(dns_resolver: ->(_host) { "not an address" })
+    assert_raises(Wreq::ConnectionError) { client.get("http://httpbin.test:8080/get") }
+  end
+
+  def test_callable_refused_in_background_and_parallel_requests
+    client = Wreq::Client.new(dns_resolver: ->(_host) { "127.0.0.1" })
+    error = assert_raises(ArgumentError) { client.get_async("http://httpbin.test:8080/get") }
+    assert_includes error.message, "background requests"
+    error = assert_raises(ArgumentError) { client.parallel([{url: "http://httpbin.test:8080/get"}]) }
+    assert_includes error.message, "parallel requests"
+  end
+
+  def test_resolve_overrides_take_precedence
+    client = Wreq::Client.new(
+      dns_resolver: ->(_host) { raise "not called" },
+      resolve: {"httpbin.test" => "127.0.0.1"}
+    )
+    assert_equal 200, client.get("http://httpbin.test:8080/get").code
+  end
+
+  def test_cache
+    calls = 0
+    client = Wreq::Client.new(
+      dns_resolver: {call: ->(_host) { calls += 1; "127.0.0.1" }, cache: {ttl: 60}},
+      pool_max_idle_per_host: 0
+    )
+    2.times { assert_equal 200, client.get("http://httpbin.test:8080/get").code }
+    assert_equal 1, calls
+    assert_equal({hits: 1, misses: 1, entries: 1}, client.dns_cache_stats)
+  end
+
+  def test_no_cache_stats_without_cache
+    assert_nil Wreq::Client.new.dns_cache_stats
+    assert_nil Wreq::Client.new(dns_resolver: {cache: false}).dns_cache_stats
+  end
+
+  def test_dns_over_https
+    server = DohServer.new
+    client = Wreq::Client.new(dns_resolver: {doh: server.url, cache: true})
+    response = client.get("http://doh.test:8080/get")
+    assert_equal 200, response.code
+
+    queries = Array.new(2) { server.queries.pop }
+    assert_equal ["A", "AAAA"], queries.map { |query| query["type"] }.sort
+    assert_equal ["doh.test"], queries.map { |query| query["name"] }.uniq
+    assert_equal 1, client.dns_cache_stats[:entries]
+  ensure
+    server&.stop
+  end
+
+  def test_invalid_dns_resolver
+    assert_raises(ArgumentError) { Wreq::Client.new(dns_resolver: :bogus) }
+    assert_raises(ArgumentError) { Wreq::Client.new(dns_resolver: {call: 42}) }
+    assert_raises(ArgumentError) { Wreq::Client.new(dns_resolver: {doh: "ftp://dns.example/"}) }
+    assert_raises(ArgumentError) { Wreq::Client.new(dns_resolver: {cache: {max_entries: 0}}) }
+  end
+end
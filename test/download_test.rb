@@ -0,0 +1,98 @@
+require "test_helper"
+require "digest"
+require "json"
+require "pathname"
+require "stringio"
+require "tmpdir"
+
+class DownloadTest < Minitest::Test
+  def test_save_to_file
+    Dir.mktmpdir do |dir|
+      path = File.join(dir, "bytes.bin")
+      response = Wreq.get("http://localhost:8080/bytes/4096")
+      result = response.save_to(path)
+
+      assert_instance_of Wreq::DownloadResult, result
+      assert_equal path, result.path
+      assert_equal 4096, result.bytes
+      assert_nil result.digest
+      assert_equal 4096, File.size(path)
+    end
+  end
+
+  def test_save_to_atomic_with_digest
+    Dir.mktmpdir do |dir|
+      path = Pathname(dir).join("data.json")
+      result = Wreq.get("http://localhost:8080/json").save_to(path, atomic: true, digest: :sha256)
+
+      assert_equal Digest::SHA256.file(path).hexdigest, result.digest
+      assert_equal [path.basename.to_s], Dir.children(dir)
+    end
+  end
+
+  def test_save_to_progress
+    Dir.mktmpdir do |dir|
+      progress = []
+      response = Wreq.get("http://localhost:8080/stream-bytes/8192?chunk_size=1024")
+      result = response.save_to(File.join(dir, "stream.bin")) { |written, _total| progress << written }
+
+      assert_equal 8192, result.bytes
+      refute_empty progress
+      assert_equal progress.sort, progress
+      assert_equal 8192, progress.last
+    end
+  end
+
+  def test_copy_to_io
+    io = StringIO.new(+"")
+    result = Wreq.get("http://localhost:8080/bytes/1024").copy_to(io, digest: "sha512")
+
+    assert_nil result.path
+    assert_equal 1024, result.bytes
+    assert_equal 1024, io.string.bytesize
+    assert_equal Digest::SHA512.hexdigest(io.string), result.digest
+  end
+
+  def test_download_helper
+    Dir.mktmpdir do |dir|
+      path = File.join(dir, "get.json")
+      result = Wreq.download("http://localhost:8080/get", path, query: {"a" => "1"})
+      assert_equal File.size(path), result.bytes
+      assert_equal "1", JSON.parse(File.read(path))["args"]["a"]
+
+      client = Wreq::Client.new
+      result = client.download("http://localhost:8080/bytes/10", path, atomic: true)
+      assert_equal 10, result.bytes
+    end
+  end
+
+  def test_download_error_status_raises_without_writing
+    Dir.mktmpdir do |dir|
+      path = File.join(dir, "missing.html")
+      error = assert_raises(Wreq::StatusError) do
+        Wreq.download("http://localhost:8080/status/404", path)
+      end
+      assert_equal 404, error.status.as_int
+      refute File.exist?(path)
+
+      assert_raises(Wreq::StatusError) do
+        Wreq::Client.new.download("http://localhost:8080/status/503", path, atomic: true)
+      end
+      assert_empty Dir.children(dir)
+    end
+  end
+
+  def test_download_error_status_opt_out
+    Dir.mktmpdir do |dir|
+      path = File.join(dir, "missing.html")
+      result = Wreq.download("http://localhost:8080/status/404", path, raise_on_status: false)
+      assert_equal 0, result.bytes
+      assert File.exist?(path)
+    end
+  end
+
+  def test_unsupported_digest
+    response = Wreq.get("http://localhost:8080/bytes/10")
+    assert_raises(ArgumentError) { response.copy_to(StringIO.new, digest: :md4) }
+  end
+end
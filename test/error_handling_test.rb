@@ -86,4 +86,56 @@ class ErrorHandlingTest < Minitest::Test
       end
     end
   end
+
+  def test_errors_share_base_class
+    assert_operator Wreq::Error, :<, RuntimeError
+    [
+      Wreq::MemoryError, Wreq::ConnectionError, Wreq::ProxyConnectionError,
+      Wreq::ConnectionResetError, Wreq::TlsError, Wreq::RequestError, Wreq::StatusError,
+      Wreq::RedirectError, Wreq::TimeoutError, Wreq::BodyError, Wreq::DecodingError,
+      Wreq::BuilderError
+    ].each { |klass| assert_operator klass, :<, Wreq::Error }
+  end
+
+  def test_connection_error_attributes
+    error = assert_raises(Wreq::ConnectionError) { Wreq.get("http://127.0.0.1:1/path") }
+    assert_includes error.url, "127.0.0.1:1/path"
+    assert_equal :ECONNREFUSED, error.io_error_kind
+    refute_empty error.cause_chain
+    assert error.cause_chain.all?(String)
+    assert error.retryable?
+    assert_nil error.status
+    assert_nil error.phase
+  end
+
+  def test_timeout_error_attributes
+    error = assert_raises(Wreq::TimeoutError) do
+      Wreq.get("http://localhost:8080/delay/3", timeout: 0.5)
+    end
+    assert_equal :total, error.phase
+    assert error.retryable?
+    assert_includes error.url, "/delay/3"
+  end
+
+  def test_status_error_attributes
+    error = assert_raises(Wreq::StatusError) do
+      Wreq.get("http://localhost:8080/status/503", raise_on_status: true)
+    end
+    assert_equal 503, error.status.as_int
+    assert error.retryable?
+    assert_empty error.cause_chain
+
+    error = assert_raises(Wreq::StatusError) do
+      Wreq.get("http://localhost:8080/status/404", raise_on_status: true)
+    end
+    refute error.retryable?
+  end
+
+  def test_errors_created_in_ruby
+    error = Wreq::ConnectionError.new("failed")
+    assert_nil error.url
+    assert_nil error.io_error_kind
+    assert_equal [], error.cause_chain
+    refute error.retryable?
+  end
 end
@@ -0,0 +1,151 @@
+require "test_helper"
+require "io/wait"
+
+# Minimal IO.select based fiber scheduler, enough to run requests concurrently.
+class TestScheduler
+  def initialize
+    @readable = {}
+    @writable = {}
+    @waiting = {}
+    @ready = []
+  end
+
+  def fiber(&block)
+    fiber = Fiber.new(blocking: false, &block)
+    fiber.resume
+    fiber
+  end
+
+  def io_wait(io, events, timeout)
+    @readable[io] = Fiber.current if events.anybits?(IO::READABLE)
+    @writable[io] = Fiber.current if events.anybits?(IO::WRITABLE)
+    @waiting[Fiber.current] = current_time + timeout if timeout
+    Fiber.yield
+    events
+  ensure
+    @readable.delete(io)
+    @writable.delete(io)
+    @waiting.delete(Fiber.current)
+  end
+
+  def kernel_sleep(duration = nil)
+    @waiting[Fiber.current] = current_time + (duration || 0)
+    Fiber.yield
+    true
+  end
+
+  def block(_blocker, timeout = nil)
+    @waiting[Fiber.current] = current_time + (timeout || 0)
+    Fiber.yield
+  ensure
+    @waiting.delete(Fiber.current)
+  end
+
+  def unblock(_blocker, fiber)
+    @ready << fiber
+  end
+
+  def close
+    run
+  end
+
+  private
+
+  def run
+    until @readable.empty? && @writable.empty? && @waiting.empty? && @ready.empty?
+      timeout = @waiting.values.min&.then { |at| [at - current_time, 0].max }
+      readable, writable = IO.select(@readable.keys, @writable.keys, [], @ready.empty? ? timeout : 0)
+
+      readable&.each { |io| @readable[io]&.resume }
+      writable&.each { |io| @writable[io]&.resume }
+      @waiting.select { |_, at| at <= current_time }.each_key do |fiber|
+        @waiting.delete(fiber)
+        fiber.resume if fiber.alive?
+      end
+      @ready.shift.resume until @ready.empty?
+    end
+  end
+
+  def current_time
+    Process.clock_gettime(Process::CLOCK_MONOTONIC)
+  end
+end
+
+class FiberSchedulerTest < Minitest::Test
+  def with_scheduler(&block)
+    Thread.new do
+      Fiber.set_scheduler(TestScheduler.new)
+      block.call
+    end.join
+  end
+
+  def test_requests_run_concurrently
+    results = []
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    with_scheduler do
+      3.times do |i|
+        Fiber.schedule do
+          response = Wreq.get("http://localhost:8080/delay/1", query: {"i" => i.to_s})
+          results << [i, response.code, response.json["args"]["i"]]
+        end
+      end
+    end
+    elapsed = Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+
+    assert_equal [[0, 200, "0"], [1, 200, "1"], [2, 200, "2"]], results.sort
+    assert_operator elapsed, :<, 2.5, "requests should not block each other"
+  end
+
+  def test_other_fibers_progress_during_request
+    events = []
+    with_scheduler do
+      Fiber.schedule do
+        Wreq.get("http://localhost:8080/delay/1").text
+        events << :request
+      end
+      Fiber.schedule do
+        sleep 0.1
+        events << :sleep
+      end
+    end
+
+    assert_equal [:sleep, :request], events
+  end
+
+  def test_other_fibers_progress_during_streaming_read
+    events = []
+    with_scheduler do
+      Fiber.schedule do
+        response = Wreq.get("http://localhost:8080/drip?duration=1&numbytes=2&delay=0")
+        response.chunks { |chunk| events << :chunk unless chunk.empty? }
+      end
+      Fiber.schedule do
+        sleep 0.1
+        events << :sleep
+      end
+    end
+
+    assert_equal :sleep, events.first
+    assert_includes events, :chunk
+  end
+
+  def test_errors_propagate_to_the_fiber
+    error = nil
+    with_scheduler do
+      Fiber.schedule do
+        Wreq.get("http://localhost:8080/delay/3", timeout: 1)
+      rescue Wreq::TimeoutError => e
+        error = e
+      end
+    end
+
+    assert_instance_of Wreq::TimeoutError, error
+  end
+
+  def test_blocking_fiber_is_unaffected
+    with_scheduler do
+      response = Fiber.new(blocking: true) { Wreq.get("http://localhost:8080/get") }.resume
+      assert_equal 200, response.code
+    end
+  end
+end
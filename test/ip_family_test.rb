@@ -0,0 +1,89 @@
+require "test_helper"
+require "socket"
+
+class IpFamilyTest < Minitest::Test
+  def setup
+    @servers = []
+    @threads = []
+  end
+
+  def teardown
+    @threads.each(&:kill)
+    @servers.each(&:close)
+  end
+
+  def test_response_reports_family
+    response = Wreq.get("http://127.0.0.1:8080/get")
+    assert_equal :v4, response.ip_family
+    assert_match(/\A127\.0\.0\.1:8080\z/, response.remote_addr)
+  end
+
+  def test_client_ip_family_filters_addresses
+    client = Wreq::Client.new(ip_family: :v4, resolve: {"dual.test" => ["::1", "127.0.0.1"]})
+    response = client.get("http://dual.test:8080/get")
+    assert_equal :v4, response.ip_family
+  end
+
+  def test_request_ip_family_without_matching_addresses
+    assert_raises(Wreq::ConnectionError) do
+      Wreq.get("http://v4only.test:8080/get", resolve: {"v4only.test" => "127.0.0.1"}, ip_family: "v6")
+    end
+  end
+
+  def test_request_ip_family_overrides_client
+    client = Wreq::Client.new(ip_family: :v6, resolve: {"v4only.test" => "127.0.0.1"})
+    assert_raises(Wreq::ConnectionError) { client.get("http://v4only.test:8080/get") }
+    assert_equal :v4, client.get("http://v4only.test:8080/get", ip_family: :any).ip_family
+  end
+
+  def test_happy_eyeballs_delay
+    [0.05, 1, false].each do |delay|
+      client = Wreq::Client.new(happy_eyeballs_delay: delay, resolve: {"dual.test" => ["127.0.0.1", "::1"]})
+      assert_equal 200, client.get("http://dual.test:8080/get").code
+    end
+  end
+
+  def test_invalid_options
+    assert_raises(ArgumentError) { Wreq::Client.new(ip_family: :v5) }
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", ip_family: "both") }
+    assert_raises(ArgumentError) { Wreq::Client.new(happy_eyeballs_delay: -1) }
+  end
+
+  def test_request_ip_family_does_not_reuse_connection
+    port = listen("127.0.0.1")
+    listen("::1", port)
+    client = Wreq::Client.new(resolve: {"dual.test" => ["127.0.0.1", "::1"]})
+    url = "http://dual.test:#{port}/"
+    assert_equal "127.0.0.1", client.get(url).text
+    assert_equal "::1", client.get(url, ip_family: :v6).text
+    assert_equal "127.0.0.1", client.get(url, ip_family: :v4).text
+  end
+
+  private
+
+  # Listen on `host`, answering each request on a kept-alive connection with `host`.
+  def listen(host, port = 0)
+    server = TCPServer.new(host, port)
+    @servers << server
+    @threads << Thread.new do
+      loop { @threads << Thread.new(server.accept) { |socket| answer(socket, host) } }
+    end
+    server.addr[1]
+  end
+
+  def answer(socket, host)
+    while socket.gets("\r\n")
+      length = 0
+      while (line = socket.gets("\r\n")) && line != "\r\n"
+        name, value = line.chomp.split(": ", 2)
+        length = value.to_i if name.casecmp?("content-length")
+      end
+      socket.read(length)
+      socket.write("HTTP/1.1 200 OK\r\nContent-Length: #{host.bytesize}\r\n\r\n#{host}")
+    end
+  rescue IOError, SystemCallError
+    nil
+  ensure
+    socket.close
+  end
+end
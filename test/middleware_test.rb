@@ -0,0 +1,103 @@
+require "test_helper"
+
+class MiddlewareTest < Minitest::Test
+  class Recorder
+    attr_reader :events
+
+    def initialize(name, events)
+      @name = name
+      @events = events
+    end
+
+    def call(request)
+      @events << [:call, @name]
+      request.headers.set("X-#{@name}", "1")
+    end
+
+    def on_response(request, response)
+      @events << [:on_response, @name, response.code]
+      nil
+    end
+  end
+
+  def test_hooks_run_in_onion_order
+    events = []
+    client = Wreq::Client.new(middleware: [Recorder.new("A", events), Recorder.new("B", events)])
+    response = client.get("http://localhost:8080/headers")
+    assert_equal "1", response.json["headers"]["X-A"]
+    assert_equal "1", response.json["headers"]["X-B"]
+    assert_equal [[:call, "A"], [:call, "B"], [:on_response, "B", 200], [:on_response, "A", 200]], events
+  end
+
+  def test_use_appends_and_returns_client
+    client = Wreq::Client.new
+    assert_same client, client.use(->(request) { request.headers.set("X-Used", "yes") })
+    response = client.get("http://localhost:8080/headers")
+    assert_equal "yes", response.json["headers"]["X-Used"]
+  end
+
+  def test_rewrites_url_and_body
+    client = Wreq::Client.new(middleware: ->(request) {
+      assert_kind_of Wreq::Method, request.method
+      assert_equal "original", request.body
+      request.url = request.url.sub("/anything", "/post")
+      request.body = "rewritten"
+    })
+    response = client.post("http://localhost:8080/anything", body: "original")
+    assert_equal "rewritten", response.json["data"]
+  end
+
+  def test_body_replaces_json
+    client = Wreq::Client.new(middleware: ->(request) { request.body = "plain" })
+    response = client.post("http://localhost:8080/post", json: {"a" => 1})
+    assert_equal "plain", response.json["data"]
+  end
+
+  def test_on_response_can_replace_response
+    other = Wreq.get("http://localhost:8080/status/204")
+    replacer = Object.new
+    replacer.define_singleton_method(:on_response) { |_request, _response| other }
+    client = Wreq::Client.new(middleware: [replacer])
+    assert_same other, client.get("http://localhost:8080/get")
+  end
+
+  def test_errors_propagate
+    client = Wreq::Client.new(middleware: ->(_request) { raise ArgumentError, "bad" })
+    error = assert_raises(ArgumentError) { client.get("http://localhost:8080/get") }
+    assert_equal "bad", error.message
+
+    client = Wreq::Client.new(middleware: ->(request) { request.url = "http://127.0.0.1:1/" })
+    assert_raises(Wreq::ConnectionError) { client.get("http://localhost:8080/get") }
+  end
+
+  def test_async_and_parallel
+    events = []
+    client = Wreq::Client.new(middleware: Recorder.new("A", events))
+    response = client.get_async("http://localhost:8080/headers").value
+    assert_equal "1", response.json["headers"]["X-A"]
+
+    results = client.parallel([{url: "http://localhost:8080/headers"}])
+    assert_equal "1", results[0].json["headers"]["X-A"]
+    assert_equal 4, events.length
+  end
+
+  def test_on_response_error_in_parallel
+    raiser = Object.new
+    raiser.define_singleton_method(:on_response) do |_request, response|
+      raise ArgumentError, "bad status" if response.code == 418
+    end
+    client = Wreq::Client.new(middleware: [raiser])
+    results = client.parallel([
+      {url: "http://localhost:8080/status/418"},
+      {url: "http://localhost:8080/get"}
+    ])
+    assert_instance_of ArgumentError, results[0]
+    assert_equal "bad status", results[0].message
+    assert_equal 200, results[1].code
+  end
+
+  def test_invalid_middleware
+    assert_raises(ArgumentError) { Wreq::Client.new(middleware: [Object.new]) }
+    assert_raises(ArgumentError) { Wreq::Client.new.use(42) }
+  end
+end
@@ -0,0 +1,108 @@
+require "test_helper"
+require "pathname"
+require "socket"
+require "stringio"
+require "tempfile"
+
+class MultipartTest < Minitest::Test
+  def setup
+    @client = Wreq::Client.new
+  end
+
+  def test_text_parts
+    form = Wreq::Multipart.new(
+      Wreq::Part.new("name", "wreq"),
+      Wreq::Part.new("lang", "ruby")
+    )
+    response = @client.post("http://localhost:8080/post", multipart: form)
+    assert_equal 200, response.code
+
+    json = response.json
+    assert_equal "wreq", json["form"]["name"]
+    assert_equal "ruby", json["form"]["lang"]
+    assert_match(%r{multipart/form-data; boundary=}, json["headers"]["Content-Type"])
+  end
+
+  def test_bytes_part_with_filename
+    data = "\x00\x01binary".b
+    form = Wreq::Multipart.new(
+      Wreq::Part.new("blob", data, filename: "blob.bin", mime: "application/octet-stream")
+    )
+    response = @client.post("http://localhost:8080/post", multipart: form)
+    assert_equal 200, response.code
+    assert response.json["files"].key?("blob")
+  end
+
+  def test_file_path_part
+    Tempfile.create(["wreq", ".txt"]) do |file|
+      file.write("file contents")
+      file.flush
+
+      form = Wreq::Multipart.new(Wreq::Part.new("upload", Pathname(file.path)))
+      response = @client.post("http://localhost:8080/post", multipart: form)
+      assert_equal 200, response.code
+      assert_equal "file contents", response.json["files"]["upload"]
+    end
+  end
+
+  def test_file_parts_are_typed_from_their_name
+    server = TCPServer.new("127.0.0.1", 0)
+    thread = Thread.new { read_body(server.accept) }
+    Tempfile.create(["wreq", ".txt"]) do |file|
+      file.write("skipped|sent")
+      file.rewind
+      file.read(8)
+      form = Wreq::Multipart.new(
+        Wreq::Part.new("upload", file),
+        Wreq::Part.new("typed", Pathname(file.path), mime: "application/x-custom")
+      )
+      @client.post("http://127.0.0.1:#{server.addr[1]}/upload", multipart: form)
+    end
+    body = thread.value
+    assert_match(%r{name="upload"; filename="[^"]+\.txt"\r\nContent-Type: text/plain\r\n\r\nsent\r\n}i, body)
+    assert_match(%r{name="typed"; filename="[^"]+\.txt"\r\nContent-Type: application/x-custom\r\n\r\nskipped\|sent\r\n}i, body)
+  ensure
+    server&.close
+  end
+
+  def test_io_part
+    io = StringIO.new("streamed from io")
+    form = Wreq::Multipart.new
+    form.add(Wreq::Part.new("stream", io, filename: "io.txt", mime: "text/plain"))
+    response = @client.post("http://localhost:8080/post", multipart: form)
+    assert_equal 200, response.code
+    assert_equal "streamed from io", response.json["files"]["stream"]
+  end
+
+  def test_part_headers
+    part = Wreq::Part.new("meta", "value", headers: {"X-Part" => "yes"})
+    assert_equal "meta", part.name
+    response = Wreq.post("http://localhost:8080/post", multipart: Wreq::Multipart.new(part))
+    assert_equal 200, response.code
+    assert_equal "value", response.json["form"]["meta"]
+  end
+
+  def test_part_is_single_use
+    part = Wreq::Part.new("name", "wreq")
+    Wreq::Multipart.new(part)
+    assert_raises(Wreq::MemoryError) { Wreq::Multipart.new(part) }
+  end
+
+  def test_invalid_part_value
+    assert_raises(TypeError) { Wreq::Part.new("name", 42) }
+  end
+
+  private
+
+  def read_body(socket)
+    length = 0
+    while (line = socket.gets("\r\n")) && line != "\r\n"
+      name, value = line.chomp.split(": ", 2)
+      length = value.to_i if name.casecmp?("content-length")
+    end
+    body = socket.read(length)
+    socket.write("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+    socket.close
+    body
+  end
+end
@@ -0,0 +1,86 @@
+require "test_helper"
+
+class ParallelTest < Minitest::Test
+  def setup
+    @client = Wreq::Client.new
+  end
+
+  def test_results_in_input_order
+    requests = 5.times.map { |i| {url: "http://localhost:8080/get", query: {"i" => i.to_s}} }
+    results = @client.parallel(requests)
+
+    assert_equal 5, results.size
+    results.each_with_index do |response, i|
+      assert_instance_of Wreq::Response, response
+      assert_equal i.to_s, response.json["args"]["i"]
+    end
+  end
+
+  def test_methods_and_options
+    results = Wreq.parallel([
+      {url: "http://localhost:8080/get"},
+      {method: Wreq::Method::POST, url: "http://localhost:8080/post", json: {"id" => 1}},
+      {method: Wreq::Method::PUT, url: "http://localhost:8080/put", body: "payload"}
+    ])
+
+    assert_equal [200, 200, 200], results.map(&:code)
+    assert_equal({"id" => 1}, results[1].json["json"])
+    assert_equal "payload", results[2].json["data"]
+  end
+
+  def test_runs_concurrently
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    results = @client.parallel(Array.new(4) { {url: "http://localhost:8080/delay/1"} })
+    elapsed = Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+
+    assert(results.all? { |r| r.code == 200 })
+    assert_operator elapsed, :<, 3
+  end
+
+  def test_concurrency_limit
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    @client.parallel(Array.new(2) { {url: "http://localhost:8080/delay/1"} }, concurrency: 1)
+    elapsed = Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+
+    assert_operator elapsed, :>=, 2
+  end
+
+  def test_errors_are_returned_as_exceptions
+    results = @client.parallel([
+      {url: "http://localhost:8080/delay/3", timeout: 1},
+      {url: "http://localhost:8080/status/204"}
+    ])
+
+    assert_kind_of Wreq::TimeoutError, results[0]
+    assert_equal 204, results[1].code
+  end
+
+  def test_errors_are_exception_instances
+    results = @client.parallel([
+      {url: "http://localhost:8080/redirect/3", max_redirects: 1},
+      {url: "http://127.0.0.1:1/"}
+    ])
+
+    assert_instance_of Wreq::RedirectError, results[0]
+    assert_includes results[0].message, "too many redirects"
+    assert_kind_of Wreq::ConnectionError, results[1]
+    refute_empty results[1].message
+  end
+
+  def test_completion_block
+    yielded = []
+    results = @client.parallel([
+      {url: "http://localhost:8080/delay/1"},
+      {url: "http://localhost:8080/get"}
+    ]) { |result, index| yielded << [index, result.code] }
+
+    assert_equal [[1, 200], [0, 200]], yielded
+    assert_equal [200, 200], results.map(&:code)
+  end
+
+  def test_invalid_arguments
+    assert_raises(ArgumentError) { @client.parallel([{method: Wreq::Method::GET}]) }
+    assert_raises(ArgumentError) { @client.parallel([{url: "http://localhost:8080/get"}], concurrency: 0) }
+    assert_empty @client.parallel([])
+  end
+end
@@ -0,0 +1,90 @@
+require "test_helper"
+
+class PendingTest < Minitest::Test
+  def setup
+    @client = Wreq::Client.new
+  end
+
+  def test_get_async_value
+    pending = @client.get_async("http://localhost:8080/get", query: {"a" => "1"})
+    assert_instance_of Wreq::Pending, pending
+
+    response = pending.value
+    assert_equal 200, response.code
+    assert_equal "1", response.json["args"]["a"]
+    assert pending.ready?
+  end
+
+  def test_verb_variants
+    post = Wreq.post_async("http://localhost:8080/post", json: {"id" => 1})
+    put = @client.put_async("http://localhost:8080/put", body: "data")
+    request = @client.request_async(Wreq::Method::DELETE, "http://localhost:8080/delete")
+
+    assert_equal({"id" => 1}, post.value.json["json"])
+    assert_equal "data", put.value.json["data"]
+    assert_equal 200, request.value.code
+  end
+
+  def test_requests_run_in_background
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    pending = Array.new(3) { @client.get_async("http://localhost:8080/delay/1") }
+    refute pending.first.ready?
+
+    assert Wreq::Pending.wait_all(pending)
+    elapsed = Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+    assert(pending.all?(&:ready?))
+    assert_operator elapsed, :<, 2.5
+  end
+
+  def test_wait_timeout
+    pending = @client.get_async("http://localhost:8080/delay/2")
+    refute pending.wait(0.1)
+    refute pending.ready?
+    assert pending.wait(5)
+    assert pending.ready?
+  end
+
+  def test_wait_any
+    slow = @client.get_async("http://localhost:8080/delay/2")
+    fast = @client.get_async("http://localhost:8080/get")
+
+    assert_same fast, Wreq::Pending.wait_any([slow, fast])
+    assert_nil Wreq::Pending.wait_any([slow], 0.1)
+    assert_nil Wreq::Pending.wait_any([])
+    refute Wreq::Pending.wait_all([slow], 0.1)
+    slow.cancel
+  end
+
+  def test_value_raises_mapped_error
+    pending = @client.get_async("http://localhost:8080/delay/3", timeout: 1)
+    assert_raises(Wreq::TimeoutError) { pending.value }
+  end
+
+  def test_cancel
+    pending = @client.get_async("http://localhost:8080/delay/3")
+    assert pending.cancel
+    refute pending.cancel
+    assert pending.wait(1)
+    assert pending.ready?
+    assert_raises(Wreq::RequestError) { pending.value }
+  end
+
+  def test_cancel_after_completion
+    pending = @client.get_async("http://localhost:8080/get")
+    pending.wait
+    refute pending.cancel
+    assert_equal 200, pending.value.code
+  end
+
+  def test_value_can_only_be_taken_once
+    pending = @client.get_async("http://localhost:8080/get")
+    pending.value
+    assert_raises(Wreq::MemoryError) { pending.value }
+  end
+
+  def test_invalid_timeout
+    pending = @client.get_async("http://localhost:8080/get")
+    assert_raises(ArgumentError) { pending.wait(-1) }
+    pending.value
+  end
+end
@@ -0,0 +1,96 @@
+require "test_helper"
+require "socket"
+
+class ProxyTest < Minitest::Test
+  # Forward proxy stand-in recording each request it receives.
+  class RecordingProxy
+    attr_reader :requests
+
+    def initialize
+      @requests = Queue.new
+      @tcp = TCPServer.new("127.0.0.1", 0)
+      @thread = Thread.new { loop { serve(@tcp.accept) } }
+    end
+
+    def url
+      "http://127.0.0.1:#{@tcp.addr[1]}"
+    end
+
+    def stop
+      @thread.kill
+      @tcp.close
+    end
+
+    private
+
+    def serve(socket)
+      request_line = socket.gets("\r\n").chomp
+      headers = {}
+      while (line = socket.gets("\r\n")) && line != "\r\n"
+        name, value = line.chomp.split(": ", 2)
+        headers[name.downcase] = value
+      end
+      @requests << [request_line, headers]
+      status = request_line.start_with?("CONNECT") ? "403 Forbidden" : "200 OK"
+      socket.write("HTTP/1.1 #{status}\r\nContent-Length: 7\r\nConnection: close\r\n\r\nproxied")
+      socket.close
+    rescue IOError, SystemCallError
+      nil
+    end
+  end
+
+  def setup
+    @proxy = RecordingProxy.new
+  end
+
+  def teardown
+    @proxy.stop
+  end
+
+  def test_string_proxy
+    response = Wreq.get("http://example.test/path", proxy: @proxy.url)
+    assert_equal "proxied", response.text
+    request_line, = @proxy.requests.pop
+    assert_equal "GET http://example.test/path HTTP/1.1", request_line
+  end
+
+  def test_basic_auth_given_separately
+    client = Wreq::Client.new(proxy: {url: @proxy.url, username: "user", password: "secret"})
+    assert_equal "proxied", client.get("http://example.test/").text
+    _, headers = @proxy.requests.pop
+    assert_equal "Basic #{["user:secret"].pack("m0")}", headers["proxy-authorization"]
+  end
+
+  def test_connect_headers
+    proxy = {https: @proxy.url, headers: {"X-Session-Id" => "sticky-42"}}
+    assert_raises(Wreq::ProxyConnectionError, Wreq::ConnectionError) do
+      Wreq.get("https://example.test/", proxy: proxy)
+    end
+    request_line, headers = @proxy.requests.pop
+    assert_equal "CONNECT example.test:443 HTTP/1.1", request_line
+    assert_equal "sticky-42", headers["x-session-id"]
+  end
+
+  def test_per_scheme_proxies
+    response = Wreq.get("http://localhost:8080/get", proxy: {https: @proxy.url})
+    assert_equal 200, response.code
+    assert_empty @proxy.requests
+
+    client = Wreq::Client.new(proxy: {http: @proxy.url, https: "http://127.0.0.1:1"})
+    assert_equal "proxied", client.get("http://example.test/").text
+  end
+
+  def test_no_proxy_list
+    client = Wreq::Client.new(proxy: {url: @proxy.url, no_proxy: ["localhost", "example.test"]})
+    assert_equal 200, client.get("http://localhost:8080/get").code
+
+    response = Wreq.get("http://127.0.0.1:8080/get", proxy: {url: @proxy.url, no_proxy: "10.0.0.0/8, 127.0.0.0/8"})
+    assert_equal 200, response.code
+    assert_empty @proxy.requests
+  end
+
+  def test_invalid_proxy
+    assert_raises(ArgumentError) { Wreq::Client.new(proxy: {}) }
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", proxy: {username: "user"}) }
+  end
+end
@@ -0,0 +1,143 @@
+require "test_helper"
+require "cgi"
+require "stringio"
+
+class RedirectTest < Minitest::Test
+  BASE = "http://localhost:8080"
+
+  def redirect_to(url, status: 302)
+    "#{BASE}/redirect-to?url=#{CGI.escape(url)}&status_code=#{status}"
+  end
+
+  def test_history_lists_every_hop
+    response = Wreq.get("#{BASE}/redirect/3", allow_redirects: true)
+    assert_equal 200, response.code
+    assert_equal 3, response.history.length
+    assert_equal [302, 302, 302], response.history.map { |hop| hop.status.as_int }
+    assert_equal "#{BASE}/redirect/3", response.history.first.url
+    assert_equal response.history[1].url, response.history.first.location
+    assert response.history.first.headers.get("Location")
+  end
+
+  def test_history_is_empty_without_redirects
+    response = Wreq.get("#{BASE}/get")
+    assert_empty response.history
+
+    response = Wreq.get("#{BASE}/redirect/1")
+    assert_equal 302, response.code
+    assert_empty response.history
+  end
+
+  def test_too_many_redirects
+    assert_raises(Wreq::RedirectError) do
+      Wreq.get("#{BASE}/redirect/3", allow_redirects: true, max_redirects: 1)
+    end
+    assert_raises(Wreq::RedirectError) do
+      Wreq.get("#{BASE}/redirect/3", redirect: {max: 2})
+    end
+  end
+
+  def test_callback_decides_each_hop
+    seen = []
+    response = Wreq.get("#{BASE}/redirect/3", redirect: ->(hop) {
+      seen << hop.location
+      seen.length < 2
+    })
+    assert_equal 302, response.code
+    assert_equal 2, seen.length
+    assert_equal 1, response.history.length
+  end
+
+  def test_callable_object
+    policy = Object.new
+    def policy.call(hop) = hop.status.as_int == 302
+
+    response = Wreq.get("#{BASE}/redirect/2", redirect: policy)
+    assert_equal 200, response.code
+  end
+
+  def test_same_origin
+    assert_raises(Wreq::RedirectError) do
+      Wreq.get(redirect_to("http://127.0.0.1:8080/get"), redirect: {same_origin: true})
+    end
+    response = Wreq.get(redirect_to("/get"), redirect: {same_origin: true})
+    assert_equal 200, response.code
+  end
+
+  def test_allowed_hosts
+    assert_raises(Wreq::RedirectError) do
+      Wreq.get(redirect_to("http://127.0.0.1:8080/get"), redirect: {allowed_hosts: ["localhost"]})
+    end
+    response = Wreq.get(redirect_to("http://127.0.0.1:8080/get"),
+      redirect: {allowed_hosts: ["127.0.0.1"]})
+    assert_equal 200, response.code
+  end
+
+  def test_strips_credentials_on_cross_host_hops
+    response = Wreq.get(redirect_to("http://127.0.0.1:8080/headers"),
+      allow_redirects: true, bearer_auth: "secret")
+    refute response.json["headers"].key?("Authorization")
+
+    response = Wreq.get(redirect_to("http://127.0.0.1:8080/headers"),
+      redirect: {strip_sensitive_headers: false}, bearer_auth: "secret")
+    assert_equal "Bearer secret", response.json["headers"]["Authorization"]
+  end
+
+  def test_keeps_credentials_on_same_host_hops
+    response = Wreq.get(redirect_to("/headers"), allow_redirects: true, bearer_auth: "secret")
+    assert_equal "Bearer secret", response.json["headers"]["Authorization"]
+  end
+
+  def test_post_switches_to_get_on_302
+    response = Wreq.post(redirect_to("/get"), allow_redirects: true, body: "payload")
+    assert_equal 200, response.code
+  end
+
+  def test_307_replays_method_and_body
+    response = Wreq.post(redirect_to("/post", status: 307), allow_redirects: true, body: "payload")
+    assert_equal 200, response.code
+    assert_equal "payload", response.json["data"]
+    assert_equal 307, response.history.first.status.as_int
+  end
+
+  def test_client_policy_and_request_override
+    client = Wreq::Client.new(redirect: {same_origin: true})
+    assert_raises(Wreq::RedirectError) do
+      client.get(redirect_to("http://127.0.0.1:8080/get"))
+    end
+    response = client.get(redirect_to("http://127.0.0.1:8080/get"), redirect: {})
+    assert_equal 200, response.code
+    response = client.get(redirect_to("/get"), allow_redirects: false)
+    assert_equal 302, response.code
+  end
+
+  def test_callback_exceptions_are_raised_as_is
+    error = Class.new(StandardError)
+    raised = assert_raises(error) do
+      Wreq.get("#{BASE}/redirect/1", redirect: ->(_hop) { raise error, "refused by test" })
+    end
+    assert_equal "refused by test", raised.message
+
+    thrown = catch(:stop) do
+      Wreq.get("#{BASE}/redirect/1", redirect: ->(_hop) { throw :stop, :thrown })
+    end
+    assert_equal :thrown, thrown
+  end
+
+  def test_307_with_stream_body_raises
+    error = assert_raises(Wreq::RedirectError) do
+      Wreq.post(redirect_to("/post", status: 307), allow_redirects: true, body: StringIO.new("payload"))
+    end
+    assert_includes error.message, "cannot be sent again"
+  end
+
+  def test_callback_refused_in_background_requests
+    error = assert_raises(ArgumentError) { Wreq.get_async("#{BASE}/redirect/1", redirect: ->(_hop) { true }) }
+    assert_includes error.message, "background requests"
+
+    client = Wreq::Client.new(redirect: ->(_hop) { true })
+    assert_raises(ArgumentError) { client.get_async("#{BASE}/redirect/1") }
+    assert_equal 302, client.get_async("#{BASE}/redirect/1", allow_redirects: false).value.code
+    assert_equal 200, client.get_async("#{BASE}/redirect/1", redirect: {max: 1}).value.code
+  end
+end
@@ -0,0 +1,78 @@
+require "test_helper"
+require "cgi"
+
+class RequestInfoTest < Minitest::Test
+  BASE = "http://localhost:8080"
+
+  def header_names(request)
+    request.headers.map(&:first)
+  end
+
+  def test_describes_the_sent_request
+    response = Wreq.get("#{BASE}/get", headers: {"X-Trace" => "abc"})
+    request = response.request
+    assert_instance_of Wreq::RequestInfo, request
+    assert_kind_of Wreq::Method, request.method
+    assert_equal "#{BASE}/get", request.url
+    assert_equal "HTTP/1.1", request.version.to_s
+    assert_includes request.headers, ["x-trace", "abc"]
+    assert_includes header_names(request), "host"
+    assert_equal 0, request.body_size
+  end
+
+  def test_is_frozen
+    request = Wreq.get("#{BASE}/get").request
+    assert request.frozen?
+    assert request.headers.frozen?
+  end
+
+  def test_follows_redirects
+    url = "#{BASE}/redirect-to?url=#{CGI.escape("#{BASE}/get")}&status_code=303"
+    response = Wreq.post(url, body: "data", allow_redirects: true)
+    # The 303 switched to a GET without the body.
+    assert_equal "#{BASE}/get", response.request.url
+    assert_equal 0, response.request.body_size
+    refute_includes header_names(response.request), "content-length"
+  end
+
+  def test_includes_client_default_headers
+    client = Wreq::Client.new(headers: {"X-Client" => "1"}, user_agent: "wreq-test")
+    request = client.get("#{BASE}/get", headers: {"X-Request" => "2"}).request
+    assert_includes request.headers, ["x-client", "1"]
+    assert_includes request.headers, ["x-request", "2"]
+    assert_includes request.headers, ["user-agent", "wreq-test"]
+
+    request = client.get("#{BASE}/get", default_headers: false).request
+    refute_includes header_names(request), "x-client"
+  end
+
+  def test_orig_headers_order_and_case
+    response = Wreq.get(
+      "#{BASE}/get",
+      headers: {"x-second" => "2", "x-first" => "1"},
+      orig_headers: ["X-First", "X-Second"]
+    )
+    names = header_names(response.request)
+    assert_equal ["X-First", "X-Second"], names.first(2)
+  end
+
+  def test_includes_emulation_headers
+    emulation = Wreq::Emulation.new(device: Wreq::EmulationDevice::Chrome136)
+    request = Wreq::Client.new(emulation: emulation).get("#{BASE}/get").request
+    user_agent = request.headers.find { |name, _| name.casecmp?("user-agent") }
+    assert_includes user_agent.last, "Chrome/136"
+  end
+
+  def test_includes_headers_added_when_sending
+    client = Wreq::Client.new(cookie_store: true, gzip: true)
+    client.get("#{BASE}/cookies/set?session=abc", allow_redirects: false)
+    request = client.get("#{BASE}/get").request
+    assert_includes request.headers, ["cookie", "session=abc"]
+    assert_includes header_names(request), "accept-encoding"
+  end
+
+  def test_body_size
+    assert_equal 5, Wreq.post("#{BASE}/post", body: "hello").request.body_size
+    assert_equal 2, Wreq.post("#{BASE}/post", json: {}).request.body_size
+  end
+end
@@ -0,0 +1,81 @@
+require "test_helper"
+require "socket"
+
+class ResolveTest < Minitest::Test
+  def setup
+    @servers = []
+    @threads = []
+  end
+
+  def teardown
+    @threads.each(&:kill)
+    @servers.each(&:close)
+  end
+
+  def test_client_resolve_keeps_host_header
+    client = Wreq::Client.new(resolve: {"httpbin.test" => ["127.0.0.1", "::1"]})
+    response = client.get("http://httpbin.test:8080/headers")
+    assert_equal 200, response.code
+    assert_equal "httpbin.test:8080", response.json["headers"]["Host"]
+  end
+
+  def test_request_resolve
+    response = Wreq.get("http://staging.test:8080/get", resolve: {"staging.test:8080" => "127.0.0.1"})
+    assert_equal "http://staging.test:8080/get", response.json["url"]
+  end
+
+  def test_port_specific_entry_only_applies_to_that_port
+    client = Wreq::Client.new(resolve: {"staging.invalid:9999" => "127.0.0.1"})
+    assert_raises(Wreq::ConnectionError) { client.get("http://staging.invalid:8080/get") }
+  end
+
+  def test_request_entries_take_precedence
+    client = Wreq::Client.new(resolve: {"staging.test" => "192.0.2.1"}, connect_timeout: 1)
+    response = client.get("http://staging.test:8080/get", resolve: {"STAGING.test" => "127.0.0.1"})
+    assert_equal 200, response.code
+  end
+
+  def test_invalid_resolve
+    assert_raises(ArgumentError) { Wreq::Client.new(resolve: {"example.com" => "not an ip"}) }
+    assert_raises(ArgumentError) { Wreq::Client.new(resolve: {"example.com" => []}) }
+    assert_raises(TypeError) { Wreq::Client.new(resolve: "example.com") }
+  end
+
+  def test_request_resolve_does_not_reuse_connection
+    port = listen("127.0.0.1")
+    listen("::1", port)
+    client = Wreq::Client.new(resolve: {"pool.test" => "127.0.0.1"})
+    url = "http://pool.test:#{port}/"
+    assert_equal "127.0.0.1", client.get(url).text
+    assert_equal "::1", client.get(url, resolve: {"pool.test" => "::1"}).text
+    assert_equal "127.0.0.1", client.get(url).text
+  end
+
+  private
+
+  # Listen on `host`, answering each request on a kept-alive connection with `host`.
+  def listen(host, port = 0)
+    server = TCPServer.new(host, port)
+    @servers << server
+    @threads << Thread.new do
+      loop { @threads << Thread.new(server.accept) { |socket| answer(socket, host) } }
+    end
+    server.addr[1]
+  end
+
+  def answer(socket, host)
+    while socket.gets("\r\n")
+      length = 0
+      while (line = socket.gets("\r\n")) && line != "\r\n"
+        name, value = line.chomp.split(": ", 2)
+        length = value.to_i if name.casecmp?("content-length")
+      end
+      socket.read(length)
+      socket.write("HTTP/1.1 200 OK\r\nContent-Length: #{host.bytesize}\r\n\r\n#{host}")
+    end
+  rescue IOError, SystemCallError
+    nil
+  ensure
+    socket.close
+  end
+end
@@ -0,0 +1,135 @@
+require "test_helper"
+require "socket"
+
+# Minimal HTTP/1.1 server that answers with scripted responses, one per connection.
+class ScriptedServer
+  attr_reader :bodies
+
+  def initialize(*responses)
+    @responses = responses
+    @bodies = []
+    @server = TCPServer.new("127.0.0.1", 0)
+    @thread = Thread.new do
+      @responses.each do |status, headers|
+        socket = @server.accept
+        length = 0
+        while (line = socket.gets("\r\n")) && line != "\r\n"
+          name, value = line.split(":", 2)
+          length = value.to_i if name.casecmp?("content-length")
+        end
+        @bodies << socket.read(length)
+        head = "HTTP/1.1 #{status} Scripted\r\nContent-Length: 2\r\nConnection: close\r\n"
+        headers.each { |name, value| head << "#{name}: #{value}\r\n" }
+        socket.write(head + "\r\nok")
+        socket.close
+      end
+    end
+  end
+
+  def url
+    "http://127.0.0.1:#{@server.addr[1]}/"
+  end
+
+  def stop
+    @thread.kill
+    @server.close
+  end
+end
+
+class RetryTest < Minitest::Test
+  def test_retries_statuses_until_attempts_exhausted
+    response = Wreq.get("http://localhost:8080/status/503", retry: {attempts: 3, backoff: 0.01})
+    assert_equal 503, response.code
+    assert_equal 3, response.attempts
+  end
+
+  def test_no_retry_by_default
+    response = Wreq.get("http://localhost:8080/status/503")
+    assert_equal 1, response.attempts
+  end
+
+  def test_successful_response_is_not_retried
+    response = Wreq.get("http://localhost:8080/get", retry: true)
+    assert_equal 200, response.code
+    assert_equal 1, response.attempts
+  end
+
+  def test_non_idempotent_methods_are_not_retried_by_default
+    response = Wreq.post("http://localhost:8080/status/503", retry: {attempts: 3, backoff: 0.01})
+    assert_equal 1, response.attempts
+
+    response = Wreq.post(
+      "http://localhost:8080/status/503",
+      retry: {attempts: 3, backoff: 0.01, methods: [Wreq::Method::POST]}
+    )
+    assert_equal 3, response.attempts
+  end
+
+  def test_custom_statuses
+    response = Wreq.get("http://localhost:8080/status/500", retry: {attempts: 2, backoff: 0.01})
+    assert_equal 1, response.attempts
+
+    response = Wreq.get("http://localhost:8080/status/500", retry: {attempts: 2, backoff: 0.01, statuses: [500]})
+    assert_equal 2, response.attempts
+  end
+
+  def test_client_default_and_request_override
+    client = Wreq::Client.new(retry: {attempts: 2, backoff: 0.01})
+    assert_equal 2, client.get("http://localhost:8080/status/502").attempts
+    assert_equal 1, client.get("http://localhost:8080/status/502", retry: false).attempts
+    assert_equal 4, client.get("http://localhost:8080/status/502", retry: {attempts: 4, backoff: 0.01}).attempts
+  end
+
+  def test_retries_errors_with_backoff
+    port = TCPServer.open("127.0.0.1", 0) { |s| s.addr[1] }
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    assert_raises(Wreq::ConnectionError) do
+      Wreq.get("http://127.0.0.1:#{port}/", retry: {attempts: 3, backoff: 0.2, jitter: false})
+    end
+    elapsed = Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+    assert_operator elapsed, :>=, 0.6
+  end
+
+  def test_error_classes
+    port = TCPServer.open("127.0.0.1", 0) { |s| s.addr[1] }
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    assert_raises(Wreq::ConnectionError) do
+      Wreq.get("http://127.0.0.1:#{port}/", retry: {attempts: 3, backoff: 1, errors: [Wreq::TimeoutError]})
+    end
+    assert_operator Process.clock_gettime(Process::CLOCK_MONOTONIC) - started, :<, 1
+  end
+
+  def test_honors_retry_after_and_replays_body
+    server = ScriptedServer.new([503, {"Retry-After" => "1"}], [200, {}])
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    response = Wreq.put(server.url, body: "payload", retry: {backoff: 0.01})
+    elapsed = Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+
+    assert_equal 200, response.code
+    assert_equal 2, response.attempts
+    assert_operator elapsed, :>=, 1
+    assert_equal ["payload", "payload"], server.bodies
+  ensure
+    server&.stop
+  end
+
+  def test_streamed_body_is_not_replayed
+    server = ScriptedServer.new([503, {}], [200, {}])
+    sender = Wreq::BodySender.new(4)
+    sender.push("streamed")
+    sender.close
+    response = Wreq.put(server.url, body: sender, retry: {backoff: 0.01})
+    assert_equal 503, response.code
+    assert_equal 1, response.attempts
+  ensure
+    server&.stop
+  end
+
+  def test_invalid_policy
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", retry: 0) }
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", retry: {backoff: -1}) }
+    error = assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", retry: {max_backoff: -1}) }
+    assert_includes error.message, "max_backoff"
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", retry: {errors: [StandardError]}) }
+  end
+end
@@ -0,0 +1,166 @@
+require "test_helper"
+require "socket"
+require "stringio"
+require "tempfile"
+require "tmpdir"
+require "pathname"
+
+class SseTest < Minitest::Test
+  # Server stand-in answering each connection with the next of the given
+  # responses, and recording the Last-Event-ID header and body of each request.
+  class EventServer
+    attr_reader :last_event_ids, :bodies
+
+    def initialize(*responses)
+      @responses = responses
+      @last_event_ids = []
+      @bodies = []
+      @tcp = TCPServer.new("127.0.0.1", 0)
+      @thread = Thread.new { loop { serve(@tcp.accept) } }
+    end
+
+    def url
+      "http://127.0.0.1:#{@tcp.addr[1]}/events"
+    end
+
+    def stop
+      @thread.kill
+      @tcp.close
+    end
+
+    private
+
+    def serve(socket)
+      last_event_id = nil
+      length = 0
+      while (line = socket.gets("\r\n")) && line != "\r\n"
+        name, value = line.chomp.split(": ", 2)
+        last_event_id = value if name.casecmp?("last-event-id")
+        length = value.to_i if name.casecmp?("content-length")
+      end
+      @last_event_ids << last_event_id
+      @bodies << socket.read(length)
+      status, body, headers = @responses.shift || [204, ""]
+      socket.write("HTTP/1.1 #{status} OK\r\nContent-Type: text/event-stream\r\n#{headers}Connection: close\r\n\r\n#{body}")
+      socket.close
+    rescue IOError, SystemCallError
+      nil
+    end
+  end
+
+  STREAM = <<~SSE.gsub("\n", "\r\n")
+    : a comment
+    data: first line
+    data: second line
+
+    event: update
+    id: 42
+    retry: 10
+    data: {"ok":true}
+
+    data: kept id
+
+  SSE
+
+  def test_parses_events
+    server = EventServer.new([200, STREAM])
+    events = []
+    Wreq.sse(server.url) { |event| events << event }
+
+    assert_equal 3, events.size
+    assert_instance_of Wreq::ServerSentEvent, events[0]
+    assert_equal "message", events[0].event
+    assert_equal "first line\nsecond line", events[0].data
+    assert_nil events[0].id
+
+    assert_equal "update", events[1].event
+    assert_equal '{"ok":true}', events[1].data
+    assert_equal "42", events[1].id
+    assert_equal 10, events[1].retry
+
+    assert_equal "42", events[2].id
+    assert_nil events[2].retry
+  ensure
+    server&.stop
+  end
+
+  def test_reconnects_with_last_event_id
+    server = EventServer.new(
+      [200, "id: 1\ndata: a\n\n"],
+      [200, "retry: 0\nid: 2\ndata: b\n\n"]
+    )
+    data = []
+    Wreq::Client.new.sse(server.url, reconnect: true, reconnect_delay: 0.01) { |event| data << event.data }
+
+    # The third connection got a 204 and stopped the stream.
+    assert_equal %w[a b], data
+    assert_equal [nil, "1", "2"], server.last_event_ids
+  ensure
+    server&.stop
+  end
+
+  def test_reconnects_send_the_body_again
+    server = EventServer.new([200, "data: a\n\n"], [200, "data: b\n\n"])
+    Tempfile.create("sse") do |file|
+      file.write("payload")
+      file.rewind
+      Wreq::Client.new.sse(server.url, reconnect: true, reconnect_delay: 0.01, body: file) {}
+    end
+    assert_equal %w[payload payload payload], server.bodies
+  ensure
+    server&.stop
+  end
+
+  def test_reconnect_refuses_streamed_body
+    error = assert_raises(ArgumentError) do
+      Wreq::Client.new.sse("http://127.0.0.1:1/events", reconnect: true, body: StringIO.new("payload")) {}
+    end
+    assert_includes error.message, "streamed body"
+  end
+
+  def test_request_body_errors_are_not_retried
+    server = EventServer.new
+    Dir.mktmpdir do |dir|
+      missing = Pathname(File.join(dir, "missing.txt"))
+      assert_raises(Wreq::BodyError) do
+        Wreq::Client.new.sse(server.url, reconnect: true, reconnect_delay: 0.01, body: missing) {}
+      end
+    end
+    assert_empty server.last_event_ids
+  ensure
+    server&.stop
+  end
+
+  def test_break_and_enumerator
+    server = EventServer.new([200, STREAM])
+    assert_equal ["first line\nsecond line"], Wreq.sse(server.url).first(1).map(&:data)
+  ensure
+    server&.stop
+  end
+
+  def test_raises_on_error_status
+    server = EventServer.new([503, ""])
+    assert_raises(Wreq::StatusError) { Wreq.sse(server.url, reconnect: true) { |_event| } }
+  ensure
+    server&.stop
+  end
+
+  def test_response_each_event
+    server = EventServer.new([200, "data: x\n\ndata: y\n\n"])
+    response = Wreq.get(server.url)
+    assert_equal %w[x y], response.each_event.map(&:data)
+  ensure
+    server&.stop
+  end
+
+  def test_response_each_event_raises_body_errors
+    # The second chunk is cut short by the connection closing.
+    body = "9\r\ndata: x\n\n\r\n20\r\ndata: partial"
+    server = EventServer.new([200, body, "Transfer-Encoding: chunked\r\n"])
+    events = []
+    assert_raises(Wreq::Error) { Wreq.get(server.url).each_event { |event| events << event.data } }
+    assert_equal %w[x], events
+  ensure
+    server&.stop
+  end
+end
@@ -0,0 +1,96 @@
+require "test_helper"
+require "socket"
+
+class StatusErrorTest < Minitest::Test
+  # Server stand-in answering every request with a 500 and a large body.
+  class FailingServer
+    BODY = "x" * 3000 + "end"
+
+    def initialize
+      @tcp = TCPServer.new("127.0.0.1", 0)
+      @thread = Thread.new { loop { Thread.new(@tcp.accept) { |socket| serve(socket) } } }
+    end
+
+    def url
+      "http://127.0.0.1:#{@tcp.addr[1]}/"
+    end
+
+    def stop
+      @thread.kill
+      @tcp.close
+    end
+
+    private
+
+    def serve(socket)
+      while (line = socket.gets("\r\n")) && line != "\r\n"; end
+      socket.write(
+        "HTTP/1.1 500 Internal Server Error\r\nContent-Length: #{BODY.bytesize}\r\n" \
+        "X-Failure: yes\r\nConnection: close\r\n\r\n#{BODY}"
+      )
+      socket.close
+    rescue IOError, SystemCallError
+      nil
+    end
+  end
+
+  def test_raise_for_status
+    response = Wreq.get("http://localhost:8080/status/404")
+    error = assert_raises(Wreq::StatusError) { response.raise_for_status }
+    assert_equal 404, error.status.as_int
+    assert_equal "http://localhost:8080/status/404", error.url
+    assert_kind_of Wreq::Headers, error.headers
+    assert_includes error.message, "404"
+  end
+
+  def test_raise_for_status_returns_response
+    response = Wreq.get("http://localhost:8080/json")
+    assert_same response, response.raise_for_status
+  end
+
+  def test_body_preview_leaves_body_readable
+    server = FailingServer.new
+    response = Wreq.get(server.url)
+    error = assert_raises(Wreq::StatusError) { response.raise_for_status }
+    assert_equal 1024, error.body.bytesize
+    assert_equal "yes", error.headers.get("X-Failure")
+    assert_equal FailingServer::BODY, response.text
+  ensure
+    server&.stop
+  end
+
+  def test_raise_on_status_option
+    client = Wreq::Client.new(raise_on_status: true)
+    error = assert_raises(Wreq::StatusError) { client.get("http://localhost:8080/status/503") }
+    assert_equal 503, error.status.as_int
+    assert_equal 200, client.get("http://localhost:8080/get").code
+
+    # Turned off per request.
+    assert_equal 503, client.get("http://localhost:8080/status/503", raise_on_status: false).code
+  end
+
+  def test_raise_on_status_codes
+    assert_equal 404, Wreq.get("http://localhost:8080/status/404", raise_on_status: [500]).code
+    assert_raises(Wreq::StatusError) do
+      Wreq.get("http://localhost:8080/status/500", raise_on_status: [500])
+    end
+  end
+
+  def test_raise_on_status_async_and_parallel
+    client = Wreq::Client.new(raise_on_status: true)
+    pending = client.get_async("http://localhost:8080/status/404")
+    assert_raises(Wreq::StatusError) { pending.value }
+
+    results = client.parallel([
+      {url: "http://localhost:8080/status/404"},
+      {url: "http://localhost:8080/get"}
+    ])
+    assert_kind_of Wreq::StatusError, results[0]
+    assert_equal 200, results[1].code
+  end
+
+  def test_invalid_raise_on_status
+    assert_raises(TypeError) { Wreq::Client.new(raise_on_status: "yes") }
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", raise_on_status: [42]) }
+  end
+end
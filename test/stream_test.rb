@@ -17,6 +17,73 @@ class StreamTest < Minitest::Test
     producer.join
   end
 
+  def test_file_body_stream
+    Tempfile.create("wreq-upload") do |file|
+      file.write("file-body-" * 1000)
+      file.flush
+
+      client = Wreq::Client.new
+      [Pathname(file.path), File.open(file.path, "rb")].each do |body|
+        resp = client.post("http://localhost:8080/post", body: body, headers: {"Content-Type" => "text/plain"})
+        assert_equal 200, resp.code
+        json = resp.json
+        assert_equal "10000", json["headers"]["Content-Length"]
+        assert_equal "file-body-" * 1000, json["data"]
+      ensure
+        body.close if body.respond_to?(:close)
+      end
+    end
+  end
+
+  def test_file_body_starts_at_position
+    Tempfile.create("wreq-upload") do |file|
+      file.write("header\nfile-body")
+      file.flush
+
+      File.open(file.path, "rb") do |body|
+        body.gets
+        resp = Wreq.post("http://localhost:8080/post", body: body, headers: {"Content-Type" => "text/plain"})
+        json = resp.json
+        assert_equal "9", json["headers"]["Content-Length"]
+        assert_equal "file-body", json["data"]
+      end
+    end
+  end
+
+  def test_string_like_body
+    body = Object.new
+    def body.to_str = "string-like"
+    json = Wreq.post("http://localhost:8080/post", body: body, headers: {"Content-Type" => "text/plain"}).json
+    assert_equal "string-like", json["data"]
+  end
+
+  def test_io_body_stream
+    client = Wreq::Client.new
+    io = StringIO.new("io-body-" * 100)
+    resp = client.post("http://localhost:8080/post", body: io, headers: {"Content-Type" => "text/plain"})
+    assert_equal 200, resp.code
+    json = resp.json
+    assert_equal "800", json["headers"]["Content-Length"]
+    assert_equal "io-body-" * 100, json["data"]
+  end
+
+  def test_io_body_read_error_fails_request
+    io = Object.new
+    def io.readpartial(_length)
+      raise IOError, "disk gone" if @read
+      @read = true
+      "first chunk"
+    end
+    error = assert_raises(Wreq::Error) do
+      Wreq.post("http://localhost:8080/post", body: io, headers: {"Content-Type" => "text/plain"})
+    end
+    refute_kind_of Wreq::StatusError, error
+  end
+
+  def test_invalid_body_type
+    assert_raises(TypeError) { Wreq.post("http://localhost:8080/post", body: 42) }
+  end
+
   def test_response_body_chunks_stream
     client = Wreq::Client.new
     resp = client.get("http://localhost:8080/stream/5")
@@ -0,0 +1,198 @@
+require "test_helper"
+require "socket"
+
+class TimeoutTest < Minitest::Test
+  # Server stand-in that waits before sending the headers and again before the body.
+  class SlowServer
+    attr_reader :connections
+
+    def initialize(header_delay: 0, body_delay: 0, stalls: nil)
+      @header_delay = header_delay
+      @body_delay = body_delay
+      @stalls = stalls
+      @connections = 0
+      @tcp = TCPServer.new("127.0.0.1", 0)
+      @thread = Thread.new { loop { Thread.new(@tcp.accept) { |socket| serve(socket) } } }
+    end
+
+    def url
+      "http://127.0.0.1:#{@tcp.addr[1]}/"
+    end
+
+    def stop
+      @thread.kill
+      @tcp.close
+    end
+
+    private
+
+    def serve(socket)
+      @connections += 1
+      while (line = socket.gets("\r\n")) && line != "\r\n"; end
+      # Stall the first connections entirely, answer the others.
+      sleep(@stalls && @connections <= @stalls ? 10 : @header_delay)
+      socket.write("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n")
+      socket.flush
+      sleep @body_delay
+      socket.write("ok")
+      socket.close
+    rescue IOError, SystemCallError
+      nil
+    end
+  end
+
+  def with_server(**options)
+    server = SlowServer.new(**options)
+    yield server
+  ensure
+    server&.stop
+  end
+
+  def elapsed
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+    yield
+    Process.clock_gettime(Process::CLOCK_MONOTONIC) - started
+  end
+
+  def test_fractional_timeout
+    error = nil
+    time = elapsed do
+      error = assert_raises(Wreq::TimeoutError) do
+        Wreq.get("http://localhost:8080/delay/3", timeout: 0.5)
+      end
+    end
+    assert_equal :total, error.phase
+    assert_operator time, :<, 2
+  end
+
+  def test_rational_timeout
+    error = assert_raises(Wreq::TimeoutError) do
+      Wreq::Client.new(timeout: 1/2r).get("http://localhost:8080/delay/3")
+    end
+    assert_equal :total, error.phase
+  end
+
+  def test_total_timeout_covers_body
+    with_server(body_delay: 2) do |server|
+      response = Wreq.get(server.url, timeout: 0.5)
+      error = assert_raises(Wreq::TimeoutError) { response.text }
+      assert_equal :total, error.phase
+    end
+  end
+
+  def test_read_timeout
+    with_server(body_delay: 2) do |server|
+      response = Wreq.get(server.url, read_timeout: 0.3)
+      error = assert_raises(Wreq::TimeoutError) { response.text }
+      assert_includes [:read, :total], error.phase
+    end
+  end
+
+  def test_first_byte_timeout
+    with_server(header_delay: 2) do |server|
+      error = nil
+      time = elapsed do
+        error = assert_raises(Wreq::TimeoutError) do
+          Wreq.get(server.url, first_byte_timeout: 0.25)
+        end
+      end
+      assert_equal :first_byte, error.phase
+      assert_operator time, :<, 1.5
+    end
+  end
+
+  def test_first_byte_timeout_excludes_body
+    with_server(body_delay: 0.5) do |server|
+      client = Wreq::Client.new(first_byte_timeout: 0.25)
+      assert_equal "ok", client.get(server.url).text
+    end
+  end
+
+  def test_first_byte_timeout_is_retried
+    with_server(stalls: 1) do |server|
+      response = Wreq.get(
+        server.url,
+        first_byte_timeout: 0.25,
+        retry: {attempts: 2, backoff: 0.01, errors: [Wreq::TimeoutError]}
+      )
+      assert_equal "ok", response.text
+      assert_equal 2, response.attempts
+      assert_equal 2, server.connections
+    end
+  end
+
+  def test_dns_timeout
+    # A DNS-over-HTTPS endpoint that never answers.
+    with_server(header_delay: 5) do |server|
+      client = Wreq::Client.new(dns_resolver: {doh: server.url}, dns_timeout: 0.25)
+      error = assert_raises(Wreq::TimeoutError) { client.get("http://example.test/") }
+      assert_equal :dns, error.phase
+
+      error = assert_raises(Wreq::TimeoutError) do
+        Wreq::Client.new(dns_resolver: {doh: server.url})
+          .get("http://example.test/", dns_timeout: 0.25)
+      end
+      assert_equal :dns, error.phase
+    end
+  end
+
+  def test_connect_timeout
+    # A non-routable address, so the connection attempt hangs.
+    client = Wreq::Client.new(connect_timeout: 0.25)
+    error = assert_raises(Wreq::TimeoutError, Wreq::ConnectionError) do
+      client.get("http://10.255.255.1/")
+    end
+    assert_equal :connect, error.phase if error.is_a?(Wreq::TimeoutError)
+  end
+
+  def test_connect_timeout_covers_tls_handshake
+    # Accepts connections but never answers the ClientHello.
+    tcp = TCPServer.new("127.0.0.1", 0)
+    sockets = []
+    thread = Thread.new { loop { sockets << tcp.accept } }
+    client = Wreq::Client.new(connect_timeout: 0.25)
+    error = nil
+    time = elapsed do
+      error = assert_raises(Wreq::TimeoutError) do
+        client.get("https://127.0.0.1:#{tcp.addr[1]}/")
+      end
+    end
+    assert_equal :connect, error.phase
+    assert_operator time, :<, 1.5
+  ensure
+    thread&.kill
+    sockets&.each(&:close)
+    tcp&.close
+  end
+
+  def test_pool_timeout
+    with_server(header_delay: 2) do |server|
+      client = Wreq::Client.new(pool_max_size: 1)
+      busy = Thread.new { client.get(server.url) }
+      sleep 0.25
+      error = assert_raises(Wreq::TimeoutError) do
+        client.get(server.url, pool_timeout: 0.25)
+      end
+      assert_equal :pool, error.phase
+      busy.join
+    end
+  end
+
+  def test_pool_timeout_excludes_the_server
+    with_server(header_delay: 0.5) do |server|
+      client = Wreq::Client.new(pool_timeout: 0.25)
+      assert_equal "ok", client.get(server.url).text
+    end
+  end
+
+  def test_other_timeouts_have_no_phase
+    assert_nil Wreq::TimeoutError.new("waited too long").phase
+  end
+
+  def test_invalid_timeouts
+    assert_raises(ArgumentError) { Wreq::Client.new(timeout: -1) }
+    assert_raises(ArgumentError) { Wreq.get("http://localhost:8080/get", first_byte_timeout: -0.5) }
+    assert_raises(TypeError) { Wreq::Client.new(dns_timeout: "1") }
+    assert_raises(TypeError) { Wreq.get("http://localhost:8080/get", pool_timeout: "1") }
+  end
+end
@@ -0,0 +1,60 @@
+require "test_helper"
+
+class TimingsTest < Minitest::Test
+  BASE = "http://localhost:8080"
+
+  def test_phases_of_a_new_connection
+    client = Wreq::Client.new
+    response = client.get("#{BASE}/delay/1")
+    timings = response.timings
+    assert_instance_of Wreq::Timings, timings
+    refute timings.reused?
+    assert_operator timings.dns, :>=, 0
+    assert_operator timings.connect, :>, 0
+    assert_operator timings.server, :>=, 1
+    phases = timings.dns + timings.connect + timings.request_written + timings.server
+    assert_operator timings.first_byte, :>=, phases - 0.001
+  end
+
+  def test_request_written_excludes_the_server
+    body = "x" * 1024 * 1024
+    timings = Wreq.post("#{BASE}/delay/1", body: body).timings
+    assert_operator timings.request_written, :>=, 0
+    assert_operator timings.request_written, :<, 1
+    assert_operator timings.server, :>=, 1
+  end
+
+  def test_body_phases_known_once_read
+    response = Wreq.get("#{BASE}/bytes/1024")
+    assert_nil response.timings.body
+    assert_nil response.timings.total
+
+    response.bytes
+    timings = response.timings
+    assert_operator timings.body, :>=, 0
+    assert_in_delta timings.first_byte + timings.body, timings.total, 0.001
+  end
+
+  def test_body_phases_after_streaming
+    response = Wreq.get("#{BASE}/stream/5")
+    response.chunks { |_chunk| }
+    refute_nil response.timings.total
+  end
+
+  def test_reused_connection
+    client = Wreq::Client.new
+    client.get("#{BASE}/get").text
+    timings = client.get("#{BASE}/get").timings
+    assert timings.reused?
+    assert_equal 0.0, timings.dns
+    assert_equal 0.0, timings.connect
+  end
+
+  def test_to_h
+    response = Wreq.get("#{BASE}/get")
+    response.text
+    hash = response.timings.to_h
+    assert_equal %i[dns connect request_written server first_byte body total reused], hash.keys
+    assert_equal response.timings.total, hash[:total]
+  end
+end
@@ -0,0 +1,255 @@
+require "test_helper"
+require "openssl"
+require "pathname"
+require "socket"
+require "tempfile"
+
+# Certificates signed by a throwaway CA, generated once per run.
+module TestPki
+  module_function
+
+  def key
+    @key ||= OpenSSL::PKey::RSA.new(2048)
+  end
+
+  def ca
+    @ca ||= certificate("Wreq Test CA", ca: true)
+  end
+
+  def server
+    @server ||= certificate("localhost", san: "DNS:localhost,IP:127.0.0.1")
+  end
+
+  def client
+    @client ||= certificate("wreq-client")
+  end
+
+  def ca_file
+    @ca_file ||= Tempfile.new(["ca", ".pem"]).tap do |file|
+      file.write(ca.to_pem)
+      file.flush
+    end
+  end
+
+  def certificate(common_name, ca: false, san: nil)
+    cert = OpenSSL::X509::Certificate.new
+    cert.version = 2
+    cert.serial = rand(1 << 64)
+    cert.subject = OpenSSL::X509::Name.parse("/CN=#{common_name}")
+    cert.issuer = ca ? cert.subject : self.ca.subject
+    cert.public_key = key
+    cert.not_before = Time.now - 60
+    cert.not_after = Time.now + 3600
+    extensions = OpenSSL::X509::ExtensionFactory.new
+    extensions.subject_certificate = cert
+    extensions.issuer_certificate = ca ? cert : self.ca
+    cert.add_extension(extensions.create_extension("basicConstraints", "CA:#{ca.to_s.upcase}", true))
+    cert.add_extension(extensions.create_extension("subjectAltName", san)) if san
+    cert.sign(key, OpenSSL::Digest.new("SHA256"))
+    cert
+  end
+end
+
+# Minimal HTTPS server answering every request with the TLS version and client certificate.
+class TlsServer
+  def initialize(max_version: nil, verify_client: false)
+    context = OpenSSL::SSL::SSLContext.new
+    context.cert = TestPki.server
+    context.key = TestPki.key
+    context.max_version = max_version if max_version
+    context.alpn_select_cb = ->(protocols) { "http/1.1" if protocols.include?("http/1.1") }
+    if verify_client
+      context.cert_store = OpenSSL::X509::Store.new.tap { |store| store.add_cert(TestPki.ca) }
+      context.verify_mode = OpenSSL::SSL::VERIFY_PEER | OpenSSL::SSL::VERIFY_FAIL_IF_NO_PEER_CERT
+    end
+    @requests = 0
+    @tcp = TCPServer.new("127.0.0.1", 0)
+    @server = OpenSSL::SSL::SSLServer.new(@tcp, context)
+    @thread = Thread.new { loop { serve } }
+  end
+
+  attr_reader :requests
+
+  def url
+    "https://localhost:#{@tcp.addr[1]}/"
+  end
+
+  def stop
+    @thread.kill
+    @tcp.close
+  end
+
+  private
+
+  def serve
+    socket = @server.accept
+    request_line = socket.gets("\r\n")
+    @requests += 1 if request_line
+    while (line = socket.gets("\r\n")) && line != "\r\n"; end
+    body = "#{socket.ssl_version} #{socket.peer_cert&.subject}"
+    socket.write("HTTP/1.1 200 OK\r\nContent-Length: #{body.bytesize}\r\nConnection: close\r\n\r\n#{body}")
+    socket.close
+  rescue OpenSSL::SSL::SSLError, IOError, SystemCallError
+    nil
+  end
+end
+
+class TlsTest < Minitest::Test
+  def setup
+    @server = TlsServer.new
+  end
+
+  def teardown
+    @server.stop
+  end
+
+  def test_untrusted_ca_is_rejected
+    assert_raises(Wreq::TlsError, Wreq::ConnectionError) do
+      Wreq::Client.new.get(@server.url)
+    end
+  end
+
+  def test_ca_cert_path_and_string
+    [TestPki.ca_file.path, Pathname.new(TestPki.ca_file.path), TestPki.ca.to_pem, [TestPki.ca.to_pem]].each do |ca_cert|
+      response = Wreq::Client.new(ca_cert: ca_cert).get(@server.url)
+      assert_equal 200, response.code
+    end
+  end
+
+  def test_ca_cert_from_ssl_cert_file
+    previous = ENV["SSL_CERT_FILE"]
+    ENV["SSL_CERT_FILE"] = TestPki.ca_file.path
+    response = Wreq::Client.new(ca_cert: :env).get(@server.url)
+    assert_equal 200, response.code
+  ensure
+    ENV["SSL_CERT_FILE"] = previous
+  end
+
+  def test_invalid_ca_cert
+    assert_raises(Wreq::TlsError) { Wreq::Client.new(ca_cert: "/nonexistent/ca.pem") }
+    assert_raises(Wreq::TlsError) { Wreq::Client.new(ca_cert: "-----BEGIN CERTIFICATE-----\nnope\n") }
+  end
+
+  def test_tls_version_bounds
+    response = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, max_tls_version: "1.2").get(@server.url)
+    assert_match(/\ATLSv1\.2 /, response.text)
+
+    server = TlsServer.new(max_version: OpenSSL::SSL::TLS1_2_VERSION)
+    assert_raises(Wreq::TlsError, Wreq::ConnectionError) do
+      Wreq::Client.new(ca_cert: TestPki.ca.to_pem, min_tls_version: :tls1_3).get(server.url)
+    end
+  ensure
+    server&.stop
+  end
+
+  def test_invalid_tls_version
+    assert_raises(ArgumentError) { Wreq::Client.new(min_tls_version: "2.0") }
+  end
+
+  def test_client_cert_and_key
+    server = TlsServer.new(verify_client: true)
+    response = Wreq::Client.new(
+      ca_cert: TestPki.ca.to_pem,
+      client_cert: TestPki.client.to_pem,
+      client_key: TestPki.key.private_to_pem
+    ).get(server.url)
+    assert_includes response.text, "CN=wreq-client"
+  ensure
+    server&.stop
+  end
+
+  def test_pkcs12_identity
+    server = TlsServer.new(verify_client: true)
+    archive = OpenSSL::PKCS12.create("secret", "client", TestPki.key, TestPki.client)
+    response = Wreq::Client.new(
+      ca_cert: TestPki.ca.to_pem,
+      identity: archive.to_der,
+      identity_password: "secret"
+    ).get(server.url)
+    assert_includes response.text, "CN=wreq-client"
+  ensure
+    server&.stop
+  end
+
+  def test_identity_options_conflict
+    assert_raises(ArgumentError) { Wreq::Client.new(client_cert: TestPki.client.to_pem) }
+  end
+
+  def test_matching_pin
+    hash = OpenSSL::Digest::SHA256.digest(TestPki.key.public_to_der)
+    ["sha256/#{[hash].pack("m0")}", [hash].pack("m0"), hash.unpack1("H*")].each do |pin|
+      response = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, pin_sha256: [pin]).get(@server.url)
+      assert_equal 200, response.code
+    end
+  end
+
+  def test_pin_mismatch
+    expected = "sha256/#{[OpenSSL::Random.random_bytes(32)].pack("m0")}"
+    actual = "sha256/#{[OpenSSL::Digest::SHA256.digest(TestPki.key.public_to_der)].pack("m0")}"
+    client = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, pin_sha256: expected)
+    error = assert_raises(Wreq::TlsError) { client.get(@server.url) }
+    assert_includes error.message, expected
+    assert_includes error.message, actual
+  end
+
+  def test_pin_mismatch_on_pooled_connection
+    pin = "sha256/#{[OpenSSL::Random.random_bytes(32)].pack("m0")}"
+    client = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, pin_sha256: pin)
+    2.times { assert_raises(Wreq::TlsError) { client.get(@server.url) } }
+  end
+
+  def test_pin_mismatch_fails_websocket_handshake
+    expected = "sha256/#{[OpenSSL::Random.random_bytes(32)].pack("m0")}"
+    client = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, pin_sha256: expected)
+    assert_raises(Wreq::TlsError) { client.websocket(@server.url.sub("https", "wss")) }
+  end
+
+  def test_pins_do_not_record_tls_info
+    hash = OpenSSL::Digest::SHA256.digest(TestPki.key.public_to_der)
+    client = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, pin_sha256: [hash].pack("m0"))
+    assert_nil client.get(@server.url).tls_info
+  end
+
+  def test_tls_info
+    response = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, tls_info: true).get(@server.url)
+    info = response.tls_info
+    assert_equal "localhost", info.sni
+    assert_equal "http/1.1", info.alpn
+
+    leaf = info.peer_certificate
+    assert_equal TestPki.server.to_der, leaf.der
+    assert_equal TestPki.server.to_pem, leaf.to_pem
+    assert_includes leaf.subject, "CN=localhost"
+    assert_includes leaf.issuer, "CN=Wreq Test CA"
+    assert_equal ["localhost", "127.0.0.1"], leaf.subject_alt_names
+    assert_equal TestPki.server.not_after.to_i, leaf.not_after.to_i
+    assert_operator leaf.not_before, :<, Time.now
+    assert_equal leaf.der, info.peer_certificates.first.der
+  end
+
+  def test_tls_info_on_pooled_connection
+    client = Wreq::Client.new(ca_cert: TestPki.ca.to_pem, tls_info: true)
+    2.times do
+      info = client.get(@server.url).tls_info
+      assert_equal "localhost", info.sni
+      assert_equal TestPki.server.to_der, info.peer_certificate.der
+    end
+  end
+
+  def test_connect_timing_includes_tls_handshake
+    timings = Wreq::Client.new(ca_cert: TestPki.ca.to_pem).get(@server.url).timings
+    refute timings.reused?
+    assert_operator timings.connect, :>, 0
+    assert_operator timings.first_byte, :>=, timings.dns + timings.connect
+  end
+
+  def test_tls_info_not_recorded_by_default
+    assert_nil Wreq::Client.new(ca_cert: TestPki.ca.to_pem).get(@server.url).tls_info
+    assert_nil Wreq::Client.new(tls_info: true).get("http://localhost:8080/get").tls_info
+  end
+
+  def test_invalid_pin
+    assert_raises(ArgumentError) { Wreq::Client.new(pin_sha256: "not a hash") }
+    assert_raises(ArgumentError) { Wreq::Client.new(pin_sha256: []) }
+  end
+end
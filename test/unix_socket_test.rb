@@ -0,0 +1,66 @@
+require "test_helper"
+require "socket"
+require "tmpdir"
+
+class UnixSocketTest < Minitest::Test
+  def setup
+    @dir = Dir.mktmpdir
+    @path = File.join(@dir, "wreq.sock")
+    @server = UNIXServer.new(@path)
+    @thread = Thread.new { loop { serve(@server.accept) } }
+  end
+
+  def teardown
+    @thread.kill
+    @server.close
+    FileUtils.remove_entry(@dir)
+  end
+
+  def test_client_unix_socket
+    client = Wreq::Client.new(unix_socket: @path)
+    response = client.get("http://docker/v1.43/info?all=1")
+    assert_equal 200, response.code
+    assert_equal "GET /v1.43/info?all=1 host=docker", response.text
+  end
+
+  def test_request_unix_socket
+    response = Wreq.post("http://sidecar.local:9000/events", unix_socket: @path, body: "{}")
+    assert_equal "POST /events host=sidecar.local:9000", response.text
+  end
+
+  def test_request_unix_socket_does_not_reuse_tcp_connection
+    client = Wreq::Client.new
+    assert_equal 200, client.get("http://localhost:8080/get").code
+    response = client.get("http://localhost:8080/get", unix_socket: @path)
+    assert_equal "GET /get host=localhost:8080", response.text
+  end
+
+  def test_unix_socket_takes_place_of_proxy
+    client = Wreq::Client.new(unix_socket: @path, proxy: "http://127.0.0.1:9")
+    assert_equal "GET /info host=docker", client.get("http://docker/info").text
+  end
+
+  def test_missing_socket
+    assert_raises(Wreq::ConnectionError) do
+      Wreq.get("http://docker/_ping", unix_socket: File.join(@dir, "missing.sock"))
+    end
+  end
+
+  private
+
+  def serve(socket)
+    request_line = socket.gets("\r\n").chomp
+    headers = {}
+    while (line = socket.gets("\r\n")) && line != "\r\n"
+      name, value = line.chomp.split(": ", 2)
+      headers[name.downcase] = value
+    end
+    socket.read(headers["content-length"].to_i) if headers["content-length"]
+    method, target = request_line.split
+    body = "#{method} #{target} host=#{headers["host"]}"
+    socket.write("HTTP/1.1 200 OK\r\nContent-Length: #{body.bytesize}\r\nConnection: close\r\n\r\n#{body}")
+    socket.close
+  rescue IOError, SystemCallError
+    nil
+  end
+end
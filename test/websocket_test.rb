@@ -0,0 +1,190 @@
+require "test_helper"
+require "digest/sha1"
+require "socket"
+
+# Minimal in-process WebSocket echo server (RFC 6455, unfragmented frames only).
+class EchoWebSocketServer
+  GUID = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11"
+
+  attr_reader :port
+
+  def initialize
+    @server = TCPServer.new("127.0.0.1", 0)
+    @port = @server.addr[1]
+    @thread = Thread.new { loop { handle(@server.accept) } }
+  end
+
+  def url
+    "ws://127.0.0.1:#{port}/echo"
+  end
+
+  def stop
+    @thread.kill
+    @server.close
+  end
+
+  private
+
+  def handle(socket)
+    Thread.new do
+      headers = {}
+      while (line = socket.gets("\r\n")) && line != "\r\n"
+        name, value = line.split(":", 2)
+        headers[name.downcase] = value.strip if value
+      end
+      accept = [Digest::SHA1.digest(headers["sec-websocket-key"] + GUID)].pack("m0")
+      response = +"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n"
+      response << "Sec-WebSocket-Accept: #{accept}\r\n"
+      if (protocols = headers["sec-websocket-protocol"])
+        response << "Sec-WebSocket-Protocol: #{protocols.split(",").first.strip}\r\n"
+      end
+      socket.write(response << "\r\n")
+
+      loop do
+        opcode, payload = read_frame(socket)
+        case opcode
+        # A reserved opcode, which the client must treat as a protocol error.
+        when 0x1 then write_frame(socket, (payload == "invalid") ? 0x3 : opcode, payload)
+        when 0x2 then write_frame(socket, opcode, payload)
+        when 0x9 then write_frame(socket, 0xA, payload)
+        when 0x8
+          write_frame(socket, 0x8, payload)
+          break
+        end
+      end
+    rescue IOError, SystemCallError
+      nil
+    ensure
+      socket.close
+    end
+  end
+
+  def read_frame(socket)
+    first, second = socket.read(2).bytes
+    length = second & 0x7f
+    length = socket.read(2).unpack1("n") if length == 126
+    length = socket.read(8).unpack1("Q>") if length == 127
+    mask = socket.read(4).bytes
+    payload = socket.read(length).bytes.each_with_index.map { |b, i| b ^ mask[i % 4] }.pack("C*")
+    [first & 0x0f, payload]
+  end
+
+  def write_frame(socket, opcode, payload)
+    header = [0x80 | opcode].pack("C")
+    header << if payload.bytesize < 126
+      [payload.bytesize].pack("C")
+    elsif payload.bytesize < 65536
+      [126, payload.bytesize].pack("Cn")
+    else
+      [127, payload.bytesize].pack("CQ>")
+    end
+    socket.write(header + payload.b)
+  end
+end
+
+class WebSocketTest < Minitest::Test
+  def setup
+    @server = EchoWebSocketServer.new
+    @client = Wreq::Client.new
+  end
+
+  def teardown
+    @server.stop
+  end
+
+  def test_handshake
+    ws = @client.websocket(@server.url, protocols: ["chat", "superchat"])
+    assert_instance_of Wreq::WebSocket, ws
+    assert_equal 101, ws.status.as_int
+    assert_equal "chat", ws.protocol
+    ws.close
+  end
+
+  def test_send_and_recv_text
+    ws = @client.websocket(@server.url)
+    ws.send_text("hello")
+    message = ws.recv
+    assert message.text?
+    assert_equal :text, message.kind
+    assert_equal "hello", message.text
+    ws.close
+  end
+
+  def test_send_and_recv_binary
+    ws = Wreq.websocket(@server.url)
+    data = "\x00\xFFbinary".b
+    ws.send_binary(data)
+    message = ws.recv
+    assert message.binary?
+    assert_equal data, message.data
+    ws.close
+  end
+
+  def test_ping
+    ws = @client.websocket(@server.url)
+    ws.ping("are you there")
+    ws.send_text("after ping")
+    messages = []
+    while (message = ws.recv(timeout: 5))
+      messages << message
+      break if message.text?
+    end
+    assert_equal "after ping", messages.last.text
+    ws.close
+  end
+
+  def test_close
+    ws = @client.websocket(@server.url)
+    ws.close(1000, "bye")
+    message = ws.recv(timeout: 5)
+    if message
+      assert message.close?
+      assert_equal 1000, message.close_code
+      assert_equal "bye", message.close_reason
+    end
+    assert_raises(Wreq::ConnectionError) { ws.send_text("too late") }
+  end
+
+  def test_each
+    ws = @client.websocket(@server.url)
+    3.times { |i| ws.send_text("message-#{i}") }
+    texts = []
+    ws.each do |message|
+      texts << message.text
+      break if texts.size == 3
+    end
+    assert_equal ["message-0", "message-1", "message-2"], texts
+    ws.close
+  end
+
+  def test_recv_timeout
+    ws = @client.websocket(@server.url)
+    assert_raises(Wreq::TimeoutError) { ws.recv(timeout: 0.2) }
+    ws.close
+  end
+
+  def test_recv_invalid_timeout
+    ws = @client.websocket(@server.url)
+    assert_raises(ArgumentError) { ws.recv(timeout: -1) }
+    assert_raises(ArgumentError) { ws.recv(timeout: Float::NAN) }
+    ws.close
+  end
+
+  def test_each_raises_receive_errors
+    ws = @client.websocket(@server.url)
+    ws.send_text("valid")
+    ws.send_text("invalid")
+    texts = []
+    assert_raises(Wreq::Error) { ws.each { |message| texts << message.text } }
+    assert_equal ["valid"], texts
+  end
+
+  def test_recv_is_interruptible
+    ws = @client.websocket(@server.url)
+    thread = Thread.new { ws.recv }
+    sleep 0.5
+    thread.kill
+    assert thread.join(5), "recv should be interruptible"
+    ws.close
+  end
+end